@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS play_sessions (
+    id INTEGER PRIMARY KEY NOT NULL,
+    profile_id INTEGER NULL,
+    engine_id INTEGER NOT NULL,
+    iwad_id INTEGER NOT NULL,
+    map_id INTEGER NULL,
+    map_id2 INTEGER NULL,
+    map_id3 INTEGER NULL,
+    map_id4 INTEGER NULL,
+    map_id5 INTEGER NULL,
+    date_started DATETIME NOT NULL,
+    date_ended DATETIME NOT NULL,
+    duration INTEGER NOT NULL,
+    exit_code INTEGER NULL,
+    FOREIGN KEY (profile_id) REFERENCES profiles (id) ON DELETE SET NULL
+);
+
+ALTER TABLE app_settings ADD COLUMN wait_for_engine BOOLEAN DEFAULT false NOT NULL;
@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS demos (
+    id INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL UNIQUE,
+    profile_id INTEGER NULL,
+    engine_id INTEGER NOT NULL,
+    iwad_id INTEGER NOT NULL,
+    map_id INTEGER NULL,
+    map_id2 INTEGER NULL,
+    map_id3 INTEGER NULL,
+    map_id4 INTEGER NULL,
+    map_id5 INTEGER NULL,
+    warp TEXT NULL,
+    skill INTEGER NULL,
+    date_recorded DATETIME NOT NULL,
+    FOREIGN KEY (profile_id) REFERENCES profiles (id) ON DELETE SET NULL
+);
+
+ALTER TABLE play_settings ADD COLUMN record_demo BOOLEAN DEFAULT false NOT NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE profiles ADD COLUMN comp_level TEXT NULL;
+
+ALTER TABLE demos ADD COLUMN comp_level TEXT NULL;
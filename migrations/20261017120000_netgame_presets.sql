@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS netgame_presets (
+    id INTEGER PRIMARY KEY NOT NULL,
+    profile_id INTEGER NOT NULL UNIQUE,
+    game_mode TEXT NOT NULL DEFAULT 'Coop',
+    players INTEGER NOT NULL DEFAULT 2,
+    address TEXT NULL,
+    port INTEGER NULL,
+    time_limit INTEGER NULL,
+    FOREIGN KEY (profile_id) REFERENCES profiles (id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+ALTER TABLE profiles ADD COLUMN environment TEXT NULL;
+ALTER TABLE profiles ADD COLUMN working_directory TEXT NULL;
+ALTER TABLE profiles ADD COLUMN wrapper_command TEXT NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE app_settings ADD COLUMN pre_launch_hook TEXT NULL;
+ALTER TABLE app_settings ADD COLUMN post_exit_hook TEXT NULL;
+ALTER TABLE app_settings ADD COLUMN post_download_hook TEXT NULL;
+
+ALTER TABLE profiles ADD COLUMN pre_launch_hook TEXT NULL;
+ALTER TABLE profiles ADD COLUMN post_exit_hook TEXT NULL;
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS map_levels (
+    id INTEGER PRIMARY KEY NOT NULL,
+    map_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    lump_name TEXT NOT NULL,
+    level_format TEXT NOT NULL,
+    FOREIGN KEY (map_id) REFERENCES maps (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS map_lumps (
+    id INTEGER PRIMARY KEY NOT NULL,
+    map_id INTEGER NOT NULL,
+    lump_name TEXT NOT NULL,
+    UNIQUE (map_id, lump_name),
+    FOREIGN KEY (map_id) REFERENCES maps (id) ON DELETE CASCADE
+);
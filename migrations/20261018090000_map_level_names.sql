@@ -0,0 +1 @@
+ALTER TABLE map_levels ADD COLUMN level_name TEXT NULL;
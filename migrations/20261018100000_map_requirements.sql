@@ -0,0 +1,2 @@
+ALTER TABLE maps ADD COLUMN required_iwad TEXT NULL;
+ALTER TABLE maps ADD COLUMN requires_zdoom BOOLEAN DEFAULT false NOT NULL;
@@ -0,0 +1 @@
+ALTER TABLE iwads ADD COLUMN release TEXT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE iwads ADD COLUMN name TEXT NULL;
+ALTER TABLE iwads ADD COLUMN game_family TEXT NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE engines ADD COLUMN sha1 TEXT NULL;
+ALTER TABLE engines ADD COLUMN md5 TEXT NULL;
+ALTER TABLE iwads ADD COLUMN sha1 TEXT NULL;
+ALTER TABLE iwads ADD COLUMN md5 TEXT NULL;
+ALTER TABLE maps ADD COLUMN sha1 TEXT NULL;
+ALTER TABLE maps ADD COLUMN md5 TEXT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE maps ADD COLUMN comp_level TEXT NULL;
+ALTER TABLE maps ADD COLUMN comp_level_confidence TEXT NULL;
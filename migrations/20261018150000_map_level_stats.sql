@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS map_level_stats (
+    id INTEGER PRIMARY KEY NOT NULL,
+    map_id INTEGER NOT NULL,
+    lump_name TEXT NOT NULL,
+    skill_tier TEXT NOT NULL,
+    monsters INTEGER NOT NULL,
+    monster_hit_points INTEGER NOT NULL,
+    items INTEGER NOT NULL,
+    weapons INTEGER NOT NULL,
+    ammo INTEGER NOT NULL,
+    keys INTEGER NOT NULL,
+    secrets INTEGER NOT NULL,
+    FOREIGN KEY (map_id) REFERENCES maps (id) ON DELETE CASCADE
+);
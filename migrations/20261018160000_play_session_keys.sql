@@ -0,0 +1,40 @@
+-- SQLite can't add foreign keys to a table, so play_sessions is rebuilt with them
+CREATE TABLE play_sessions_new (
+    id INTEGER PRIMARY KEY NOT NULL,
+    profile_id INTEGER NULL,
+    engine_id INTEGER NOT NULL,
+    iwad_id INTEGER NOT NULL,
+    map_id INTEGER NULL,
+    map_id2 INTEGER NULL,
+    map_id3 INTEGER NULL,
+    map_id4 INTEGER NULL,
+    map_id5 INTEGER NULL,
+    date_started DATETIME NOT NULL,
+    date_ended DATETIME NOT NULL,
+    duration INTEGER NOT NULL,
+    exit_code INTEGER NULL,
+    FOREIGN KEY (profile_id) REFERENCES profiles (id) ON DELETE SET NULL,
+    FOREIGN KEY (engine_id) REFERENCES engines (id) ON DELETE CASCADE,
+    FOREIGN KEY (iwad_id) REFERENCES iwads (id) ON DELETE CASCADE,
+    FOREIGN KEY (map_id) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id2) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id3) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id4) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id5) REFERENCES maps (id) ON DELETE SET NULL
+);
+
+-- Sessions for Engines, IWADs and Maps that have since been removed have nothing to point at
+INSERT INTO play_sessions_new
+SELECT ps.id, ps.profile_id, ps.engine_id, ps.iwad_id,
+    (SELECT id FROM maps WHERE id = ps.map_id),
+    (SELECT id FROM maps WHERE id = ps.map_id2),
+    (SELECT id FROM maps WHERE id = ps.map_id3),
+    (SELECT id FROM maps WHERE id = ps.map_id4),
+    (SELECT id FROM maps WHERE id = ps.map_id5),
+    ps.date_started, ps.date_ended, ps.duration, ps.exit_code
+FROM play_sessions ps
+WHERE ps.engine_id IN (SELECT id FROM engines)
+    AND ps.iwad_id IN (SELECT id FROM iwads);
+
+DROP TABLE play_sessions;
+ALTER TABLE play_sessions_new RENAME TO play_sessions;
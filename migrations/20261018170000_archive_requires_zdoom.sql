@@ -0,0 +1,2 @@
+-- PK3 and PKE Maps only run in ZDoom family Engines, whatever lumps they hold
+UPDATE maps SET requires_zdoom = 1 WHERE lower(path) LIKE '%.pk3' OR lower(path) LIKE '%.pke';
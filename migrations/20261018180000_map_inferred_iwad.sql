@@ -0,0 +1,4 @@
+-- GAMEINFO's IWAD stays in required_iwad, the one worked out from a readme or the levels goes here
+ALTER TABLE maps ADD COLUMN inferred_iwad TEXT NULL;
+-- Existing Maps start at 0 so they are read once more on the next scan
+ALTER TABLE maps ADD COLUMN contents_version INTEGER DEFAULT 0 NOT NULL;
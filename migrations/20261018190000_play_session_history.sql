@@ -0,0 +1,32 @@
+-- Removing an Engine or IWAD keeps its play sessions, and the time played, like Profiles and Maps
+CREATE TABLE play_sessions_new (
+    id INTEGER PRIMARY KEY NOT NULL,
+    profile_id INTEGER NULL,
+    engine_id INTEGER NULL,
+    iwad_id INTEGER NULL,
+    map_id INTEGER NULL,
+    map_id2 INTEGER NULL,
+    map_id3 INTEGER NULL,
+    map_id4 INTEGER NULL,
+    map_id5 INTEGER NULL,
+    date_started DATETIME NOT NULL,
+    date_ended DATETIME NOT NULL,
+    duration INTEGER NOT NULL,
+    exit_code INTEGER NULL,
+    FOREIGN KEY (profile_id) REFERENCES profiles (id) ON DELETE SET NULL,
+    FOREIGN KEY (engine_id) REFERENCES engines (id) ON DELETE SET NULL,
+    FOREIGN KEY (iwad_id) REFERENCES iwads (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id2) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id3) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id4) REFERENCES maps (id) ON DELETE SET NULL,
+    FOREIGN KEY (map_id5) REFERENCES maps (id) ON DELETE SET NULL
+);
+
+INSERT INTO play_sessions_new
+SELECT id, profile_id, engine_id, iwad_id, map_id, map_id2, map_id3, map_id4, map_id5,
+    date_started, date_ended, duration, exit_code
+FROM play_sessions;
+
+DROP TABLE play_sessions;
+ALTER TABLE play_sessions_new RENAME TO play_sessions;
@@ -0,0 +1,179 @@
+use std::{
+    io::{Cursor, Read, Seek},
+    path::Path,
+    str::FromStr,
+};
+
+use eyre::Context;
+use zip::ZipArchive;
+
+use crate::{
+    complevel, data,
+    doom_data::{self, KeyLump},
+    files, mapinfo, paths, required_iwad, wad,
+};
+
+// PK3 and PKE files are zip archives, with lumps as files and levels as WADs in the maps
+// folder. The layout is described at https://zdoom.org/wiki/Using_ZIPs_as_WAD_replacement
+
+const MAPS_FOLDER: &str = "maps";
+
+pub fn is_valid_archive(archive_path: &str) -> bool {
+    paths::open_file(archive_path)
+        .ok()
+        .is_some_and(|reader| ZipArchive::new(reader).is_ok())
+}
+
+pub fn read_archive_contents(archive_path: &str) -> Result<data::MapContents, eyre::Report> {
+    let reader =
+        paths::open_file(archive_path).wrap_err(format!("Failed to open '{}'", archive_path))?;
+    let mut archive =
+        ZipArchive::new(reader).wrap_err(format!("Failed to read archive '{}'", archive_path))?;
+    read_archive(&mut archive).wrap_err(format!("Failed to read archive '{}'", archive_path))
+}
+
+fn read_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<data::MapContents, eyre::Report> {
+    let mut map_contents = data::MapContents {
+        is_archive: true,
+        ..Default::default()
+    };
+    let mut key_lump_texts: Vec<(KeyLump, String)> = Vec::new();
+    let mut text_files: Vec<String> = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if file.is_dir() {
+            continue;
+        }
+
+        let file_path = file.name().to_string();
+        let (folder, file_name) = file_path.rsplit_once('/').unwrap_or(("", &file_path));
+        let stem = Path::new(file_name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        let extension = Path::new(file_name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if folder.eq_ignore_ascii_case(MAPS_FOLDER) && extension == doom_data::EXT_WAD {
+            // The level takes its name from the file, whatever the marker inside says
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            let wad_directory = wad::read_directory(&mut Cursor::new(bytes))
+                .wrap_err(format!("Failed to read WAD directory from '{}'", file_path))?;
+            if let Some(level) = wad_directory.get_levels().into_iter().next() {
+                map_contents.levels.push(data::MapLevel {
+                    lump_name: stem,
+                    level_format: level.level_format,
+                    level_name: None,
+                });
+            }
+        } else if folder.is_empty() {
+            // Lumps in the root folder are loaded as if they were in a WAD
+            if let Ok(key_lump) = KeyLump::from_str(&stem) {
+                if !map_contents.lumps.contains(&key_lump) {
+                    map_contents.lumps.push(key_lump.clone());
+                }
+                if mapinfo::LEVEL_NAME_LUMPS.contains(&key_lump)
+                    || complevel::COMP_LEVEL_LUMPS.contains(&key_lump)
+                    || key_lump == KeyLump::Gameinfo
+                {
+                    key_lump_texts.push((key_lump, read_text(&mut file)?));
+                }
+            } else if extension == doom_data::EXT_TXT {
+                text_files.push(read_text(&mut file)?);
+            }
+        }
+    }
+
+    map_contents
+        .levels
+        .sort_by(|a, b| a.lump_name.cmp(&b.lump_name));
+    for key_lump in &mapinfo::LEVEL_NAME_LUMPS {
+        for (_, text) in key_lump_texts.iter().filter(|(lump, _)| lump == key_lump) {
+            mapinfo::set_level_names(&mut map_contents.levels, key_lump, text);
+        }
+    }
+    map_contents.required_iwad = key_lump_texts
+        .iter()
+        .filter(|(lump, _)| *lump == KeyLump::Gameinfo)
+        .find_map(|(_, text)| mapinfo::get_gameinfo_iwad(text));
+    map_contents.comp_level_evidence = key_lump_texts
+        .iter()
+        .filter_map(|(lump, text)| complevel::get_lump_evidence(lump, text))
+        .collect();
+
+    // An archive usually carries its own readme, which may have a title
+    map_contents.title = text_files.iter().find_map(|text| {
+        text.lines()
+            .find_map(|line| files::check_readme_line(line, "title"))
+            .filter(|title| !title.is_empty())
+    });
+    map_contents.comp_level_evidence.extend(
+        text_files
+            .iter()
+            .find_map(|text| complevel::get_readme_evidence(text)),
+    );
+    map_contents.readme_game = text_files
+        .iter()
+        .find_map(|text| required_iwad::get_readme_game(text));
+
+    Ok(map_contents)
+}
+
+fn read_text<R: Read>(file: &mut R) -> Result<String, eyre::Report> {
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archive::{is_valid_archive, read_archive_contents},
+        doom_data::{KeyLump, LevelFormat},
+    };
+
+    #[test]
+    fn test_read_archive_contents() {
+        // Act
+        let actual = read_archive_contents("./test-data/levels.pk3").unwrap();
+
+        // Assert
+        let levels: Vec<(&str, LevelFormat, Option<&str>)> = actual
+            .levels
+            .iter()
+            .map(|level| {
+                (
+                    level.lump_name.as_str(),
+                    level.level_format.clone(),
+                    level.level_name.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                ("MAP01", LevelFormat::Udmf, Some("Outpost")),
+                ("MAP02", LevelFormat::Doom, None),
+            ]
+        );
+        assert_eq!(
+            actual.lumps,
+            vec![KeyLump::Zmapinfo, KeyLump::Gameinfo, KeyLump::Zscript]
+        );
+        assert_eq!(actual.required_iwad, Some("doom2.wad".to_string()));
+        assert_eq!(actual.title, Some("Outpost Omega".to_string()));
+        assert!(actual.requires_zdoom());
+    }
+
+    #[test]
+    fn test_is_valid_archive() {
+        assert!(is_valid_archive("./test-data/levels.pk3"));
+        assert!(!is_valid_archive("./test-data/levels.wad"));
+    }
+}
@@ -1,9 +1,10 @@
 use clap::{Parser, ValueEnum};
+use eyre::Context;
 use log::debug;
 
 use crate::{
-    constants, data, menu_app_settings, menu_editor, menu_main, menu_play_settings, menu_profiles,
-    menu_queues, paths,
+    constants, data, doom_data, menu_app_settings, menu_demos, menu_editor, menu_main, menu_maps,
+    menu_play_settings, menu_profiles, menu_queues, paths,
     tui::{self, MenuCommand},
 };
 
@@ -30,15 +31,47 @@ pub struct Args {
 pub enum Action {
     /// Play Doom with the Default Profile
     #[clap(short_flag = 'p')]
-    Play,
+    Play {
+        /// Print the Engine command instead of running it
+        #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "shell")]
+        dry_run: Option<data::DryRunFormat>,
+    },
 
     /// Play Doom with the Last Run Profile
-    PlayLast,
+    PlayLast {
+        /// Print the Engine command instead of running it
+        #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "shell")]
+        dry_run: Option<data::DryRunFormat>,
+    },
 
     /// Play Doom with the specified Profile
     PlayProfile {
         /// Profile name
         profile_name: String,
+        /// Host a netgame for this many players. Uses the Netgame Preset players when no number is given
+        #[arg(long, num_args = 0..=1, default_missing_value = "0", conflicts_with = "join")]
+        host: Option<u8>,
+        /// Join a netgame. Uses the Netgame Preset address when no address is given
+        #[arg(long, num_args = 0..=1, default_missing_value = "")]
+        join: Option<String>,
+        /// Warp to a level in the Profile's IWAD or Maps, such as 'MAP07', 'E2M3' or '2 3'
+        #[arg(long)]
+        warp: Option<String>,
+        /// Print the Engine command instead of running it
+        #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "shell")]
+        dry_run: Option<data::DryRunFormat>,
+    },
+
+    /// Play back a recorded Demo
+    PlayDemo {
+        /// Demo name
+        demo_name: String,
+        /// Run as a Time Demo, to benchmark the Engine
+        #[arg(long, default_value = "false")]
+        timedemo: bool,
+        /// Print the Engine command instead of running it
+        #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "shell")]
+        dry_run: Option<data::DryRunFormat>,
     },
 
     /// Open the Editor with the Default Profile. Takes the first Map in Profile
@@ -47,6 +80,38 @@ pub enum Action {
     /// Open the Editor with the Last Run Profile. Takes the first Map in Profile
     EditorLast,
 
+    /// Show the Engine output from the most recent launch
+    LastLaunchLog,
+
+    /// Import a folder of Demos, such as zips downloaded from DSDA, into matching Profiles
+    ImportDemos {
+        /// Folder to search for .lmp and .zip files
+        folder: String,
+    },
+
+    /// Draw a top-down overview of a level in a Map, showing secrets, exits, keys and monsters
+    MapPreview {
+        /// Map path or file name
+        map: String,
+
+        /// Level slot, such as 'MAP07' or 'E2M3'
+        slot: String,
+
+        /// Image format to save the preview as
+        #[clap(value_enum, long, default_value = "svg")]
+        format: data::PreviewFormat,
+    },
+
+    /// Save the title screen of a Map as a PNG next to it, and show it in terminals with kitty or sixel graphics
+    TitleScreen {
+        /// Map path or file name
+        map: String,
+
+        /// Graphic to show, defaults to the first the Map has
+        #[clap(value_enum, long)]
+        lump: Option<data::TitleScreenLump>,
+    },
+
     /// Initializes the app for use. Asks a quick set of questions to get you Dooming!
     #[clap(short_flag = 'i')]
     Init {
@@ -79,6 +144,24 @@ pub enum Action {
         /// Show full details. Does not apply to all data types
         #[arg(long, default_value = "false")]
         full: bool,
+
+        /// Show the per level details of the Map with this title or file name. Only applies to Maps
+        #[arg(long)]
+        map: Option<String>,
+    },
+
+    /// Add a custom IWAD, for games dcli doesn't know about
+    AddIwad {
+        /// IWAD path
+        path: String,
+
+        /// Name to show for the IWAD
+        #[arg(long)]
+        name: String,
+
+        /// Game the IWAD is built on, which decides the Engines that can run it
+        #[clap(value_enum, long, default_value = "doom")]
+        game_family: doom_data::GameFamily,
     },
 
     /// Add a new Profile to combine Engine, IWAD, and Maps
@@ -103,6 +186,63 @@ pub enum Action {
         /// Additional arguments to pass to the engine
         #[arg(long)]
         args: Option<Vec<String>>,
+
+        /// Compatibility Level, used instead of the one in Play Settings. Defaults to the one
+        /// recommended for the Maps, use not-set for none
+        #[clap(value_enum, long)]
+        comp_level: Option<data::CompLevel>,
+
+        /// Environment variables to set for the engine, as KEY=VALUE
+        #[arg(long)]
+        env: Option<Vec<String>>,
+
+        /// Folder to start the engine in
+        #[arg(long)]
+        working_dir: Option<String>,
+
+        /// Command to run the engine through, such as 'gamescope -f --'
+        #[arg(long)]
+        wrapper: Option<String>,
+
+        /// Shell command to run before the engine starts
+        #[arg(long)]
+        pre_launch_hook: Option<String>,
+
+        /// Shell command to run after the engine exits
+        #[arg(long)]
+        post_exit_hook: Option<String>,
+    },
+
+    /// Check a Profile for Engine, IWAD and Map combinations that won't work
+    CheckProfile {
+        /// Profile name
+        profile_name: String,
+    },
+
+    /// Set the Netgame Preset used when hosting or joining with a Profile
+    SetNetgame {
+        /// Profile name
+        profile_name: String,
+
+        /// Game mode
+        #[clap(value_enum, long)]
+        game_mode: Option<data::NetGameMode>,
+
+        /// Number of players to host for
+        #[arg(long)]
+        players: Option<u8>,
+
+        /// Address to join. Pass an empty value to clear it
+        #[arg(long)]
+        address: Option<String>,
+
+        /// Network port
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Time limit in minutes
+        #[arg(long)]
+        time_limit: Option<u32>,
     },
 
     /// Delete a Profile. Sad times
@@ -185,6 +325,18 @@ pub enum Action {
         // Use Doomworld API
         #[clap(value_enum, long)]
         use_doomworld_api: Option<bool>,
+        /// Wait for the Engine to exit and record the time played
+        #[clap(value_enum, long)]
+        wait_for_engine: Option<bool>,
+        /// Shell command to run before any Engine starts. Pass an empty value to clear it
+        #[arg(long)]
+        pre_launch_hook: Option<String>,
+        /// Shell command to run after any Engine exits. Pass an empty value to clear it
+        #[arg(long)]
+        post_exit_hook: Option<String>,
+        /// Shell command to run after Maps are downloaded. Pass an empty value to clear it
+        #[arg(long)]
+        post_download_hook: Option<String>,
     },
 
     /// Set Defaults for Engine, IWAD, Profile, and Editor
@@ -264,6 +416,10 @@ pub enum Action {
         #[clap(value_enum, long)]
         windowed: Option<bool>,
 
+        /// Record Demo
+        #[clap(value_enum, long)]
+        record_demo: Option<bool>,
+
         /// Additional Arguments
         #[clap(value_enum, long)]
         additional_args: Option<Vec<String>>,
@@ -280,6 +436,8 @@ pub enum ListData {
     Editors,
     AppSettings,
     PlaySettings,
+    Demos,
+    Duplicates,
 }
 
 pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report> {
@@ -301,18 +459,62 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
         }
 
         match action {
-            Action::Play => Ok((
-                tui::run_menu_command(MenuCommand::PlayDefaultProfile)?,
-                CliRunMode::Quit,
-            )),
-            Action::PlayLast => Ok((
-                tui::run_menu_command(MenuCommand::PlayLastProfile)?,
+            Action::Play { dry_run } => {
+                let play_mode = get_play_mode(dry_run);
+                let result = menu_main::play_default_profile(play_mode.clone())?;
+                Ok((print_dry_run_command(result, &play_mode), CliRunMode::Quit))
+            }
+            Action::PlayLast { dry_run } => {
+                let play_mode = get_play_mode(dry_run);
+                let result = menu_main::play_last_profile(play_mode.clone())?;
+                Ok((print_dry_run_command(result, &play_mode), CliRunMode::Quit))
+            }
+            Action::PlayProfile {
+                profile_name,
+                host,
+                join,
+                warp,
+                dry_run,
+            } => {
+                let play_mode = get_play_mode(dry_run);
+                let net_role = match (host, join) {
+                    (Some(players), _) => Some(data::NetRole::Host { players }),
+                    (None, Some(address)) => Some(data::NetRole::Join { address }),
+                    (None, None) => None,
+                };
+                let result = menu_main::cli_play_selected_profile(
+                    &profile_name,
+                    net_role,
+                    warp,
+                    play_mode.clone(),
+                )?;
+                Ok((print_dry_run_command(result, &play_mode), CliRunMode::Quit))
+            }
+            Action::PlayDemo {
+                demo_name,
+                timedemo,
+                dry_run,
+            } => {
+                let demo_action = match timedemo {
+                    true => data::DemoAction::TimeDemo,
+                    false => data::DemoAction::Play,
+                };
+                let play_mode = get_play_mode(dry_run);
+                let result = menu_demos::cli_play_demo(&demo_name, demo_action, play_mode.clone())?;
+                Ok((print_dry_run_command(result, &play_mode), CliRunMode::Quit))
+            }
+            Action::LastLaunchLog => Ok((menu_main::view_last_launch_log()?, CliRunMode::Quit)),
+            Action::ImportDemos { folder } => Ok((
+                menu_demos::cli_import_demos(&paths::resolve_path(&folder))?,
                 CliRunMode::Quit,
             )),
-            Action::PlayProfile { profile_name } => Ok((
-                menu_main::cli_play_selected_profile(&profile_name)?,
+            Action::MapPreview { map, slot, format } => Ok((
+                menu_maps::cli_map_preview(&map, &slot, format)?,
                 CliRunMode::Quit,
             )),
+            Action::TitleScreen { map, lump } => {
+                Ok((menu_maps::cli_title_screen(&map, lump)?, CliRunMode::Quit))
+            }
             Action::Editor => Ok((
                 tui::run_menu_command(MenuCommand::OpenFromDefaultProfile)?,
                 CliRunMode::Quit,
@@ -351,7 +553,11 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
                     Ok((result, CliRunMode::Tui))
                 }
             }
-            Action::List { list_data, full } => {
+            Action::List {
+                list_data,
+                full,
+                map,
+            } => {
                 let list_type = match full {
                     true => data::ListType::Full,
                     false => data::ListType::Summary,
@@ -359,15 +565,29 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
                 let result = match list_data {
                     ListData::Engines => menu_app_settings::list_engines(),
                     ListData::Iwads => menu_app_settings::list_iwads(),
-                    ListData::Maps => menu_app_settings::list_maps(),
+                    ListData::Maps => menu_app_settings::list_maps(list_type, map),
                     ListData::Profiles => menu_profiles::list_profiles(list_type),
                     ListData::Queues => menu_queues::list_queues(),
                     ListData::Editors => menu_editor::list_editors(),
                     ListData::AppSettings => menu_app_settings::list_app_settings(),
                     ListData::PlaySettings => menu_play_settings::list_play_settings(),
+                    ListData::Demos => menu_demos::list_demos(),
+                    ListData::Duplicates => menu_app_settings::list_duplicates(),
                 }?;
                 Ok((result, CliRunMode::Quit))
             }
+            Action::AddIwad {
+                path,
+                name,
+                game_family,
+            } => Ok((
+                menu_app_settings::cli_add_custom_iwad(
+                    &paths::resolve_path(&path),
+                    &name,
+                    game_family,
+                )?,
+                CliRunMode::Quit,
+            )),
             Action::AddProfile {
                 name,
                 engine,
@@ -375,23 +595,70 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
                 maps,
                 save_game,
                 args,
+                comp_level,
+                env,
+                working_dir,
+                wrapper,
+                pre_launch_hook,
+                post_exit_hook,
             } => {
                 debug!(
-                    "AddProfile: name '{}', engine '{}', iwad '{}', maps '{:?}', save_game '{:?}', args '{:?}'",
-                    name, engine, iwad, maps, save_game, args
+                    "AddProfile: name '{}', engine '{}', iwad '{}', maps '{:?}', save_game '{:?}', args '{:?}', comp_level '{:?}', env '{:?}', working_dir '{:?}', wrapper '{:?}'",
+                    name, engine, iwad, maps, save_game, args, comp_level, env, working_dir, wrapper
                 );
+                // Each variable is quoted, so values with spaces survive being stored as one string
+                let environment = match env {
+                    Some(env) => Some(
+                        shlex::try_join(env.iter().map(|value| value.as_str()))
+                            .wrap_err("Failed to quote environment variables")?,
+                    ),
+                    None => None,
+                };
                 Ok((
                     menu_profiles::cli_add_profile(
                         &name,
                         &paths::resolve_path(&engine),
                         &paths::resolve_path(&iwad),
                         maps,
-                        save_game,
-                        args,
+                        data::ProfileOptions {
+                            save_game,
+                            additional_arguments: args
+                                .map(|args_unwrapped| args_unwrapped.join(" ")),
+                            comp_level,
+                            launch_environment: data::LaunchEnvironment {
+                                environment,
+                                working_directory: paths::resolve_path_opt(working_dir),
+                                wrapper_command: wrapper,
+                                pre_launch_hook,
+                                post_exit_hook,
+                            },
+                        },
                     )?,
                     CliRunMode::Quit,
                 ))
             }
+            Action::CheckProfile { profile_name } => Ok((
+                menu_profiles::cli_check_profile(&profile_name)?,
+                CliRunMode::Quit,
+            )),
+            Action::SetNetgame {
+                profile_name,
+                game_mode,
+                players,
+                address,
+                port,
+                time_limit,
+            } => Ok((
+                menu_profiles::cli_set_netgame_preset(
+                    &profile_name,
+                    game_mode,
+                    players,
+                    address,
+                    port,
+                    time_limit,
+                )?,
+                CliRunMode::Quit,
+            )),
             Action::DeleteProfile { name, force } => Ok((
                 menu_profiles::cli_delete_profile(&name, force)?,
                 CliRunMode::Quit,
@@ -435,6 +702,10 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
             Action::SetAppSettings {
                 menu_mode,
                 use_doomworld_api,
+                wait_for_engine,
+                pre_launch_hook,
+                post_exit_hook,
+                post_download_hook,
             } => {
                 if let Some(menu_mode) = menu_mode {
                     Ok((
@@ -446,6 +717,23 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
                         menu_app_settings::cli_update_use_doomworld_api(use_doomworld_api)?,
                         CliRunMode::Quit,
                     ))
+                } else if let Some(wait_for_engine) = wait_for_engine {
+                    Ok((
+                        menu_app_settings::cli_update_wait_for_engine(wait_for_engine)?,
+                        CliRunMode::Quit,
+                    ))
+                } else if pre_launch_hook.is_some()
+                    || post_exit_hook.is_some()
+                    || post_download_hook.is_some()
+                {
+                    Ok((
+                        menu_app_settings::cli_update_hooks(
+                            pre_launch_hook,
+                            post_exit_hook,
+                            post_download_hook,
+                        )?,
+                        CliRunMode::Quit,
+                    ))
                 } else {
                     Ok(("No arguments specified".to_string(), CliRunMode::Quit))
                 }
@@ -495,6 +783,7 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
                 screen_height,
                 full_screen,
                 windowed,
+                record_demo,
                 additional_args,
             } => {
                 if let Some(reset) = reset {
@@ -560,6 +849,11 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
                         menu_play_settings::cli_set_windowed(windowed)?,
                         CliRunMode::Quit,
                     ))
+                } else if let Some(record_demo) = record_demo {
+                    Ok((
+                        menu_play_settings::cli_set_record_demo(record_demo)?,
+                        CliRunMode::Quit,
+                    ))
                 } else if let Some(additional_args) = additional_args {
                     Ok((
                         menu_play_settings::cli_set_additional_args(additional_args)?,
@@ -577,3 +871,22 @@ pub fn run_cli_action(args: Args) -> Result<(String, CliRunMode), eyre::Report>
         ))
     }
 }
+
+fn get_play_mode(dry_run: Option<data::DryRunFormat>) -> data::PlayMode {
+    match dry_run {
+        Some(dry_run_format) => data::PlayMode::DryRun(dry_run_format),
+        None => data::PlayMode::Launch,
+    }
+}
+
+// Only the command goes to stdout, so it can be piped or copied, and every other message
+// stays in the log
+fn print_dry_run_command(result: String, play_mode: &data::PlayMode) -> String {
+    match play_mode {
+        data::PlayMode::DryRun(_) => {
+            println!("{}", result);
+            "Dry run only, Engine not started".to_string()
+        }
+        data::PlayMode::Launch => result,
+    }
+}
@@ -0,0 +1,323 @@
+use regex::Regex;
+
+use crate::{
+    data::{self, CompLevel, CompLevelConfidence, CompLevelEvidence},
+    doom_data::KeyLump,
+    files,
+};
+
+// Recommends a Compatibility Level for a Map from what is inside it and its readme. A COMPLVL
+// lump is the author saying so, codepointers show what the DeHackEd patch needs, and readme
+// lines and UMAPINFO are hints. See https://doomwiki.org/wiki/COMPLVL and
+// https://doomwiki.org/wiki/MBF21
+
+// Codepointers added by MBF21, they fail to load in anything older
+const MBF21_CODEPOINTERS: [&str; 28] = [
+    "A_SpawnObject",
+    "A_MonsterProjectile",
+    "A_MonsterBulletAttack",
+    "A_MonsterMeleeAttack",
+    "A_RadiusDamage",
+    "A_NoiseAlert",
+    "A_HealChase",
+    "A_SeekTracer",
+    "A_FindTracer",
+    "A_ClearTracer",
+    "A_JumpIfHealthBelow",
+    "A_JumpIfTargetInSight",
+    "A_JumpIfTargetCloser",
+    "A_JumpIfTracerInSight",
+    "A_JumpIfTracerCloser",
+    "A_JumpIfFlagsSet",
+    "A_AddFlags",
+    "A_RemoveFlags",
+    "A_WeaponProjectile",
+    "A_WeaponBulletAttack",
+    "A_WeaponMeleeAttack",
+    "A_WeaponSound",
+    "A_WeaponAlert",
+    "A_WeaponJump",
+    "A_ConsumeAmmo",
+    "A_CheckAmmo",
+    "A_RefireTo",
+    "A_GunFlashTo",
+];
+const MBF_CODEPOINTERS: [&str; 12] = [
+    "A_Detonate",
+    "A_Mushroom",
+    "A_Spawn",
+    "A_Turn",
+    "A_Face",
+    "A_Scratch",
+    "A_PlaySound",
+    "A_RandomJump",
+    "A_LineEffect",
+    "A_Die",
+    "A_FireOldBFG",
+    "A_BetaSkullAttack",
+];
+const README_KEYS: [&str; 2] = ["advanced engine needed", "port"];
+
+pub const COMP_LEVEL_LUMPS: [KeyLump; 3] = [KeyLump::Complvl, KeyLump::Dehacked, KeyLump::Umapinfo];
+
+pub fn get_lump_evidence(key_lump: &KeyLump, text: &str) -> Option<CompLevelEvidence> {
+    match key_lump {
+        KeyLump::Complvl => get_complvl_evidence(text),
+        KeyLump::Dehacked => get_dehacked_evidence(text),
+        KeyLump::Umapinfo => get_umapinfo_evidence(),
+        _ => None,
+    }
+}
+
+fn evidence(comp_level: CompLevel, confidence: CompLevelConfidence) -> Option<CompLevelEvidence> {
+    Some(CompLevelEvidence {
+        comp_level,
+        confidence,
+    })
+}
+
+// COMPLVL holds one of vanilla, boom, mbf or mbf21
+fn get_complvl_evidence(text: &str) -> Option<CompLevelEvidence> {
+    let comp_level = match text.trim().to_lowercase().as_str() {
+        "vanilla" => CompLevel::DoomV19,
+        "boom" => CompLevel::BoomV202,
+        "mbf" => CompLevel::Mbf,
+        "mbf21" => CompLevel::Mbf21,
+        _ => return None,
+    };
+    evidence(comp_level, CompLevelConfidence::High)
+}
+
+fn get_dehacked_evidence(text: &str) -> Option<CompLevelEvidence> {
+    let has_codepointer = |codepointers: &[&str]| {
+        codepointers.iter().any(|codepointer| {
+            Regex::new(&format!(r"(?i)\b{}\b", codepointer))
+                .unwrap()
+                .is_match(text)
+        })
+    };
+    let mbf21_header_re = Regex::new(r"(?im)^\s*(doom version\s*=\s*2021|mbf21 bits\s*=)").unwrap();
+    let bex_section_re =
+        Regex::new(r"(?im)^\s*\[(codeptr|strings|pars|music|sprites|sounds|helper)\]").unwrap();
+
+    if mbf21_header_re.is_match(text) || has_codepointer(&MBF21_CODEPOINTERS) {
+        evidence(CompLevel::Mbf21, CompLevelConfidence::High)
+    } else if has_codepointer(&MBF_CODEPOINTERS) {
+        evidence(CompLevel::Mbf, CompLevelConfidence::Medium)
+    } else if bex_section_re.is_match(text) {
+        evidence(CompLevel::BoomV202, CompLevelConfidence::Medium)
+    } else {
+        evidence(CompLevel::DoomV19, CompLevelConfidence::Low)
+    }
+}
+
+// UMAPINFO came with MBF21 Engines, though a few older ports read it too
+fn get_umapinfo_evidence() -> Option<CompLevelEvidence> {
+    evidence(CompLevel::Mbf21, CompLevelConfidence::Low)
+}
+
+// From the idgames template: "Advanced engine needed : Boom" or "Port : Limit-removing"
+pub fn get_readme_evidence(text: &str) -> Option<CompLevelEvidence> {
+    let number_re = Regex::new(r"(?i)\b(?:cl|complevel)\s*-?\s*(\d+)\b").unwrap();
+
+    text.lines()
+        .filter_map(|line| {
+            README_KEYS
+                .iter()
+                .find_map(|key| files::check_readme_line(line.trim(), key))
+        })
+        .find_map(|value| {
+            let value = value.to_lowercase();
+            let comp_level = if let Some(captures) = number_re.captures(&value) {
+                get_comp_level_from_number(captures[1].parse().ok()?)?
+            } else if value.contains("mbf21") || value.contains("mbf 21") {
+                CompLevel::Mbf21
+            } else if value.contains("mbf") {
+                CompLevel::Mbf
+            } else if value.contains("boom") {
+                CompLevel::BoomV202
+            } else if value.contains("limit") || value.contains("vanilla") || value.contains("none")
+            {
+                CompLevel::DoomV19
+            } else {
+                return None;
+            };
+            evidence(comp_level, CompLevelConfidence::Medium)
+        })
+}
+
+fn get_comp_level_from_number(number: i32) -> Option<CompLevel> {
+    let comp_level = match number {
+        0 => CompLevel::DoomV12,
+        1 => CompLevel::DoomV1666,
+        2 => CompLevel::DoomV19,
+        3 => CompLevel::UltimateDoom,
+        4 => CompLevel::FinalDoom,
+        5 => CompLevel::DosDoom,
+        6 => CompLevel::TasDoom,
+        7 => CompLevel::Boom,
+        8 => CompLevel::BoomV201,
+        9 => CompLevel::BoomV202,
+        10 => CompLevel::LxDoom,
+        11 => CompLevel::Mbf,
+        12 => CompLevel::PrBoomV203,
+        13 => CompLevel::PrBoomV210,
+        14 => CompLevel::PrBoomV211,
+        15 => CompLevel::PrBoomV23,
+        16 => CompLevel::PrBoomV24,
+        17 => CompLevel::PrBoomPlus,
+        21 => CompLevel::Mbf21,
+        _ => return None,
+    };
+    Some(comp_level)
+}
+
+// The most certain evidence wins, and between equals the higher level, as a Map that needs
+// MBF21 won't play at Boom
+pub fn pick_comp_level(evidence: &[CompLevelEvidence]) -> Option<CompLevelEvidence> {
+    evidence
+        .iter()
+        .max_by_key(|evidence| {
+            (
+                evidence.confidence.clone(),
+                evidence.comp_level.clone() as i32,
+            )
+        })
+        .cloned()
+}
+
+// For a set of Maps played together, the highest recommendation covers them all. Low
+// confidence guesses are only good for suggesting, not for applying without asking
+pub fn get_maps_comp_level(
+    maps: &[data::Map],
+    min_confidence: CompLevelConfidence,
+) -> Option<CompLevel> {
+    maps.iter()
+        .filter(|map| {
+            map.comp_level_confidence
+                .as_ref()
+                .is_some_and(|confidence| *confidence >= min_confidence)
+        })
+        .filter_map(|map| map.comp_level.clone())
+        .max_by_key(|comp_level| comp_level.clone() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        complevel::{
+            get_complvl_evidence, get_dehacked_evidence, get_maps_comp_level, get_readme_evidence,
+            pick_comp_level,
+        },
+        data::{self, CompLevel, CompLevelConfidence, CompLevelEvidence},
+    };
+
+    fn evidence(comp_level: CompLevel, confidence: CompLevelConfidence) -> CompLevelEvidence {
+        CompLevelEvidence {
+            comp_level,
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_get_complvl_evidence() {
+        assert_eq!(
+            get_complvl_evidence(" MBF21\n"),
+            Some(evidence(CompLevel::Mbf21, CompLevelConfidence::High))
+        );
+        assert_eq!(
+            get_complvl_evidence("vanilla"),
+            Some(evidence(CompLevel::DoomV19, CompLevelConfidence::High))
+        );
+        assert_eq!(get_complvl_evidence("zdoom"), None);
+    }
+
+    #[test]
+    fn test_get_dehacked_evidence() {
+        // Arrange
+        let mbf21 = "Patch File for DeHackEd v3.0\nDoom version = 2021\n\nFrame 1000\nCodepointer = A_MonsterProjectile\n";
+        let mbf = "[CODEPTR]\nFRAME 1000 = Mushroom\n\nFrame 1001\nAction pointer = A_Mushroom\n";
+        let bex = "[STRINGS]\nGOTARMOR = Picked up the armor.\n";
+        let vanilla = "Patch File for DeHackEd v3.0\nDoom version = 19\n\nThing 1 (Player)\nInitial health = 200\n";
+
+        // Act
+        let actual: Vec<Option<CompLevelEvidence>> = [mbf21, mbf, bex, vanilla]
+            .iter()
+            .map(|text| get_dehacked_evidence(text))
+            .collect();
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                Some(evidence(CompLevel::Mbf21, CompLevelConfidence::High)),
+                Some(evidence(CompLevel::Mbf, CompLevelConfidence::Medium)),
+                Some(evidence(CompLevel::BoomV202, CompLevelConfidence::Medium)),
+                Some(evidence(CompLevel::DoomV19, CompLevelConfidence::Low)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_readme_evidence() {
+        assert_eq!(
+            get_readme_evidence("Title : Sunlust\nAdvanced engine needed  : Boom compatible\n"),
+            Some(evidence(CompLevel::BoomV202, CompLevelConfidence::Medium))
+        );
+        assert_eq!(
+            get_readme_evidence("Port: Limit-removing (-complevel 2)\n"),
+            Some(evidence(CompLevel::DoomV19, CompLevelConfidence::Medium))
+        );
+        assert_eq!(
+            get_readme_evidence("Port: MBF21 (cl21)\n"),
+            Some(evidence(CompLevel::Mbf21, CompLevelConfidence::Medium))
+        );
+        assert_eq!(
+            get_readme_evidence("Port: PrBoom+ -complevel 14\n"),
+            Some(evidence(CompLevel::PrBoomV211, CompLevelConfidence::Medium))
+        );
+        assert_eq!(get_readme_evidence("Port: GZDoom\n"), None);
+    }
+
+    #[test]
+    fn test_pick_comp_level() {
+        // Arrange
+        let evidence_list = vec![
+            evidence(CompLevel::Mbf21, CompLevelConfidence::Low),
+            evidence(CompLevel::BoomV202, CompLevelConfidence::Medium),
+            evidence(CompLevel::DoomV19, CompLevelConfidence::Medium),
+        ];
+
+        // Act
+        let actual = pick_comp_level(&evidence_list);
+
+        // Assert
+        assert_eq!(
+            actual,
+            Some(evidence(CompLevel::BoomV202, CompLevelConfidence::Medium))
+        );
+        assert_eq!(pick_comp_level(&[]), None);
+    }
+
+    #[test]
+    fn test_get_maps_comp_level_skips_low_confidence() {
+        // Arrange
+        let map = |comp_level: CompLevel, confidence: CompLevelConfidence| data::Map {
+            comp_level: Some(comp_level),
+            comp_level_confidence: Some(confidence),
+            ..Default::default()
+        };
+        let maps = vec![
+            map(CompLevel::Mbf21, CompLevelConfidence::Low),
+            map(CompLevel::BoomV202, CompLevelConfidence::Medium),
+        ];
+
+        // Act
+        let actual_applied = get_maps_comp_level(&maps, CompLevelConfidence::Medium);
+        let actual_suggested = get_maps_comp_level(&maps, CompLevelConfidence::Low);
+
+        // Assert
+        assert_eq!(actual_applied, Some(CompLevel::BoomV202));
+        assert_eq!(actual_suggested, Some(CompLevel::Mbf21));
+    }
+}
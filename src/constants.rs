@@ -7,10 +7,17 @@ pub const CRATE_AUTHORS: &str = env!("CARGO_PKG_AUTHORS");
 pub const OS_MACOS: &str = "macos";
 pub const OS_WINDOWS: &str = "windows";
 pub const OS_LINUX: &str = "linux";
+pub const VERSION_CHECK_TIMEOUT_SECONDS: u64 = 3;
+pub const XDG_OPEN: &str = "xdg-open";
+pub const ENV_BROWSER: &str = "BROWSER";
+pub const ENV_EDITOR: &str = "EDITOR";
 
 pub const DEFAULT_UNKNOWN: &str = "Unknown";
 pub const DEFAULT_NOT_SET: &str = "Not Set";
 pub const MENU_NOT_SET: &str = "<Not Set>";
 pub const EMPTY_QUEUE: &str = "<Empty Queue>";
 
-pub const MIN_NAME_LENGTH: usize = 3;
\ No newline at end of file
+pub const MIN_NAME_LENGTH: usize = 3;
+pub const MAX_NETGAME_PLAYERS: u8 = 8;
+// Bump when reading a Map finds something new, so Maps read before are read again on the next scan
+pub const MAP_CONTENTS_VERSION: i32 = 1;
@@ -8,7 +8,7 @@ use strum_macros::{Display, EnumString};
 use tabled::Tabled;
 
 use crate::{
-    constants, doom_data,
+    complevel, constants, doom_data,
     tui::{self, MenuMode},
 };
 
@@ -58,6 +58,12 @@ pub struct Engine {
     pub version: String,
     #[tabled(rename = "Engine Type")]
     pub game_engine_type: doom_data::GameEngineType,
+    #[sqlx(default)]
+    #[tabled(skip)]
+    pub sha1: Option<String>,
+    #[sqlx(default)]
+    #[tabled(skip)]
+    pub md5: Option<String>,
 }
 
 impl Engine {
@@ -94,6 +100,8 @@ impl Default for Engine {
             internal_path: None,
             version: "-".to_string(),
             game_engine_type: doom_data::GameEngineType::Unknown,
+            sha1: None,
+            md5: None,
         }
     }
 }
@@ -106,11 +114,44 @@ pub struct Iwad {
     pub path: String,
     #[tabled(rename = "Internal WAD Type")]
     pub internal_wad_type: doom_data::InternalWadType,
+    #[sqlx(default)]
+    #[tabled(rename = "Release", display_with = "display_option_string")]
+    pub release: Option<String>,
+    #[sqlx(default)]
+    #[tabled(rename = "Name", display_with = "display_option_string")]
+    pub name: Option<String>,
+    #[sqlx(default)]
+    #[tabled(rename = "Game Family", display_with = "display_option_game_family")]
+    pub game_family: Option<doom_data::GameFamily>,
+    #[sqlx(default)]
+    #[tabled(skip)]
+    pub sha1: Option<String>,
+    #[sqlx(default)]
+    #[tabled(skip)]
+    pub md5: Option<String>,
 }
 
 impl Iwad {
     pub fn simple_display(&self) -> String {
-        format!("{}, {}", self.internal_wad_type, self.path)
+        format!("{}, {}", self.type_display(), self.path)
+    }
+    pub fn type_display(&self) -> String {
+        match &self.release {
+            Some(release) => format!("{} ({})", self.get_type_name(), release),
+            None => self.get_type_name(),
+        }
+    }
+    // Custom IWADs go by the name they were registered with
+    pub fn get_type_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => self.internal_wad_type.to_string(),
+        }
+    }
+    pub fn get_game_family(&self) -> doom_data::GameFamily {
+        self.game_family
+            .clone()
+            .unwrap_or_else(|| self.internal_wad_type.get_game_family())
     }
 }
 
@@ -120,7 +161,7 @@ impl fmt::Display for Iwad {
         write!(
             f,
             "{:<width$} | {}",
-            truncate_string_end(&self.internal_wad_type.to_string(), width),
+            truncate_string_end(&self.type_display(), width),
             self.path,
             width = width,
         )
@@ -133,10 +174,24 @@ impl Default for Iwad {
             id: 0,
             path: constants::DEFAULT_NOT_SET.to_string(),
             internal_wad_type: doom_data::InternalWadType::Unknown,
+            release: None,
+            name: None,
+            game_family: None,
+            sha1: None,
+            md5: None,
         }
     }
 }
 
+// Files with the same contents, found by SHA-1 across the Map and IWAD folders
+#[derive(Clone, Debug, Tabled)]
+pub struct DuplicateFiles {
+    #[tabled(rename = "SHA-1")]
+    pub sha1: String,
+    #[tabled(rename = "Paths")]
+    pub paths: String,
+}
+
 #[derive(Clone, Debug, FromRow, Tabled)]
 pub struct Map {
     #[tabled(skip)]
@@ -151,12 +206,46 @@ pub struct Map {
     pub doomworld_id: Option<i32>,
     #[tabled(rename = "Doomworld Url", display_with = "display_option_string")]
     pub doomworld_url: Option<String>,
+    #[sqlx(default)]
+    #[tabled(rename = "IWAD", display_with = "display_option_string")]
+    pub required_iwad: Option<String>,
+    #[sqlx(default)]
+    #[tabled(rename = "Inferred IWAD", display_with = "display_option_string")]
+    pub inferred_iwad: Option<String>,
+    #[sqlx(default)]
+    #[tabled(skip)]
+    pub contents_version: i32,
+    #[sqlx(default)]
+    #[tabled(rename = "ZDoom", display_with = "display_requires_zdoom")]
+    pub requires_zdoom: bool,
+    #[sqlx(default)]
+    #[tabled(rename = "Complevel", display_with = "display_option_comp_level")]
+    pub comp_level: Option<CompLevel>,
+    #[sqlx(default)]
+    #[tabled(
+        rename = "Confidence",
+        display_with = "display_option_comp_level_confidence"
+    )]
+    pub comp_level_confidence: Option<CompLevelConfidence>,
+    #[sqlx(default)]
+    #[tabled(skip)]
+    pub sha1: Option<String>,
+    #[sqlx(default)]
+    #[tabled(skip)]
+    pub md5: Option<String>,
 }
 
 impl Map {
     pub fn simple_display(&self) -> String {
         format!("{}, {}, {}", self.title, self.author, self.path)
     }
+
+    // GAMEINFO's IWAD, otherwise the one worked out from the Map's readme and levels
+    pub fn iwad(&self) -> Option<&str> {
+        self.required_iwad
+            .as_deref()
+            .or(self.inferred_iwad.as_deref())
+    }
 }
 
 impl fmt::Display for Map {
@@ -184,10 +273,166 @@ impl Default for Map {
             path: constants::DEFAULT_NOT_SET.to_string(),
             doomworld_id: None,
             doomworld_url: None,
+            required_iwad: None,
+            inferred_iwad: None,
+            contents_version: 0,
+            requires_zdoom: false,
+            comp_level: None,
+            comp_level_confidence: None,
+            sha1: None,
+            md5: None,
         }
     }
 }
 
+// A Map as listed, with the time it has been played across all play sessions
+#[derive(Clone, Debug, FromRow, Tabled)]
+pub struct MapDisplay {
+    #[tabled(skip)]
+    pub id: i32,
+    #[tabled(rename = "Title")]
+    pub title: String,
+    #[tabled(rename = "Author")]
+    pub author: String,
+    #[tabled(rename = "Path")]
+    pub path: String,
+    #[tabled(rename = "Doomworld Id", display_with = "display_option_i32")]
+    pub doomworld_id: Option<i32>,
+    #[tabled(rename = "Doomworld Url", display_with = "display_option_string")]
+    pub doomworld_url: Option<String>,
+    #[tabled(rename = "Time Played", display_with = "display_time_played")]
+    pub time_played: i64,
+    #[tabled(rename = "IWAD", display_with = "display_option_string")]
+    pub required_iwad: Option<String>,
+    #[tabled(rename = "Inferred IWAD", display_with = "display_option_string")]
+    pub inferred_iwad: Option<String>,
+    #[tabled(rename = "ZDoom", display_with = "display_requires_zdoom")]
+    pub requires_zdoom: bool,
+    #[tabled(rename = "Complevel", display_with = "display_option_comp_level")]
+    pub comp_level: Option<CompLevel>,
+    #[tabled(
+        rename = "Confidence",
+        display_with = "display_option_comp_level_confidence"
+    )]
+    pub comp_level_confidence: Option<CompLevelConfidence>,
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct MapLevel {
+    pub lump_name: String,
+    pub level_format: doom_data::LevelFormat,
+    #[sqlx(default)]
+    pub level_name: Option<String>,
+}
+
+impl fmt::Display for MapLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.level_name {
+            Some(level_name) => write!(f, "{} – {}", self.lump_name, level_name),
+            None => write!(f, "{}", self.lump_name),
+        }
+    }
+}
+
+// Doom has five skills, but a thing can only be flagged for three groups of them
+#[derive(Clone, Debug, Display, EnumString, PartialEq, sqlx::Type)]
+pub enum SkillTier {
+    #[strum(serialize = "Easy (1-2)")]
+    Easy,
+    #[strum(serialize = "Medium (3)")]
+    Medium,
+    #[strum(serialize = "Hard (4-5)")]
+    Hard,
+}
+
+// What a single player meets in a level on one Skill Tier
+#[derive(Clone, Debug, FromRow, PartialEq)]
+pub struct LevelStats {
+    #[sqlx(default)]
+    pub map_id: i32,
+    pub lump_name: String,
+    pub skill_tier: SkillTier,
+    pub monsters: i32,
+    pub monster_hit_points: i32,
+    pub items: i32,
+    pub weapons: i32,
+    pub ammo: i32,
+    pub keys: i32,
+    pub secrets: i32,
+}
+
+// A Map's levels added up on the Hard Skill Tier, which is what Ultra-Violence plays
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapStatsSummary {
+    pub map_id: i32,
+    pub levels: i32,
+    pub monsters: i32,
+    pub monster_hit_points: i32,
+    pub secrets: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct WarpLevel {
+    pub lump_name: String,
+    pub level_name: Option<String>,
+    pub warp: String,
+}
+
+impl fmt::Display for WarpLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.level_name {
+            Some(level_name) => write!(f, "{} – {}", self.lump_name, level_name),
+            None => write!(f, "{}", self.lump_name),
+        }
+    }
+}
+
+// What was found inside a Map file, empty when it hasn't been read. The title, required
+// IWAD and Compatibility Level are only found when reading the file, they are saved to the
+// Map itself
+#[derive(Clone, Debug, Default)]
+pub struct MapContents {
+    pub levels: Vec<MapLevel>,
+    pub lumps: Vec<doom_data::KeyLump>,
+    pub title: Option<String>,
+    // Named by GAMEINFO
+    pub required_iwad: Option<String>,
+    // Worked out from the readme's "Game:" line and the levels when GAMEINFO doesn't name one
+    pub inferred_iwad: Option<String>,
+    pub comp_level_evidence: Vec<CompLevelEvidence>,
+    pub level_stats: Vec<LevelStats>,
+    // The game named by a readme's "Game:" line
+    pub readme_game: Option<doom_data::InternalWadType>,
+    // Read from a PK3 or PKE rather than a WAD
+    pub is_archive: bool,
+}
+
+impl MapContents {
+    // PK3 and PKE archives, and lumps that only the ZDoom family of Engines read
+    pub fn requires_zdoom(&self) -> bool {
+        self.is_archive
+            || self.lumps.iter().any(|lump| {
+                matches!(
+                    lump,
+                    doom_data::KeyLump::Zmapinfo
+                        | doom_data::KeyLump::Gameinfo
+                        | doom_data::KeyLump::Decorate
+                        | doom_data::KeyLump::Zscript
+                )
+            })
+    }
+
+    pub fn recommended_comp_level(&self) -> Option<CompLevelEvidence> {
+        complevel::pick_comp_level(&self.comp_level_evidence)
+    }
+
+    pub fn iwad(&self) -> Option<&str> {
+        self.required_iwad
+            .as_deref()
+            .or(self.inferred_iwad.as_deref())
+    }
+}
+
 fn get_author_or_empty(author: &str) -> &str {
     match author {
         a if a == constants::DEFAULT_UNKNOWN => "",
@@ -253,6 +498,49 @@ pub struct Profile {
     pub run_count: i32,
     pub save_game: Option<String>,
     pub additional_arguments: Option<String>,
+    #[sqlx(default)]
+    pub comp_level: Option<CompLevel>,
+    #[sqlx(default)]
+    pub environment: Option<String>,
+    #[sqlx(default)]
+    pub working_directory: Option<String>,
+    #[sqlx(default)]
+    pub wrapper_command: Option<String>,
+    #[sqlx(default)]
+    pub pre_launch_hook: Option<String>,
+    #[sqlx(default)]
+    pub post_exit_hook: Option<String>,
+}
+
+impl Profile {
+    pub fn launch_environment(&self) -> LaunchEnvironment {
+        LaunchEnvironment {
+            environment: self.environment.clone(),
+            working_directory: self.working_directory.clone(),
+            wrapper_command: self.wrapper_command.clone(),
+            pre_launch_hook: self.pre_launch_hook.clone(),
+            post_exit_hook: self.post_exit_hook.clone(),
+        }
+    }
+}
+
+// How the Engine process is started and what runs around it, rather than what is passed to it
+#[derive(Clone, Debug, Default)]
+pub struct LaunchEnvironment {
+    pub environment: Option<String>,
+    pub working_directory: Option<String>,
+    pub wrapper_command: Option<String>,
+    pub pre_launch_hook: Option<String>,
+    pub post_exit_hook: Option<String>,
+}
+
+// Optional Profile settings given when adding a Profile from the command line
+#[derive(Clone, Debug, Default)]
+pub struct ProfileOptions {
+    pub save_game: Option<String>,
+    pub additional_arguments: Option<String>,
+    pub comp_level: Option<CompLevel>,
+    pub launch_environment: LaunchEnvironment,
 }
 
 pub type MapIds = (i32, i32, i32, i32, i32);
@@ -332,6 +620,23 @@ pub struct ProfileDisplay {
     pub save_game: String,
     #[tabled(rename = "Run Count")]
     pub run_count: i32,
+    #[tabled(rename = "Time Played", display_with = "display_time_played")]
+    pub time_played: i64,
+    #[tabled(
+        rename = "Compatibility Level",
+        display_with = "display_option_comp_level"
+    )]
+    pub comp_level: Option<CompLevel>,
+    #[tabled(rename = "Environment")]
+    pub environment: String,
+    #[tabled(rename = "Working Directory")]
+    pub working_directory: String,
+    #[tabled(rename = "Wrapper Command")]
+    pub wrapper_command: String,
+    #[tabled(rename = "Pre-Launch Hook")]
+    pub pre_launch_hook: String,
+    #[tabled(rename = "Post-Exit Hook")]
+    pub post_exit_hook: String,
 }
 
 impl ProfileDisplay {
@@ -393,6 +698,79 @@ impl fmt::Display for ProfileDisplay {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct PlaySession {
+    pub profile_id: Option<i32>,
+    pub engine_id: i32,
+    pub iwad_id: i32,
+    pub map_id: Option<i32>,
+    pub map_id2: Option<i32>,
+    pub map_id3: Option<i32>,
+    pub map_id4: Option<i32>,
+    pub map_id5: Option<i32>,
+    pub date_started: DateTime<Utc>,
+    pub date_ended: DateTime<Utc>,
+    pub duration: i64,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct Demo {
+    pub id: i32,
+    pub name: String,
+    pub path: String,
+    pub profile_id: Option<i32>,
+    pub engine_id: i32,
+    pub iwad_id: i32,
+    pub map_id: Option<i32>,
+    pub map_id2: Option<i32>,
+    pub map_id3: Option<i32>,
+    pub map_id4: Option<i32>,
+    pub map_id5: Option<i32>,
+    pub warp: Option<String>,
+    pub skill: Option<u8>,
+    pub date_recorded: DateTime<Utc>,
+    #[sqlx(default)]
+    pub comp_level: Option<CompLevel>,
+}
+
+impl fmt::Display for Demo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let converted: DateTime<Local> = DateTime::from(self.date_recorded);
+        write!(f, "{} | {}", self.name, format_local_datetime(&converted))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Display)]
+pub enum DemoAction {
+    Record,
+    #[strum(serialize = "Play Demo")]
+    Play,
+    #[strum(serialize = "Time Demo")]
+    TimeDemo,
+}
+
+#[derive(Clone, Debug)]
+pub struct DemoPlayback {
+    pub demo_action: DemoAction,
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PlayOptions {
+    pub profile_id: Option<i32>,
+    // Playing a Profile counts as a run of it, and can make it the Last Profile
+    pub record_profile_run: bool,
+    pub update_last_profile: bool,
+    pub save_game: Option<String>,
+    pub additional_arguments: Option<String>,
+    pub demo_playback: Option<DemoPlayback>,
+    pub comp_level: Option<CompLevel>,
+    pub netgame: Option<Netgame>,
+    pub warp: Option<String>,
+    pub launch_environment: LaunchEnvironment,
+}
+
 #[derive(Clone, Debug, FromRow)]
 pub struct Queue {
     pub id: i32,
@@ -496,6 +874,10 @@ pub struct AppSettings {
     pub editor_search_folder: Option<String>,
     pub menu_mode: tui::MenuMode,
     pub use_doomworld_api: bool,
+    pub wait_for_engine: bool,
+    pub pre_launch_hook: Option<String>,
+    pub post_exit_hook: Option<String>,
+    pub post_download_hook: Option<String>,
 }
 
 impl Default for AppSettings {
@@ -513,6 +895,10 @@ impl Default for AppSettings {
             editor_search_folder: None,
             menu_mode: MenuMode::Full,
             use_doomworld_api: false,
+            wait_for_engine: false,
+            pre_launch_hook: None,
+            post_exit_hook: None,
+            post_download_hook: None,
         }
     }
 }
@@ -541,6 +927,14 @@ pub struct AppSettingsDisplay {
     pub editor_search_folder: String,
     #[tabled(rename = "Use Doomworld API")]
     pub use_doomworld_api: bool,
+    #[tabled(rename = "Wait for Engine to Exit")]
+    pub wait_for_engine: bool,
+    #[tabled(rename = "Pre-Launch Hook")]
+    pub pre_launch_hook: String,
+    #[tabled(rename = "Post-Exit Hook")]
+    pub post_exit_hook: String,
+    #[tabled(rename = "Post-Download Hook")]
+    pub post_download_hook: String,
 }
 
 pub fn display_option_u8(value: &Option<u8>) -> String {
@@ -571,6 +965,13 @@ pub fn display_option_string(value: &Option<String>) -> String {
     }
 }
 
+pub fn display_option_game_family(value: &Option<doom_data::GameFamily>) -> String {
+    match value {
+        Some(game_family) => game_family.to_string(),
+        None => constants::DEFAULT_NOT_SET.to_string(),
+    }
+}
+
 pub fn display_option_comp_level(value: &Option<CompLevel>) -> String {
     match value {
         Some(s) => s.to_string(),
@@ -608,12 +1009,43 @@ pub enum CompLevel {
     LxDoom = 10,
     #[strum(serialize = "MBF (11)")]
     Mbf = 11,
+    #[strum(serialize = "PrBoom v2.03 (12)")]
+    PrBoomV203 = 12,
+    #[strum(serialize = "PrBoom v2.1.0 (13)")]
+    PrBoomV210 = 13,
+    #[strum(serialize = "PrBoom v2.1.1-2.2.6 (14)")]
+    PrBoomV211 = 14,
+    #[strum(serialize = "PrBoom v2.3.x (15)")]
+    PrBoomV23 = 15,
+    #[strum(serialize = "PrBoom v2.4.0 (16)")]
+    PrBoomV24 = 16,
     #[strum(serialize = "PrBoom+ (17)")]
     PrBoomPlus = 17,
     #[strum(serialize = "MBF 21 (21)")]
     Mbf21 = 21,
 }
 
+#[derive(Clone, Debug, Display, EnumString, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+pub enum CompLevelConfidence {
+    Low,
+    Medium,
+    High,
+}
+
+pub fn display_option_comp_level_confidence(value: &Option<CompLevelConfidence>) -> String {
+    match value {
+        Some(s) => s.to_string(),
+        None => constants::DEFAULT_NOT_SET.to_string(),
+    }
+}
+
+// A Compatibility Level suggested by something found in or alongside a Map
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompLevelEvidence {
+    pub comp_level: CompLevel,
+    pub confidence: CompLevelConfidence,
+}
+
 #[derive(Clone, Debug, FromRow, Default, Tabled)]
 pub struct PlaySettings {
     #[tabled(skip)]
@@ -647,6 +1079,8 @@ pub struct PlaySettings {
     pub full_screen: bool,
     #[tabled(rename = "Windowed")]
     pub windowed: bool,
+    #[tabled(rename = "Record Demo")]
+    pub record_demo: bool,
     #[tabled(
         rename = "Additional Arguments",
         display_with = "display_option_string"
@@ -721,6 +1155,30 @@ pub fn display_option_utc_datetime_to_local(value: &Option<DateTime<Utc>>) -> St
     "N/A".to_string()
 }
 
+pub fn display_requires_zdoom(requires_zdoom: &bool) -> String {
+    match requires_zdoom {
+        true => "Yes".to_string(),
+        false => "No".to_string(),
+    }
+}
+
+pub fn display_time_played(seconds: &i64) -> String {
+    if *seconds <= 0 {
+        return "N/A".to_string();
+    }
+
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, secs)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
 fn format_local_datetime(local_datetime: &DateTime<Local>) -> String {
     format!(
         "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
@@ -738,9 +1196,97 @@ pub enum ListType {
     Summary,
 }
 
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+pub enum DryRunFormat {
+    Shell,
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+pub enum PreviewFormat {
+    Svg,
+    Png,
+}
+
+impl PreviewFormat {
+    pub fn get_extension(&self) -> &str {
+        match self {
+            PreviewFormat::Svg => "svg",
+            PreviewFormat::Png => "png",
+        }
+    }
+}
+
+// Title and menu graphics a Map can replace, in the order they are worth showing
+#[derive(Clone, Debug, Display, EnumString, PartialEq, ValueEnum)]
+pub enum TitleScreenLump {
+    #[strum(serialize = "TITLEPIC")]
+    Titlepic,
+    #[strum(serialize = "INTERPIC")]
+    Interpic,
+    #[strum(serialize = "M_DOOM")]
+    MDoom,
+}
+
+#[derive(Clone, Debug, Default, Display, EnumString, PartialEq, sqlx::Type, ValueEnum)]
+pub enum NetGameMode {
+    #[default]
+    #[strum(serialize = "Co-op")]
+    Coop,
+    Deathmatch,
+    #[strum(serialize = "AltDeath")]
+    AltDeath,
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct NetgamePreset {
+    pub profile_id: i32,
+    pub game_mode: NetGameMode,
+    pub players: u8,
+    pub address: Option<String>,
+    pub port: Option<u16>,
+    pub time_limit: Option<u32>,
+}
+
+impl NetgamePreset {
+    pub fn new(profile_id: i32) -> Self {
+        NetgamePreset {
+            profile_id,
+            game_mode: NetGameMode::Coop,
+            players: 2,
+            address: None,
+            port: None,
+            time_limit: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NetRole {
+    Host { players: u8 },
+    Join { address: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct Netgame {
+    pub net_role: NetRole,
+    pub game_mode: NetGameMode,
+    pub port: Option<u16>,
+    pub time_limit: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlayMode {
+    Launch,
+    DryRun(DryRunFormat),
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::data::truncate_string_end;
+    use crate::{
+        data::{display_time_played, truncate_string_end, MapContents},
+        doom_data::KeyLump,
+    };
 
     #[test]
     fn test_truncate_string_end_weird_author_name() {
@@ -785,4 +1331,33 @@ mod tests {
         assert_eq!(actual.len(), input.len());
         assert_eq!(actual, input);
     }
+
+    #[test]
+    fn test_display_time_played() {
+        assert_eq!(display_time_played(&0), "N/A");
+        assert_eq!(display_time_played(&42), "42s");
+        assert_eq!(display_time_played(&605), "10m 05s");
+        assert_eq!(display_time_played(&7384), "2h 03m 04s");
+    }
+
+    #[test]
+    fn test_map_contents_requires_zdoom() {
+        // Arrange
+        let archive = MapContents {
+            is_archive: true,
+            ..Default::default()
+        };
+        let wad = MapContents {
+            lumps: vec![KeyLump::Dehacked, KeyLump::Umapinfo],
+            ..Default::default()
+        };
+
+        // Act
+        let actual_archive = archive.requires_zdoom();
+        let actual_wad = wad.requires_zdoom();
+
+        // Assert
+        assert!(actual_archive);
+        assert!(!actual_wad);
+    }
 }
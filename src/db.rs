@@ -6,7 +6,7 @@ use sqlx::{
     Sqlite, SqlitePool,
 };
 
-use crate::{constants, data, paths};
+use crate::{constants, data, doom_data, files, paths};
 
 const DB_URL: &str = "sqlite://dcli.db";
 const DB_FILE: &str = "dcli.db";
@@ -73,19 +73,42 @@ pub fn add_engine(engine: &data::Engine) -> Result<sqlx::sqlite::SqliteQueryResu
         let db = get_db().await;
 
         sqlx::query(
-            "INSERT INTO engines (app_name, path, internal_path, version, game_engine_type) VALUES (?,?,?,?,?)",
+            "INSERT INTO engines (app_name, path, internal_path, version, game_engine_type, sha1, md5) VALUES (?,?,?,?,?,?,?)",
         )
         .bind(&engine.app_name)
         .bind(&engine.path)
         .bind(&engine.internal_path)
         .bind(&engine.version)
         .bind(&engine.game_engine_type)
+        .bind(&engine.sha1)
+        .bind(&engine.md5)
         .execute(&db)
         .await
         .wrap_err(format!("Failed to add engine '{:?}", engine))
     })
 }
 
+pub fn update_engine_hashes(
+    id: i32,
+    file_hashes: &files::FileHashes,
+) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query("UPDATE engines SET sha1 = $1, md5 = $2 WHERE id = $3")
+            .bind(&file_hashes.sha1)
+            .bind(&file_hashes.md5)
+            .bind(id)
+            .execute(&db)
+            .await
+            .wrap_err(format!(
+                "Failed to update hashes for engine with id '{}'",
+                id
+            ))
+    })
+}
+
 pub fn delete_engine(path: &str) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.block_on(async {
@@ -178,15 +201,59 @@ pub fn add_iwad(iwad: &data::Iwad) -> Result<sqlx::sqlite::SqliteQueryResult, ey
     runtime.block_on(async {
         let db = get_db().await;
 
-        sqlx::query("INSERT INTO iwads (path, internal_wad_type) VALUES (?,?)")
-            .bind(&iwad.path)
-            .bind(&iwad.internal_wad_type)
+        sqlx::query(
+            "INSERT INTO iwads (path, internal_wad_type, release, name, game_family, sha1, md5) VALUES (?,?,?,?,?,?,?)",
+        )
+        .bind(&iwad.path)
+        .bind(&iwad.internal_wad_type)
+        .bind(&iwad.release)
+        .bind(&iwad.name)
+        .bind(&iwad.game_family)
+        .bind(&iwad.sha1)
+        .bind(&iwad.md5)
             .execute(&db)
             .await
             .wrap_err(format!("Failed to add internal wad '{:?}", iwad))
     })
 }
 
+pub fn update_iwad_identity(
+    id: i32,
+    internal_wad_type: &doom_data::InternalWadType,
+    release: &Option<String>,
+) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query("UPDATE iwads SET internal_wad_type = $1, release = $2 WHERE id = $3")
+            .bind(internal_wad_type)
+            .bind(release)
+            .bind(id)
+            .execute(&db)
+            .await
+            .wrap_err(format!("Failed to update internal wad with id '{}'", id))
+    })
+}
+
+pub fn update_iwad_hashes(
+    id: i32,
+    file_hashes: &files::FileHashes,
+) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query("UPDATE iwads SET sha1 = $1, md5 = $2 WHERE id = $3")
+            .bind(&file_hashes.sha1)
+            .bind(&file_hashes.md5)
+            .bind(id)
+            .execute(&db)
+            .await
+            .wrap_err(format!("Failed to update hashes for iwad with id '{}'", id))
+    })
+}
+
 pub fn delete_iwad(path: &str) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.block_on(async {
@@ -265,23 +332,80 @@ pub fn get_maps() -> Result<Vec<data::Map>, eyre::Report> {
     })
 }
 
+pub fn get_map_display_list() -> Result<Vec<data::MapDisplay>, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        // A Map can be in any of the five Map slots of a play session
+        sqlx::query_as::<_, data::MapDisplay>(
+            "SELECT maps.*, COALESCE((SELECT SUM(duration) FROM play_sessions
+            WHERE maps.id IN (map_id, map_id2, map_id3, map_id4, map_id5)), 0) AS time_played
+            FROM maps ORDER BY title",
+        )
+        .fetch_all(&db)
+        .await
+        .wrap_err("Failed to get list of all maps with time played")
+    })
+}
+
 pub fn add_map(map: &data::Map) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.block_on(async {
         let db = get_db().await;
 
-        sqlx::query("INSERT INTO maps (title, author, path, doomworld_id, doomworld_url) VALUES (?,?,?,?,?)")
+        sqlx::query("INSERT INTO maps (title, author, path, doomworld_id, doomworld_url, sha1, md5) VALUES (?,?,?,?,?,?,?)")
             .bind(&map.title)
             .bind(&map.author)
             .bind(&map.path)
             .bind(map.doomworld_id)
             .bind(&map.doomworld_url)
+            .bind(&map.sha1)
+            .bind(&map.md5)
             .execute(&db)
             .await
             .wrap_err(format!("Failed to add map '{:?}", map))
     })
 }
 
+pub fn update_map_hashes(
+    id: i32,
+    file_hashes: &files::FileHashes,
+) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query("UPDATE maps SET sha1 = $1, md5 = $2 WHERE id = $3")
+            .bind(&file_hashes.sha1)
+            .bind(&file_hashes.md5)
+            .bind(id)
+            .execute(&db)
+            .await
+            .wrap_err(format!("Failed to update hashes for map with id '{}'", id))
+    })
+}
+
+// A Map that can't be read is marked as read, so it isn't tried again on every scan
+pub fn update_map_contents_version(
+    id: i32,
+) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query("UPDATE maps SET contents_version = $1 WHERE id = $2")
+            .bind(constants::MAP_CONTENTS_VERSION)
+            .bind(id)
+            .execute(&db)
+            .await
+            .wrap_err(format!(
+                "Failed to update contents version for map with id '{}'",
+                id
+            ))
+    })
+}
+
 pub fn update_map(map: data::Map) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.block_on(async {
@@ -342,6 +466,19 @@ pub fn get_maps_by_ids(map_ids: data::MapIds) -> Result<Vec<data::Map>, eyre::Re
     Ok(result)
 }
 
+pub fn get_map_by_sha1(sha1: &str) -> Result<data::Map, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query_as::<_, data::Map>("SELECT * FROM maps WHERE sha1 = $1")
+            .bind(sha1)
+            .fetch_one(&db)
+            .await
+            .wrap_err(format!("Failed to get Map with SHA-1 '{}'", sha1))
+    })
+}
+
 pub fn get_map_by_path(path: &str) -> Result<data::Map, eyre::Report> {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.block_on(async {
@@ -390,8 +527,9 @@ fn add_app_settings(
         sqlx::query(
             "INSERT INTO app_settings (default_profile_id, last_profile_id, default_engine_id,
                 default_iwad_id, default_editor_id, engine_search_folder, iwad_search_folder,
-                map_search_folder, editor_search_folder, menu_mode, use_doomworld_api)
-                VALUES (?,?,?,?,?,?,?,?,?,?,?)",
+                map_search_folder, editor_search_folder, menu_mode, use_doomworld_api,
+                wait_for_engine, pre_launch_hook, post_exit_hook, post_download_hook)
+                VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)",
         )
         .bind(app_settings.default_profile_id)
         .bind(app_settings.last_profile_id)
@@ -404,6 +542,10 @@ fn add_app_settings(
         .bind(&app_settings.editor_search_folder)
         .bind(&app_settings.menu_mode)
         .bind(app_settings.use_doomworld_api)
+        .bind(app_settings.wait_for_engine)
+        .bind(&app_settings.pre_launch_hook)
+        .bind(&app_settings.post_exit_hook)
+        .bind(&app_settings.post_download_hook)
         .execute(&db)
         .await
         .wrap_err(format!("Failed to add app settings '{:?}", app_settings))
@@ -421,7 +563,9 @@ fn update_app_settings(
             "UPDATE app_settings SET default_profile_id = $2, last_profile_id = $3,
         default_engine_id = $4, default_iwad_id = $5, default_editor_id = $6,
         engine_search_folder = $7, iwad_search_folder = $8, map_search_folder = $9,
-        editor_search_folder = $10, menu_mode = $11, use_doomworld_api = $12
+        editor_search_folder = $10, menu_mode = $11, use_doomworld_api = $12,
+        wait_for_engine = $13, pre_launch_hook = $14, post_exit_hook = $15,
+        post_download_hook = $16
         WHERE id = $1",
         )
         .bind(app_settings.id)
@@ -436,6 +580,10 @@ fn update_app_settings(
         .bind(&app_settings.editor_search_folder)
         .bind(&app_settings.menu_mode)
         .bind(app_settings.use_doomworld_api)
+        .bind(app_settings.wait_for_engine)
+        .bind(&app_settings.pre_launch_hook)
+        .bind(&app_settings.post_exit_hook)
+        .bind(&app_settings.post_download_hook)
         .execute(&db)
         .await
         .wrap_err(format!("Failed to update app settings '{:?}", app_settings))
@@ -525,6 +673,16 @@ pub fn get_app_settings_display() -> Result<data::AppSettingsDisplay, eyre::Repo
         editor_search_folder,
         menu_mode: app_settings.menu_mode.to_string(),
         use_doomworld_api: app_settings.use_doomworld_api,
+        wait_for_engine: app_settings.wait_for_engine,
+        pre_launch_hook: app_settings
+            .pre_launch_hook
+            .unwrap_or(constants::DEFAULT_NOT_SET.to_string()),
+        post_exit_hook: app_settings
+            .post_exit_hook
+            .unwrap_or(constants::DEFAULT_NOT_SET.to_string()),
+        post_download_hook: app_settings
+            .post_download_hook
+            .unwrap_or(constants::DEFAULT_NOT_SET.to_string()),
     })
 }
 
@@ -538,8 +696,9 @@ pub fn add_profile(
         sqlx::query(
             "INSERT INTO profiles (name, engine_id, iwad_id,
             map_id, map_id2, map_id3, map_id4, map_id5, additional_arguments,
-            date_created, date_edited, date_last_run, save_game, run_count)
-            VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?)",
+            date_created, date_edited, date_last_run, save_game, run_count, comp_level,
+            environment, working_directory, wrapper_command, pre_launch_hook, post_exit_hook)
+            VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)",
         )
         .bind(&profile.name)
         .bind(profile.engine_id)
@@ -555,6 +714,12 @@ pub fn add_profile(
         .bind(profile.date_last_run)
         .bind(&profile.save_game)
         .bind(profile.run_count)
+        .bind(&profile.comp_level)
+        .bind(&profile.environment)
+        .bind(&profile.working_directory)
+        .bind(&profile.wrapper_command)
+        .bind(&profile.pre_launch_hook)
+        .bind(&profile.post_exit_hook)
         .execute(&db)
         .await
         .wrap_err(format!("Failed to add profile '{:?}", profile))
@@ -572,7 +737,9 @@ pub fn update_profile(
             "UPDATE profiles SET name = $2, engine_id = $3, iwad_id = $4,
             map_id = $5, map_id2 = $6, map_id3 = $7, map_id4 = $8, map_id5 = $9,
             additional_arguments = $10, date_created = $11, date_edited = $12,
-            date_last_run = $13, save_game = $14, run_count = $15 WHERE id=$1",
+            date_last_run = $13, save_game = $14, run_count = $15, comp_level = $16,
+            environment = $17, working_directory = $18, wrapper_command = $19,
+            pre_launch_hook = $20, post_exit_hook = $21 WHERE id=$1",
         )
         .bind(profile.id)
         .bind(&profile.name)
@@ -589,6 +756,12 @@ pub fn update_profile(
         .bind(profile.date_last_run)
         .bind(&profile.save_game)
         .bind(profile.run_count)
+        .bind(&profile.comp_level)
+        .bind(&profile.environment)
+        .bind(&profile.working_directory)
+        .bind(&profile.wrapper_command)
+        .bind(&profile.pre_launch_hook)
+        .bind(&profile.post_exit_hook)
         .execute(&db)
         .await
         .wrap_err(format!(
@@ -707,6 +880,7 @@ fn get_profile_display(
     engine: data::Engine,
     iwad: data::Iwad,
     maps: Vec<data::Map>,
+    time_played: i64,
 ) -> data::ProfileDisplay {
     data::ProfileDisplay {
         id: profile.id,
@@ -746,6 +920,13 @@ fn get_profile_display(
         date_last_run: profile.date_last_run,
         save_game: profile.save_game.unwrap_or_default(),
         run_count: profile.run_count,
+        time_played,
+        comp_level: profile.comp_level,
+        environment: profile.environment.unwrap_or_default(),
+        working_directory: profile.working_directory.unwrap_or_default(),
+        wrapper_command: profile.wrapper_command.unwrap_or_default(),
+        pre_launch_hook: profile.pre_launch_hook.unwrap_or_default(),
+        post_exit_hook: profile.post_exit_hook.unwrap_or_default(),
     }
 }
 
@@ -759,6 +940,7 @@ pub fn get_profile_display_list(
     let engines = get_engines()?;
     let iwads = get_iwads()?;
     let maps = get_maps()?;
+    let profile_time_played = get_time_played_by_profile()?;
 
     let default_engine = data::Engine::default();
     let default_iwad = data::Iwad::default();
@@ -794,6 +976,11 @@ pub fn get_profile_display_list(
             .iter()
             .find(|p| p.id == profile.map_id5.unwrap_or(0))
             .unwrap_or(&default_map);
+        let time_played = profile_time_played
+            .iter()
+            .find(|(profile_id, _)| *profile_id == profile.id)
+            .map(|(_, time_played)| *time_played)
+            .unwrap_or(0);
 
         profile_list.push(get_profile_display(
             profile.clone(),
@@ -806,6 +993,7 @@ pub fn get_profile_display_list(
                 map4.clone(),
                 map5.clone(),
             ],
+            time_played,
         ));
     }
 
@@ -844,14 +1032,83 @@ pub fn get_profile_display_by_id(id: i32) -> Result<data::ProfileDisplay, eyre::
         None => data::Map::default(),
     };
 
+    let time_played = get_time_played_for_profile(id)?;
+
     Ok(get_profile_display(
         profile,
         engine,
         iwad,
         vec![map, map2, map3, map4, map5],
+        time_played,
     ))
 }
 
+pub fn add_play_session(
+    play_session: &data::PlaySession,
+) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query(
+            "INSERT INTO play_sessions (profile_id, engine_id, iwad_id,
+            map_id, map_id2, map_id3, map_id4, map_id5,
+            date_started, date_ended, duration, exit_code)
+            VALUES (?,?,?,?,?,?,?,?,?,?,?,?)",
+        )
+        .bind(play_session.profile_id)
+        .bind(play_session.engine_id)
+        .bind(play_session.iwad_id)
+        .bind(play_session.map_id)
+        .bind(play_session.map_id2)
+        .bind(play_session.map_id3)
+        .bind(play_session.map_id4)
+        .bind(play_session.map_id5)
+        .bind(play_session.date_started)
+        .bind(play_session.date_ended)
+        .bind(play_session.duration)
+        .bind(play_session.exit_code)
+        .execute(&db)
+        .await
+        .wrap_err(format!("Failed to add play session '{:?}", play_session))
+    })
+}
+
+fn get_time_played_by_profile() -> Result<Vec<(i32, i64)>, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query_as::<_, (i32, i64)>(
+            "SELECT profile_id, SUM(duration) FROM play_sessions
+            WHERE profile_id IS NOT NULL GROUP BY profile_id",
+        )
+        .fetch_all(&db)
+        .await
+        .wrap_err("Failed to get time played for profiles")
+    })
+}
+
+fn get_time_played_for_profile(profile_id: i32) -> Result<i64, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        let result: (i64,) = sqlx::query_as(
+            "SELECT COALESCE(SUM(duration), 0) FROM play_sessions WHERE profile_id = $1",
+        )
+        .bind(profile_id)
+        .fetch_one(&db)
+        .await
+        .wrap_err(format!(
+            "Failed to get time played for profile with id '{}'",
+            profile_id
+        ))?;
+
+        Ok(result.0)
+    })
+}
+
 pub fn add_queue(queue: data::Queue) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     runtime.block_on(async {
@@ -1106,7 +1363,7 @@ fn add_play_settings(
         sqlx::query(
             "INSERT INTO play_settings (comp_level, config_file, fast_monsters, no_monsters,
             respawn_monsters, warp, skill, turbo, timer, width, height, full_screen,
-            windowed, additional_arguments) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?)",
+            windowed, additional_arguments, record_demo) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)",
         )
         .bind(&play_settings.comp_level)
         .bind(&play_settings.config_file)
@@ -1122,6 +1379,7 @@ fn add_play_settings(
         .bind(play_settings.full_screen)
         .bind(play_settings.windowed)
         .bind(&play_settings.additional_arguments)
+        .bind(play_settings.record_demo)
         .execute(&db)
         .await
         .wrap_err(format!("Failed to add app settings '{:?}", play_settings))
@@ -1140,7 +1398,7 @@ fn update_play_settings(
                 fast_monsters = $4, no_monsters = $5, respawn_monsters = $6,
                 warp = $7, skill = $8, turbo = $9, timer = $10, width = $11,
                 height = $12, full_screen = $13, windowed = $14,
-                additional_arguments = $15
+                additional_arguments = $15, record_demo = $16
                 WHERE id=$1",
         )
         .bind(play_settings.id)
@@ -1158,6 +1416,7 @@ fn update_play_settings(
         .bind(play_settings.full_screen)
         .bind(play_settings.windowed)
         .bind(&play_settings.additional_arguments)
+        .bind(play_settings.record_demo)
         .execute(&db)
         .await
         .wrap_err(format!(
@@ -1285,3 +1544,245 @@ pub fn get_editor_count() -> Result<i64, eyre::Report> {
         Ok(result.0)
     })
 }
+
+pub fn add_demo(demo: &data::Demo) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query(
+            "INSERT INTO demos (name, path, profile_id, engine_id, iwad_id,
+            map_id, map_id2, map_id3, map_id4, map_id5, warp, skill, date_recorded, comp_level)
+            VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?)",
+        )
+        .bind(&demo.name)
+        .bind(&demo.path)
+        .bind(demo.profile_id)
+        .bind(demo.engine_id)
+        .bind(demo.iwad_id)
+        .bind(demo.map_id)
+        .bind(demo.map_id2)
+        .bind(demo.map_id3)
+        .bind(demo.map_id4)
+        .bind(demo.map_id5)
+        .bind(&demo.warp)
+        .bind(demo.skill)
+        .bind(demo.date_recorded)
+        .bind(&demo.comp_level)
+        .execute(&db)
+        .await
+        .wrap_err(format!("Failed to add demo '{:?}", demo))
+    })
+}
+
+pub fn delete_demo(id: i32) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query("DELETE FROM demos WHERE id=$1")
+            .bind(id)
+            .execute(&db)
+            .await
+            .wrap_err(format!("Failed to delete demo with id '{}'", id))
+    })
+}
+
+pub fn get_demos() -> Result<Vec<data::Demo>, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query_as::<_, data::Demo>("SELECT * FROM demos ORDER BY date_recorded DESC")
+            .fetch_all(&db)
+            .await
+            .wrap_err("Failed to get list of all demos")
+    })
+}
+
+pub fn get_demo_by_name(name: &str) -> Result<data::Demo, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query_as::<_, data::Demo>("SELECT * FROM demos WHERE name = $1 COLLATE NOCASE")
+            .bind(name)
+            .fetch_one(&db)
+            .await
+            .wrap_err(format!("Failed to get demo with name '{}'", name))
+    })
+}
+
+pub fn get_netgame_preset(profile_id: i32) -> Result<Option<data::NetgamePreset>, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query_as::<_, data::NetgamePreset>(
+            "SELECT * FROM netgame_presets WHERE profile_id = $1",
+        )
+        .bind(profile_id)
+        .fetch_optional(&db)
+        .await
+        .wrap_err(format!(
+            "Failed to get netgame preset for profile id '{}'",
+            profile_id
+        ))
+    })
+}
+
+pub fn save_netgame_preset(
+    netgame_preset: &data::NetgamePreset,
+) -> Result<sqlx::sqlite::SqliteQueryResult, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query(
+            "INSERT INTO netgame_presets (profile_id, game_mode, players, address, port, time_limit)
+            VALUES (?,?,?,?,?,?)
+            ON CONFLICT (profile_id) DO UPDATE SET game_mode = excluded.game_mode,
+            players = excluded.players, address = excluded.address, port = excluded.port,
+            time_limit = excluded.time_limit",
+        )
+        .bind(netgame_preset.profile_id)
+        .bind(&netgame_preset.game_mode)
+        .bind(netgame_preset.players)
+        .bind(&netgame_preset.address)
+        .bind(netgame_preset.port)
+        .bind(netgame_preset.time_limit)
+        .execute(&db)
+        .await
+        .wrap_err(format!(
+            "Failed to save netgame preset '{:?}'",
+            netgame_preset
+        ))
+    })
+}
+
+pub fn save_map_contents(
+    map_id: i32,
+    map_contents: &data::MapContents,
+) -> Result<(), eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+        let mut transaction = db.begin().await?;
+
+        // Replace whatever was found the last time the Map was read
+        sqlx::query("DELETE FROM map_levels WHERE map_id = $1")
+            .bind(map_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query("DELETE FROM map_lumps WHERE map_id = $1")
+            .bind(map_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query("DELETE FROM map_level_stats WHERE map_id = $1")
+            .bind(map_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        for (position, level) in map_contents.levels.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO map_levels (map_id, position, lump_name, level_format, level_name) VALUES (?,?,?,?,?)",
+            )
+            .bind(map_id)
+            .bind(position as i32)
+            .bind(&level.lump_name)
+            .bind(&level.level_format)
+            .bind(&level.level_name)
+            .execute(&mut *transaction)
+            .await?;
+        }
+        for lump in &map_contents.lumps {
+            sqlx::query("INSERT INTO map_lumps (map_id, lump_name) VALUES (?,?)")
+                .bind(map_id)
+                .bind(lump)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        for level_stats in &map_contents.level_stats {
+            sqlx::query(
+                "INSERT INTO map_level_stats (map_id, lump_name, skill_tier, monsters, monster_hit_points, items, weapons, ammo, keys, secrets) VALUES (?,?,?,?,?,?,?,?,?,?)",
+            )
+            .bind(map_id)
+            .bind(&level_stats.lump_name)
+            .bind(&level_stats.skill_tier)
+            .bind(level_stats.monsters)
+            .bind(level_stats.monster_hit_points)
+            .bind(level_stats.items)
+            .bind(level_stats.weapons)
+            .bind(level_stats.ammo)
+            .bind(level_stats.keys)
+            .bind(level_stats.secrets)
+            .execute(&mut *transaction)
+            .await?;
+        }
+        let comp_level = map_contents.recommended_comp_level();
+        sqlx::query(
+            "UPDATE maps SET required_iwad = $2, inferred_iwad = $3, requires_zdoom = $4, comp_level = $5, comp_level_confidence = $6, contents_version = $7 WHERE id = $1",
+        )
+        .bind(map_id)
+        .bind(&map_contents.required_iwad)
+        .bind(&map_contents.inferred_iwad)
+        .bind(map_contents.requires_zdoom())
+        .bind(comp_level.as_ref().map(|evidence| &evidence.comp_level))
+        .bind(comp_level.as_ref().map(|evidence| &evidence.confidence))
+        .bind(constants::MAP_CONTENTS_VERSION)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await
+    })
+    .wrap_err(format!("Failed to save contents of map id '{}'", map_id))
+}
+
+pub fn get_level_stats_list() -> Result<Vec<data::LevelStats>, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        sqlx::query_as::<_, data::LevelStats>("SELECT * FROM map_level_stats ORDER BY map_id, id")
+            .fetch_all(&db)
+            .await
+            .wrap_err("Failed to get list of level stats")
+    })
+}
+
+pub fn get_map_contents(map_id: i32) -> Result<data::MapContents, eyre::Report> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let db = get_db().await;
+
+        let levels = sqlx::query_as::<_, data::MapLevel>(
+            "SELECT lump_name, level_format, level_name FROM map_levels WHERE map_id = $1 ORDER BY position",
+        )
+        .bind(map_id)
+        .fetch_all(&db)
+        .await
+        .wrap_err(format!("Failed to get levels of map id '{}'", map_id))?;
+
+        let lumps: Vec<(doom_data::KeyLump,)> =
+            sqlx::query_as("SELECT lump_name FROM map_lumps WHERE map_id = $1 ORDER BY id")
+                .bind(map_id)
+                .fetch_all(&db)
+                .await
+                .wrap_err(format!("Failed to get lumps of map id '{}'", map_id))?;
+
+        let level_stats = sqlx::query_as::<_, data::LevelStats>(
+            "SELECT * FROM map_level_stats WHERE map_id = $1 ORDER BY id",
+        )
+        .bind(map_id)
+        .fetch_all(&db)
+        .await
+        .wrap_err(format!("Failed to get level stats of map id '{}'", map_id))?;
+
+        Ok(data::MapContents {
+            levels,
+            lumps: lumps.into_iter().map(|lump| lump.0).collect(),
+            level_stats,
+            ..Default::default()
+        })
+    })
+}
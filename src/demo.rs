@@ -0,0 +1,503 @@
+use chrono::{DateTime, Local};
+use strum_macros::Display;
+
+use crate::{data, doom_data, paths};
+
+// Recorded demos are saved to the app data folder, in a folder per Profile. The file
+// name says what was played, so demos are still recognisable outside of dcli.
+
+const DEMO_FOLDER: &str = "demos";
+const NO_PROFILE_FOLDER: &str = "no-profile";
+pub const DEMO_EXTENSION: &str = "lmp";
+
+pub fn get_demo_folder(profile_name: Option<&str>) -> String {
+    let folder_name = match profile_name {
+        Some(profile_name) => paths::get_safe_file_name(profile_name),
+        None => NO_PROFILE_FOLDER.to_string(),
+    };
+    paths::get_full_path(
+        &paths::get_full_path(&paths::get_app_data_dir(), DEMO_FOLDER),
+        &folder_name,
+    )
+}
+
+pub fn get_demo_name(
+    profile_name: Option<&str>,
+    warp: Option<&str>,
+    skill: Option<u8>,
+    date_recorded: &DateTime<Local>,
+) -> String {
+    let mut name_parts: Vec<String> = Vec::new();
+    if let Some(profile_name) = profile_name {
+        name_parts.push(paths::get_safe_file_name(profile_name));
+    }
+    if let Some(map_slot) = warp.and_then(get_map_slot) {
+        name_parts.push(map_slot);
+    }
+    if let Some(skill) = skill {
+        name_parts.push(format!("skill{}", skill));
+    }
+    name_parts.push(date_recorded.format("%Y%m%d_%H%M%S").to_string());
+
+    name_parts.join("_")
+}
+
+fn get_map_slot(warp: &str) -> Option<String> {
+    // Warp is either "map" or "episode map"
+    let warp_parts: Vec<u8> = warp
+        .split_whitespace()
+        .filter_map(|part| part.parse::<u8>().ok())
+        .collect();
+
+    match warp_parts.as_slice() {
+        [map] => Some(format!("map{:02}", map)),
+        [episode, map] => Some(format!("e{}m{}", episode, map)),
+        _ => None,
+    }
+}
+
+// Demo headers come in three flavours. Vanilla demos start with the game version (or
+// the skill, before v1.4). Boom and its descendants follow the version with a signature,
+// a block of game options and room for 32 players. dsda-doom and PrBoom+um can put an
+// extension block in front of either, which names the UMAPINFO map being played.
+
+const DEMO_EXTENSION_MARKER: u8 = 255;
+const DEMO_EXTENSION_SIGNATURE: &[u8] = b"PR+UM";
+const DEMO_EXTENSION_UMAPINFO: &str = "UMAPINFO";
+const BOOM_SIGNATURE_SIZE: usize = 6;
+const BOOM_OPTIONS_SIZE: usize = 64;
+const BOOM_V200_OPTIONS_SIZE: usize = 256;
+const BOOM_PLAYERS_SIZE: usize = 32;
+const VANILLA_PLAYERS_SIZE: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Display)]
+pub enum DemoFormat {
+    Vanilla,
+    Boom,
+    #[strum(serialize = "LxDoom")]
+    LxDoom,
+    #[strum(serialize = "MBF")]
+    Mbf,
+    #[strum(serialize = "PrBoom")]
+    PrBoom,
+    #[strum(serialize = "PrBoom+")]
+    PrBoomPlus,
+    #[strum(serialize = "MBF21")]
+    Mbf21,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DemoHeader {
+    pub demo_format: DemoFormat,
+    pub version: u8,
+    // Skill as used on the command line, 1 to 5
+    pub skill: u8,
+    pub episode: u8,
+    pub map: u8,
+    pub deathmatch: u8,
+    pub respawn_monsters: bool,
+    pub fast_monsters: bool,
+    pub no_monsters: bool,
+    pub player_count: u8,
+    pub comp_level: data::CompLevel,
+    pub umapinfo_map: Option<String>,
+}
+
+impl DemoHeader {
+    pub fn is_episodic(&self) -> Option<bool> {
+        // Doom II demos always have an episode of 1, so short map numbers are ambiguous
+        if let Some(umapinfo_map) = &self.umapinfo_map {
+            return Some(!umapinfo_map.to_uppercase().starts_with("MAP"));
+        }
+        if self.episode > 1 {
+            Some(true)
+        } else if self.map > 9 {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_warp(&self, internal_wad_type: &doom_data::InternalWadType) -> String {
+        match internal_wad_type {
+            doom_data::InternalWadType::Doom | doom_data::InternalWadType::DoomShareware => {
+                format!("{} {}", self.episode, self.map)
+            }
+            _ => self.map.to_string(),
+        }
+    }
+
+    pub fn get_comp_level(
+        &self,
+        internal_wad_type: &doom_data::InternalWadType,
+    ) -> data::CompLevel {
+        // A v1.9 demo plays back with whatever executable matches the IWAD
+        if self.comp_level != data::CompLevel::DoomV19 {
+            return self.comp_level.clone();
+        }
+        match internal_wad_type {
+            doom_data::InternalWadType::Doom => data::CompLevel::UltimateDoom,
+            doom_data::InternalWadType::Tnt | doom_data::InternalWadType::Plutonia => {
+                data::CompLevel::FinalDoom
+            }
+            _ => data::CompLevel::DoomV19,
+        }
+    }
+}
+
+struct DemoReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> DemoReader<'a> {
+    fn read_u8(&mut self) -> Result<u8, eyre::Report> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], eyre::Report> {
+        if self.position + count > self.bytes.len() {
+            return Err(eyre::eyre!(
+                "Demo header is too short, expected at least {} bytes",
+                self.position + count
+            ));
+        }
+        let bytes = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn peek_u8(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+}
+
+pub fn parse_demo_header(bytes: &[u8]) -> Result<DemoHeader, eyre::Report> {
+    let mut reader = DemoReader { bytes, position: 0 };
+    let umapinfo_map = read_demo_extensions(&mut reader)?;
+
+    let version = reader.read_u8()?;
+    let mut header = match version {
+        0..=4 => read_old_vanilla_header(&mut reader, version)?,
+        104..=111 => read_vanilla_header(&mut reader, version)?,
+        200..=203 | 210..=214 | 221 => read_boom_header(&mut reader, version)?,
+        _ => return Err(eyre::eyre!("Unknown demo version '{}'", version)),
+    };
+    header.umapinfo_map = umapinfo_map;
+
+    Ok(header)
+}
+
+fn read_demo_extensions(reader: &mut DemoReader) -> Result<Option<String>, eyre::Report> {
+    if reader.peek_u8() != Some(DEMO_EXTENSION_MARKER) {
+        return Ok(None);
+    }
+    reader.read_u8()?;
+    if reader.read_bytes(DEMO_EXTENSION_SIGNATURE.len())? != DEMO_EXTENSION_SIGNATURE {
+        return Err(eyre::eyre!("Unknown demo extension signature"));
+    }
+    let extension_version = reader.read_u8()?;
+    if extension_version != 1 {
+        return Err(eyre::eyre!(
+            "Unknown demo extension version '{}'",
+            extension_version
+        ));
+    }
+
+    let extension_count = u16::from_le_bytes([reader.read_u8()?, reader.read_u8()?]);
+    let mut has_umapinfo = false;
+    for _ in 0..extension_count {
+        let length = reader.read_u8()? as usize;
+        let extension = String::from_utf8_lossy(reader.read_bytes(length)?).to_string();
+        has_umapinfo |= extension == DEMO_EXTENSION_UMAPINFO;
+    }
+
+    if !has_umapinfo {
+        return Ok(None);
+    }
+    let map_name = reader.read_bytes(8)?;
+    Ok(Some(get_lump_name(map_name)))
+}
+
+fn read_old_vanilla_header(reader: &mut DemoReader, skill: u8) -> Result<DemoHeader, eyre::Report> {
+    // Before v1.4 there was no version, the demo started with the skill
+    let episode = reader.read_u8()?;
+    let map = reader.read_u8()?;
+    let player_count = get_player_count(reader.read_bytes(VANILLA_PLAYERS_SIZE)?);
+
+    Ok(DemoHeader {
+        demo_format: DemoFormat::Vanilla,
+        version: skill,
+        skill: skill + 1,
+        episode,
+        map,
+        deathmatch: 0,
+        respawn_monsters: false,
+        fast_monsters: false,
+        no_monsters: false,
+        player_count,
+        comp_level: data::CompLevel::DoomV12,
+        umapinfo_map: None,
+    })
+}
+
+fn read_vanilla_header(reader: &mut DemoReader, version: u8) -> Result<DemoHeader, eyre::Report> {
+    let skill = reader.read_u8()?;
+    let episode = reader.read_u8()?;
+    let map = reader.read_u8()?;
+    let deathmatch = reader.read_u8()?;
+    let respawn_monsters = reader.read_u8()? != 0;
+    let fast_monsters = reader.read_u8()? != 0;
+    let no_monsters = reader.read_u8()? != 0;
+    let _console_player = reader.read_u8()?;
+    let player_count = get_player_count(reader.read_bytes(VANILLA_PLAYERS_SIZE)?);
+
+    let comp_level = match version {
+        104..=106 => data::CompLevel::DoomV1666,
+        _ if episode == 4 => data::CompLevel::UltimateDoom,
+        _ => data::CompLevel::DoomV19,
+    };
+
+    Ok(DemoHeader {
+        demo_format: DemoFormat::Vanilla,
+        version,
+        skill: skill + 1,
+        episode,
+        map,
+        deathmatch,
+        respawn_monsters,
+        fast_monsters,
+        no_monsters,
+        player_count,
+        comp_level,
+        umapinfo_map: None,
+    })
+}
+
+fn read_boom_header(reader: &mut DemoReader, version: u8) -> Result<DemoHeader, eyre::Report> {
+    // The second byte of the signature tells LxDoom ("\x1dBoom\xe6") from MBF ("\x1dMBF\xe6\0")
+    let signature = reader.read_bytes(BOOM_SIGNATURE_SIZE)?;
+    let is_lxdoom = version == 203 && signature[1] != b'M';
+
+    // Every version has a compatibility byte after the signature, except LxDoom's
+    let compatibility = match is_lxdoom {
+        true => false,
+        false => reader.read_u8()? != 0,
+    };
+
+    let (demo_format, comp_level) = match version {
+        200 | 201 if compatibility => (DemoFormat::Boom, data::CompLevel::Boom),
+        200 | 201 => (DemoFormat::Boom, data::CompLevel::BoomV201),
+        202 if compatibility => (DemoFormat::Boom, data::CompLevel::Boom),
+        202 => (DemoFormat::Boom, data::CompLevel::BoomV202),
+        203 if is_lxdoom => (DemoFormat::LxDoom, data::CompLevel::LxDoom),
+        203 => (DemoFormat::Mbf, data::CompLevel::Mbf),
+        210 => (DemoFormat::PrBoom, data::CompLevel::PrBoomV203),
+        211 => (DemoFormat::PrBoom, data::CompLevel::PrBoomV210),
+        212 => (DemoFormat::PrBoom, data::CompLevel::PrBoomV211),
+        213 => (DemoFormat::PrBoom, data::CompLevel::PrBoomV23),
+        // PrBoom+ writes 214 for both complevel 16 and 17
+        214 => (DemoFormat::PrBoomPlus, data::CompLevel::PrBoomV24),
+        221 => (DemoFormat::Mbf21, data::CompLevel::Mbf21),
+        _ => return Err(eyre::eyre!("Unsupported demo version '{}'", version)),
+    };
+
+    let skill = reader.read_u8()?;
+    let episode = reader.read_u8()?;
+    let map = reader.read_u8()?;
+    let deathmatch = reader.read_u8()?;
+    let _console_player = reader.read_u8()?;
+
+    let options_size = match version {
+        200 => BOOM_V200_OPTIONS_SIZE,
+        _ => BOOM_OPTIONS_SIZE,
+    };
+    let options = reader.read_bytes(options_size)?;
+    let player_count = get_player_count(reader.read_bytes(BOOM_PLAYERS_SIZE)?);
+
+    Ok(DemoHeader {
+        demo_format,
+        version,
+        skill: skill + 1,
+        episode,
+        map,
+        deathmatch,
+        respawn_monsters: options[6] != 0,
+        fast_monsters: options[7] != 0,
+        no_monsters: options[8] != 0,
+        player_count,
+        comp_level,
+        umapinfo_map: None,
+    })
+}
+
+fn get_player_count(players_in_game: &[u8]) -> u8 {
+    players_in_game
+        .iter()
+        .filter(|&&player| player != 0)
+        .count() as u8
+}
+
+fn get_lump_name(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|&&byte| byte != 0)
+        .map(|&byte| byte as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use crate::{
+        data,
+        demo::{get_demo_name, parse_demo_header, read_boom_header, DemoFormat, DemoReader},
+    };
+
+    fn get_boom_header(version: u8, signature: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![version];
+        bytes.extend_from_slice(signature);
+        // Compatibility, missing from LxDoom demos
+        if signature != b"\x1dBoom\xe6" || version != 203 {
+            bytes.push(0);
+        }
+        // Skill, episode, map, deathmatch, console player
+        bytes.extend_from_slice(&[3, 1, 15, 0, 0]);
+        let mut options = vec![0; 64];
+        options[7] = 1;
+        bytes.extend_from_slice(&options);
+        let mut players = vec![0; 32];
+        players[0] = 1;
+        players[1] = 1;
+        bytes.extend_from_slice(&players);
+        bytes
+    }
+
+    #[test]
+    fn test_get_demo_name() {
+        // Arrange
+        let date_recorded = Local.with_ymd_and_hms(2024, 3, 9, 21, 5, 7).unwrap();
+
+        // Act
+        let actual_map = get_demo_name(Some("Sunlust UV"), Some("7"), Some(4), &date_recorded);
+        let actual_episode = get_demo_name(None, Some("1 4"), None, &date_recorded);
+
+        // Assert
+        assert_eq!(actual_map, "Sunlust_UV_map07_skill4_20240309_210507");
+        assert_eq!(actual_episode, "e1m4_20240309_210507");
+    }
+
+    #[test]
+    fn test_parse_demo_header_vanilla() {
+        // Arrange
+        let bytes = [109, 3, 4, 2, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0x80];
+
+        // Act
+        let actual = parse_demo_header(&bytes).unwrap();
+
+        // Assert
+        assert_eq!(actual.demo_format, DemoFormat::Vanilla);
+        assert_eq!(actual.skill, 4);
+        assert_eq!((actual.episode, actual.map), (4, 2));
+        assert_eq!(actual.player_count, 1);
+        assert_eq!(actual.comp_level, data::CompLevel::UltimateDoom);
+        assert_eq!(actual.is_episodic(), Some(true));
+    }
+
+    #[test]
+    fn test_parse_demo_header_boom_and_mbf() {
+        // Arrange
+        let boom = get_boom_header(202, b"\x1dBoom\xe6");
+        let mbf = get_boom_header(203, b"\x1dMBF\xe6\0");
+        let mbf21 = get_boom_header(221, b"\x1dMBF\xe6\0");
+
+        // Act
+        let actual_boom = parse_demo_header(&boom).unwrap();
+        let actual_mbf = parse_demo_header(&mbf).unwrap();
+        let actual_mbf21 = parse_demo_header(&mbf21).unwrap();
+
+        // Assert
+        assert_eq!(actual_boom.comp_level, data::CompLevel::BoomV202);
+        assert_eq!(actual_mbf.demo_format, DemoFormat::Mbf);
+        assert_eq!(actual_mbf.comp_level, data::CompLevel::Mbf);
+        assert_eq!(actual_mbf21.comp_level, data::CompLevel::Mbf21);
+        assert_eq!(actual_mbf21.skill, 4);
+        assert_eq!(actual_mbf21.map, 15);
+        assert_eq!(actual_mbf21.player_count, 2);
+        assert!(actual_mbf21.fast_monsters);
+        assert_eq!(actual_mbf21.is_episodic(), Some(false));
+    }
+
+    #[test]
+    fn test_parse_demo_header_lxdoom_and_prboom() {
+        // Arrange
+        let lxdoom = get_boom_header(203, b"\x1dBoom\xe6");
+        let prboom_v203 = get_boom_header(210, b"\x1dBoom\xe6");
+        let prboom = get_boom_header(212, b"\x1dBoom\xe6");
+        let prboom_v23 = get_boom_header(213, b"\x1dBoom\xe6");
+        let prboom_plus = get_boom_header(214, b"\x1dBoom\xe6");
+        let unsupported = get_boom_header(215, b"\x1dBoom\xe6");
+
+        // Act
+        let actual_lxdoom = parse_demo_header(&lxdoom).unwrap();
+        let actual_prboom_v203 = parse_demo_header(&prboom_v203).unwrap();
+        let actual_prboom = parse_demo_header(&prboom).unwrap();
+        let actual_prboom_v23 = parse_demo_header(&prboom_v23).unwrap();
+        let actual_prboom_plus = parse_demo_header(&prboom_plus).unwrap();
+        let actual_unsupported = read_boom_header(
+            &mut DemoReader {
+                bytes: &unsupported[1..],
+                position: 0,
+            },
+            215,
+        );
+
+        // Assert
+        assert_eq!(actual_lxdoom.demo_format, DemoFormat::LxDoom);
+        assert_eq!(actual_lxdoom.comp_level, data::CompLevel::LxDoom);
+        assert_eq!(actual_lxdoom.skill, 4);
+        assert_eq!(actual_lxdoom.map, 15);
+        assert_eq!(actual_lxdoom.player_count, 2);
+        assert!(actual_lxdoom.fast_monsters);
+        assert_eq!(actual_prboom_v203.comp_level, data::CompLevel::PrBoomV203);
+        assert_eq!(actual_prboom.demo_format, DemoFormat::PrBoom);
+        assert_eq!(actual_prboom.comp_level, data::CompLevel::PrBoomV211);
+        assert_eq!(actual_prboom.map, 15);
+        assert_eq!(actual_prboom_v23.comp_level, data::CompLevel::PrBoomV23);
+        assert_eq!(actual_prboom_plus.demo_format, DemoFormat::PrBoomPlus);
+        assert_eq!(actual_prboom_plus.comp_level, data::CompLevel::PrBoomV24);
+        assert!(actual_unsupported.is_err());
+    }
+
+    #[test]
+    fn test_parse_demo_header_umapinfo_extension() {
+        // Arrange
+        let mut bytes = vec![255];
+        bytes.extend_from_slice(b"PR+UM");
+        bytes.extend_from_slice(&[1, 1, 0, 8]);
+        bytes.extend_from_slice(b"UMAPINFO");
+        bytes.extend_from_slice(b"E5M1\0\0\0\0");
+        bytes.extend(get_boom_header(221, b"\x1dMBF\xe6\0"));
+
+        // Act
+        let actual = parse_demo_header(&bytes).unwrap();
+
+        // Assert
+        assert_eq!(actual.umapinfo_map, Some("E5M1".to_string()));
+        assert_eq!(actual.comp_level, data::CompLevel::Mbf21);
+        assert_eq!(actual.is_episodic(), Some(true));
+    }
+
+    #[test]
+    fn test_parse_demo_header_unknown_version() {
+        // Arrange
+        let bytes = [150, 0, 0, 0];
+
+        // Act
+        let actual = parse_demo_header(&bytes);
+
+        // Assert
+        assert!(actual.is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use std::env;
 
-use strum_macros::Display;
+use clap::ValueEnum;
+use strum_macros::{Display, EnumString};
 
 use crate::constants;
 
@@ -8,12 +9,38 @@ pub const EXT_WAD: &str = "wad";
 pub const EXT_PK3: &str = "pk3";
 pub const EXT_PKE: &str = "pke";
 pub const EXT_TXT: &str = "txt";
+pub const EXT_DEH: &str = "deh";
+pub const EXT_BEX: &str = "bex";
 
-pub const GAME_FILES: [&str; 3] = [EXT_WAD, EXT_PK3, EXT_PKE];
+pub const GAME_FILES: [&str; 5] = [EXT_WAD, EXT_PK3, EXT_PKE, EXT_DEH, EXT_BEX];
+pub const PATCH_FILES: [&str; 2] = [EXT_DEH, EXT_BEX];
 
 pub const IWAD_IDENTIFIER: [u8; 4] = *b"IWAD";
 pub const PWAD_IDENTIFIER: [u8; 4] = *b"PWAD";
 
+#[derive(Clone, Debug, PartialEq, sqlx::Type, Display)]
+pub enum LevelFormat {
+    Doom,
+    Hexen,
+    #[strum(serialize = "UDMF")]
+    Udmf,
+}
+
+// Lumps that say something about how a WAD should be played, named as they are in the WAD
+#[derive(Clone, Debug, PartialEq, sqlx::Type, Display, EnumString)]
+#[sqlx(rename_all = "UPPERCASE")]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum KeyLump {
+    Dehacked,
+    Umapinfo,
+    Mapinfo,
+    Zmapinfo,
+    Complvl,
+    Gameinfo,
+    Decorate,
+    Zscript,
+}
+
 #[derive(Clone, Debug, PartialEq, sqlx::Type, Display)]
 pub enum GameEngineType {
     #[strum(serialize = "GZDoom")]
@@ -99,6 +126,36 @@ pub fn get_engine_list(operating_system: OperatingSystem) -> Vec<GameEngine> {
             game_engine_type: GameEngineType::GzDoom,
             operating_system: OperatingSystem::MacOs,
         },
+        GameEngine {
+            exe_name: "gzdoom".to_string(),
+            internal_path: None,
+            game_engine_type: GameEngineType::GzDoom,
+            operating_system: OperatingSystem::Linux,
+        },
+        GameEngine {
+            exe_name: "dsda-doom".to_string(),
+            internal_path: None,
+            game_engine_type: GameEngineType::PrBoomPlus,
+            operating_system: OperatingSystem::Linux,
+        },
+        GameEngine {
+            exe_name: "crispy-doom".to_string(),
+            internal_path: None,
+            game_engine_type: GameEngineType::CrispyDoom,
+            operating_system: OperatingSystem::Linux,
+        },
+        GameEngine {
+            exe_name: "woof".to_string(),
+            internal_path: None,
+            game_engine_type: GameEngineType::Woof,
+            operating_system: OperatingSystem::Linux,
+        },
+        GameEngine {
+            exe_name: "eternity".to_string(),
+            internal_path: None,
+            game_engine_type: GameEngineType::EternityEngine,
+            operating_system: OperatingSystem::Linux,
+        },
     ];
 
     result
@@ -133,9 +190,67 @@ pub enum InternalWadType {
     Strife,
     #[strum(serialize = "Strife Voices")]
     StrifeVoices,
+    #[strum(serialize = "Freedoom: Phase 1")]
+    FreedoomPhase1,
+    #[strum(serialize = "Freedoom: Phase 2")]
+    FreedoomPhase2,
+    #[strum(serialize = "FreeDM")]
+    FreeDm,
+    #[strum(serialize = "Chex Quest")]
+    ChexQuest,
+    #[strum(serialize = "Chex Quest 3")]
+    ChexQuest3,
+    #[strum(serialize = "HacX")]
+    Hacx,
+    #[strum(serialize = "Harmony")]
+    Harmony,
+    #[strum(serialize = "REKKR")]
+    Rekkr,
+    // Registered by the user, with their own display name and game family
+    #[strum(serialize = "Custom")]
+    Custom,
     Unknown,
 }
 
+impl InternalWadType {
+    pub fn get_game_family(&self) -> GameFamily {
+        match self {
+            InternalWadType::Heretic | InternalWadType::HereticShareware => GameFamily::Heretic,
+            InternalWadType::Hexen | InternalWadType::HexenDeathkings => GameFamily::Hexen,
+            InternalWadType::Strife
+            | InternalWadType::StrifeTeaser
+            | InternalWadType::StrifeVoices => GameFamily::Strife,
+            _ => GameFamily::Doom,
+        }
+    }
+
+    // None when it can't be known, such as a Custom IWAD
+    pub fn is_episodic(&self) -> Option<bool> {
+        match self {
+            InternalWadType::Doom
+            | InternalWadType::DoomShareware
+            | InternalWadType::Heretic
+            | InternalWadType::HereticShareware
+            | InternalWadType::FreedoomPhase1
+            | InternalWadType::ChexQuest
+            | InternalWadType::ChexQuest3
+            | InternalWadType::Rekkr => Some(true),
+            InternalWadType::Custom | InternalWadType::Unknown => None,
+            _ => Some(false),
+        }
+    }
+}
+
+// The game an IWAD is built on, which decides the Engines that can run it
+#[derive(Clone, Debug, Display, EnumString, PartialEq, sqlx::Type, ValueEnum)]
+#[strum(ascii_case_insensitive)]
+pub enum GameFamily {
+    Doom,
+    Heretic,
+    Hexen,
+    Strife,
+}
+
 #[derive(Clone, Debug)]
 pub struct InternalWad {
     pub file_name: String,
@@ -193,5 +308,1417 @@ pub fn get_internal_wad_list() -> Vec<InternalWad> {
             file_name: "VOICES.WAD".to_string(),
             internal_wad_type: InternalWadType::StrifeVoices,
         },
+        InternalWad {
+            file_name: "FREEDOOM1.WAD".to_string(),
+            internal_wad_type: InternalWadType::FreedoomPhase1,
+        },
+        InternalWad {
+            file_name: "FREEDOOM2.WAD".to_string(),
+            internal_wad_type: InternalWadType::FreedoomPhase2,
+        },
+        InternalWad {
+            file_name: "FREEDM.WAD".to_string(),
+            internal_wad_type: InternalWadType::FreeDm,
+        },
+        InternalWad {
+            file_name: "CHEX.WAD".to_string(),
+            internal_wad_type: InternalWadType::ChexQuest,
+        },
+        InternalWad {
+            file_name: "CHEX3.WAD".to_string(),
+            internal_wad_type: InternalWadType::ChexQuest3,
+        },
+        InternalWad {
+            file_name: "HACX.WAD".to_string(),
+            internal_wad_type: InternalWadType::Hacx,
+        },
+        InternalWad {
+            file_name: "HARM1.WAD".to_string(),
+            internal_wad_type: InternalWadType::Harmony,
+        },
+        InternalWad {
+            file_name: "REKKRSA.WAD".to_string(),
+            internal_wad_type: InternalWadType::Rekkr,
+        },
+    ]
+}
+
+#[derive(Clone, Debug)]
+pub struct IwadRelease {
+    pub md5: String,
+    pub internal_wad_type: InternalWadType,
+    pub release: String,
+}
+
+pub fn get_iwad_release_list() -> Vec<IwadRelease> {
+    // Via: https://doomwiki.org/wiki/IWAD and the checksums listed on each game's page. Freedoom
+    // and FreeDM are not listed yet, they are told apart by their FREEDOOM and FREEDM lumps
+    vec![
+        IwadRelease {
+            md5: "762fd6d4b960d4b759730f01387a50a1".to_string(),
+            internal_wad_type: InternalWadType::DoomShareware,
+            release: "Shareware v1.666".to_string(),
+        },
+        IwadRelease {
+            md5: "f0cefca49926d00903cf57551d901abe".to_string(),
+            internal_wad_type: InternalWadType::DoomShareware,
+            release: "Shareware v1.9".to_string(),
+        },
+        IwadRelease {
+            md5: "54978d12de87f162b9bcc011676cb3c0".to_string(),
+            internal_wad_type: InternalWadType::Doom,
+            release: "Registered v1.666".to_string(),
+        },
+        IwadRelease {
+            md5: "1cd63c5ddff1bf8ce844237f580e9cf3".to_string(),
+            internal_wad_type: InternalWadType::Doom,
+            release: "Registered v1.9".to_string(),
+        },
+        IwadRelease {
+            md5: "c4fe9fd920207691a9f493668e0a2083".to_string(),
+            internal_wad_type: InternalWadType::Doom,
+            release: "The Ultimate Doom v1.9".to_string(),
+        },
+        IwadRelease {
+            md5: "fb35c4a5a9fd49ec29ab6e900572c524".to_string(),
+            internal_wad_type: InternalWadType::Doom,
+            release: "BFG Edition".to_string(),
+        },
+        IwadRelease {
+            md5: "8517c4e8f0eef90b82852667d345eb86".to_string(),
+            internal_wad_type: InternalWadType::Doom,
+            release: "Unity".to_string(),
+        },
+        IwadRelease {
+            md5: "3b37188f6337f15718b617c16e6e7a9c".to_string(),
+            internal_wad_type: InternalWadType::Doom,
+            release: "KEX (2024)".to_string(),
+        },
+        IwadRelease {
+            md5: "30e3c2d0350b67bfbf47271970b74b2f".to_string(),
+            internal_wad_type: InternalWadType::Doom2,
+            release: "v1.666".to_string(),
+        },
+        IwadRelease {
+            md5: "ea74a47a791fdef2e9f2ea8b8a9da13b".to_string(),
+            internal_wad_type: InternalWadType::Doom2,
+            release: "v1.7".to_string(),
+        },
+        IwadRelease {
+            md5: "d7a07e5d3f4625074312bc299d7ed33f".to_string(),
+            internal_wad_type: InternalWadType::Doom2,
+            release: "v1.8".to_string(),
+        },
+        IwadRelease {
+            md5: "25e1459ca71d321525f84628f45ca8cd".to_string(),
+            internal_wad_type: InternalWadType::Doom2,
+            release: "v1.9".to_string(),
+        },
+        IwadRelease {
+            md5: "c3bea40570c23e511a7ed3ebcd9865f7".to_string(),
+            internal_wad_type: InternalWadType::Doom2,
+            release: "BFG Edition".to_string(),
+        },
+        IwadRelease {
+            md5: "8ab6d0527a29efdc1ef200e5687b5cae".to_string(),
+            internal_wad_type: InternalWadType::Doom2,
+            release: "Unity".to_string(),
+        },
+        IwadRelease {
+            md5: "9aa3cbf65b961d00bdd9fa05a95f5cf2".to_string(),
+            internal_wad_type: InternalWadType::Doom2,
+            release: "KEX (2024)".to_string(),
+        },
+        IwadRelease {
+            md5: "4e158d9953c79ccf97bd0663244cc6b6".to_string(),
+            internal_wad_type: InternalWadType::Tnt,
+            release: "v1.9".to_string(),
+        },
+        IwadRelease {
+            md5: "1d39e405bf6ee3df69a8d2646c8d5c49".to_string(),
+            internal_wad_type: InternalWadType::Tnt,
+            release: "id Anthology".to_string(),
+        },
+        IwadRelease {
+            md5: "75c8cf89566741fa9d22447604053bd7".to_string(),
+            internal_wad_type: InternalWadType::Plutonia,
+            release: "v1.9".to_string(),
+        },
+        IwadRelease {
+            md5: "3493be7e1e2588bc9c8b31eab2587a04".to_string(),
+            internal_wad_type: InternalWadType::Plutonia,
+            release: "id Anthology".to_string(),
+        },
+        IwadRelease {
+            md5: "ae779722390ec32fa37b0d361f7d82f8".to_string(),
+            internal_wad_type: InternalWadType::HereticShareware,
+            release: "Shareware v1.2".to_string(),
+        },
+        IwadRelease {
+            md5: "3117e399cdb4298eaa3941625f4b2923".to_string(),
+            internal_wad_type: InternalWadType::Heretic,
+            release: "Registered v1.0".to_string(),
+        },
+        IwadRelease {
+            md5: "1e4cb4ef075ad344dd63971637307e04".to_string(),
+            internal_wad_type: InternalWadType::Heretic,
+            release: "Registered v1.2".to_string(),
+        },
+        IwadRelease {
+            md5: "66d686b1ed6d35ff103f15dbd30e0341".to_string(),
+            internal_wad_type: InternalWadType::Heretic,
+            release: "Shadow of the Serpent Riders v1.3".to_string(),
+        },
+        IwadRelease {
+            md5: "b2543a03521365261d0a0f74d5dd90f0".to_string(),
+            internal_wad_type: InternalWadType::Hexen,
+            release: "v1.0".to_string(),
+        },
+        IwadRelease {
+            md5: "abb033caf81e26f12a2103e1fa25453f".to_string(),
+            internal_wad_type: InternalWadType::Hexen,
+            release: "v1.1".to_string(),
+        },
+        IwadRelease {
+            md5: "1077432e2690d390c256ac908b5f4efa".to_string(),
+            internal_wad_type: InternalWadType::HexenDeathkings,
+            release: "v1.0".to_string(),
+        },
+        IwadRelease {
+            md5: "78d5898e99e220e4de64edaa0e479593".to_string(),
+            internal_wad_type: InternalWadType::HexenDeathkings,
+            release: "v1.1".to_string(),
+        },
+        IwadRelease {
+            md5: "8f2d3a6a289f5d2f2f9c1eec02b47299".to_string(),
+            internal_wad_type: InternalWadType::Strife,
+            release: "v1.1".to_string(),
+        },
+        IwadRelease {
+            md5: "2fed2031a5b03892106e0f117f17901f".to_string(),
+            internal_wad_type: InternalWadType::Strife,
+            release: "v1.2".to_string(),
+        },
+        IwadRelease {
+            md5: "082234d6a3f7086424856478b5aa9e95".to_string(),
+            internal_wad_type: InternalWadType::StrifeVoices,
+            release: "v1.1".to_string(),
+        },
+    ]
+}
+
+#[derive(Clone, Debug)]
+pub struct SteamApp {
+    pub app_id: String,
+    pub name: String,
+}
+
+pub fn get_steam_app_list() -> Vec<SteamApp> {
+    // Via: https://steamdb.info, the 2024 DOOM + DOOM II re-release installs under The Ultimate Doom
+    vec![
+        SteamApp {
+            app_id: "2280".to_string(),
+            name: "The Ultimate Doom / DOOM + DOOM II".to_string(),
+        },
+        SteamApp {
+            app_id: "2290".to_string(),
+            name: "Final DOOM".to_string(),
+        },
+        SteamApp {
+            app_id: "2300".to_string(),
+            name: "DOOM II".to_string(),
+        },
+        SteamApp {
+            app_id: "2360".to_string(),
+            name: "HeXen: Beyond Heretic".to_string(),
+        },
+        SteamApp {
+            app_id: "2370".to_string(),
+            name: "HeXen: Deathkings of the Dark Citadel".to_string(),
+        },
+        SteamApp {
+            app_id: "2390".to_string(),
+            name: "Heretic: Shadow of the Serpent Riders".to_string(),
+        },
+        SteamApp {
+            app_id: "208200".to_string(),
+            name: "DOOM 3: BFG Edition".to_string(),
+        },
+        SteamApp {
+            app_id: "317040".to_string(),
+            name: "The Original Strife: Veteran Edition".to_string(),
+        },
+        SteamApp {
+            app_id: "3286930".to_string(),
+            name: "Heretic + Hexen".to_string(),
+        },
+    ]
+}
+
+// GOG game titles that ship IWADs, matched against the start of the installed title, ignoring case
+pub const GOG_GAME_TITLES: [&str; 8] = [
+    "The Ultimate DOOM",
+    "DOOM II",
+    "Final DOOM",
+    "DOOM + DOOM II",
+    "DOOM 3: BFG Edition",
+    "Heretic",
+    "Hexen",
+    "Strife",
+];
+
+// Common places for IWADs on Linux, as used by Debian, Fedora and Arch packages
+pub const LINUX_IWAD_FOLDERS: [&str; 4] = [
+    "/usr/share/games/doom",
+    "/usr/local/share/games/doom",
+    "/usr/share/doom",
+    "~/.local/share/games/doom",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyColour {
+    Blue,
+    Yellow,
+    Red,
+    Green,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ThingCategory {
+    Player,
+    Monster,
+    Weapon,
+    Ammo,
+    // Health, armor and power ups
+    Item,
+    Key(KeyColour),
+}
+
+#[derive(Clone, Debug)]
+pub struct ThingType {
+    pub doomednum: u16,
+    pub name: String,
+    pub category: ThingCategory,
+    // Only set for monsters
+    pub hit_points: u32,
+}
+
+// Engines and games built on Doom, like Freedoom, HacX, Harmony and REKKR, keep Doom's thing
+// numbers, though some change what they are. Strife isn't covered beyond the player start
+pub fn get_thing_type_list(internal_wad_type: &InternalWadType) -> Vec<ThingType> {
+    match internal_wad_type {
+        InternalWadType::ChexQuest | InternalWadType::ChexQuest3 => get_chex_thing_type_list(),
+        _ => match internal_wad_type.get_game_family() {
+            GameFamily::Doom => get_doom_thing_type_list(),
+            GameFamily::Heretic => get_heretic_thing_type_list(),
+            GameFamily::Hexen => get_hexen_thing_type_list(),
+            GameFamily::Strife => vec![ThingType {
+                doomednum: 1,
+                name: "Player 1 Start".to_string(),
+                category: ThingCategory::Player,
+                hit_points: 0,
+            }],
+        },
+    }
+}
+
+fn get_doom_thing_type_list() -> Vec<ThingType> {
+    // Via: https://doomwiki.org/wiki/Thing_types
+    vec![
+        ThingType {
+            doomednum: 1,
+            name: "Player 1 Start".to_string(),
+            category: ThingCategory::Player,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 3004,
+            name: "Zombieman".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 20,
+        },
+        ThingType {
+            doomednum: 9,
+            name: "Shotgun Guy".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 30,
+        },
+        ThingType {
+            doomednum: 65,
+            name: "Heavy Weapon Dude".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 70,
+        },
+        ThingType {
+            doomednum: 3001,
+            name: "Imp".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 60,
+        },
+        ThingType {
+            doomednum: 3002,
+            name: "Demon".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 150,
+        },
+        ThingType {
+            doomednum: 58,
+            name: "Spectre".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 150,
+        },
+        ThingType {
+            doomednum: 3006,
+            name: "Lost Soul".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 100,
+        },
+        ThingType {
+            doomednum: 3005,
+            name: "Cacodemon".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 400,
+        },
+        ThingType {
+            doomednum: 69,
+            name: "Hell Knight".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 500,
+        },
+        ThingType {
+            doomednum: 3003,
+            name: "Baron of Hell".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 1000,
+        },
+        ThingType {
+            doomednum: 68,
+            name: "Arachnotron".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 500,
+        },
+        ThingType {
+            doomednum: 71,
+            name: "Pain Elemental".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 400,
+        },
+        ThingType {
+            doomednum: 66,
+            name: "Revenant".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 300,
+        },
+        ThingType {
+            doomednum: 67,
+            name: "Mancubus".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 600,
+        },
+        ThingType {
+            doomednum: 64,
+            name: "Arch-vile".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 700,
+        },
+        ThingType {
+            doomednum: 7,
+            name: "Spiderdemon".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 3000,
+        },
+        ThingType {
+            doomednum: 16,
+            name: "Cyberdemon".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 4000,
+        },
+        ThingType {
+            doomednum: 84,
+            name: "Wolfenstein SS".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 50,
+        },
+        ThingType {
+            doomednum: 72,
+            name: "Commander Keen".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 100,
+        },
+        ThingType {
+            doomednum: 88,
+            name: "Icon of Sin".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 250,
+        },
+        ThingType {
+            doomednum: 2005,
+            name: "Chainsaw".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2001,
+            name: "Shotgun".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 82,
+            name: "Super Shotgun".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2002,
+            name: "Chaingun".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2003,
+            name: "Rocket Launcher".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2004,
+            name: "Plasma Gun".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2006,
+            name: "BFG9000".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2007,
+            name: "Clip".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2048,
+            name: "Box of Bullets".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2008,
+            name: "Shotgun Shells".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2049,
+            name: "Box of Shotgun Shells".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2010,
+            name: "Rocket".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2046,
+            name: "Box of Rockets".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2047,
+            name: "Energy Cell".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 17,
+            name: "Energy Cell Pack".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8,
+            name: "Backpack".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2011,
+            name: "Stimpack".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2012,
+            name: "Medikit".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2014,
+            name: "Health Bonus".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2015,
+            name: "Armor Bonus".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2018,
+            name: "Armor".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2019,
+            name: "Megaarmor".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2013,
+            name: "Supercharge".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 83,
+            name: "Megasphere".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2022,
+            name: "Invulnerability".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2023,
+            name: "Berserk".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2024,
+            name: "Partial Invisibility".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2025,
+            name: "Radiation Shielding Suit".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2026,
+            name: "Computer Area Map".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2045,
+            name: "Light Amplification Visor".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 5,
+            name: "Blue Keycard".to_string(),
+            category: ThingCategory::Key(KeyColour::Blue),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 6,
+            name: "Yellow Keycard".to_string(),
+            category: ThingCategory::Key(KeyColour::Yellow),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 13,
+            name: "Red Keycard".to_string(),
+            category: ThingCategory::Key(KeyColour::Red),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 40,
+            name: "Blue Skull Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Blue),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 39,
+            name: "Yellow Skull Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Yellow),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 38,
+            name: "Red Skull Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Red),
+            hit_points: 0,
+        },
+    ]
+}
+
+// Chex Quest renamed the monsters it kept from Doom, without changing their health
+fn get_chex_thing_type_list() -> Vec<ThingType> {
+    let chex_names = [
+        (3004, "Flemoidus Commonus"),
+        (9, "Flemoidus Bipedicus"),
+        (3001, "Armored Flemoidus Bipedicus"),
+        (3002, "Flemoidus Cycloptis Commonus"),
+        (3003, "The Flembrane"),
+    ];
+    get_doom_thing_type_list()
+        .into_iter()
+        .map(|thing_type| {
+            match chex_names
+                .iter()
+                .find(|(doomednum, _)| *doomednum == thing_type.doomednum)
+            {
+                Some((_, name)) => ThingType {
+                    name: name.to_string(),
+                    ..thing_type
+                },
+                None => thing_type,
+            }
+        })
+        .collect()
+}
+
+fn get_heretic_thing_type_list() -> Vec<ThingType> {
+    // Via: https://doomwiki.org/wiki/Thing_types_(Heretic)
+    vec![
+        ThingType {
+            doomednum: 1,
+            name: "Player 1 Start".to_string(),
+            category: ThingCategory::Player,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 66,
+            name: "Gargoyle".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 40,
+        },
+        ThingType {
+            doomednum: 5,
+            name: "Fire Gargoyle".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 80,
+        },
+        ThingType {
+            doomednum: 68,
+            name: "Golem".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 80,
+        },
+        ThingType {
+            doomednum: 69,
+            name: "Golem Ghost".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 80,
+        },
+        ThingType {
+            doomednum: 45,
+            name: "Nitrogolem".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 100,
+        },
+        ThingType {
+            doomednum: 46,
+            name: "Nitrogolem Ghost".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 100,
+        },
+        ThingType {
+            doomednum: 64,
+            name: "Undead Warrior".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 200,
+        },
+        ThingType {
+            doomednum: 65,
+            name: "Undead Warrior Ghost".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 200,
+        },
+        ThingType {
+            doomednum: 90,
+            name: "Sabreclaw".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 150,
+        },
+        ThingType {
+            doomednum: 70,
+            name: "Weredragon".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 220,
+        },
+        ThingType {
+            doomednum: 92,
+            name: "Ophidian".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 280,
+        },
+        ThingType {
+            doomednum: 15,
+            name: "Disciple of D'Sparil".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 180,
+        },
+        ThingType {
+            doomednum: 6,
+            name: "Iron Lich".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 700,
+        },
+        ThingType {
+            doomednum: 9,
+            name: "Maulotaur".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 3000,
+        },
+        ThingType {
+            doomednum: 7,
+            name: "D'Sparil".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 2000,
+        },
+        ThingType {
+            doomednum: 2005,
+            name: "Gauntlets of the Necromancer".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2001,
+            name: "Ethereal Crossbow".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 53,
+            name: "Dragon Claw".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2004,
+            name: "Hellstaff".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2003,
+            name: "Phoenix Rod".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 2002,
+            name: "Firemace".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 10,
+            name: "Wand Crystal".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 12,
+            name: "Crystal Geode".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 18,
+            name: "Ethereal Arrows".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 19,
+            name: "Quiver of Ethereal Arrows".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 54,
+            name: "Claw Orb".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 55,
+            name: "Energy Orb".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 20,
+            name: "Lesser Runes".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 21,
+            name: "Greater Runes".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 22,
+            name: "Flame Orb".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 23,
+            name: "Inferno Orb".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 13,
+            name: "Mace Spheres".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 16,
+            name: "Pile of Mace Spheres".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8,
+            name: "Bag of Holding".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 81,
+            name: "Crystal Vial".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 82,
+            name: "Quartz Flask".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 85,
+            name: "Silver Shield".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 31,
+            name: "Enchanted Shield".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 32,
+            name: "Mystic Urn".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 33,
+            name: "Torch".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 30,
+            name: "Morph Ovum".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 34,
+            name: "Time Bomb of the Ancients".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 35,
+            name: "Map Scroll".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 36,
+            name: "Chaos Device".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 75,
+            name: "Shadowsphere".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 84,
+            name: "Ring of Invincibility".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 86,
+            name: "Tome of Power".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 83,
+            name: "Wings of Wrath".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 73,
+            name: "Green Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Green),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 79,
+            name: "Blue Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Blue),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 80,
+            name: "Yellow Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Yellow),
+            hit_points: 0,
+        },
+    ]
+}
+
+fn get_hexen_thing_type_list() -> Vec<ThingType> {
+    // Via: https://doomwiki.org/wiki/Thing_types_(Hexen)
+    vec![
+        ThingType {
+            doomednum: 1,
+            name: "Player 1 Start".to_string(),
+            category: ThingCategory::Player,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 10030,
+            name: "Ettin".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 175,
+        },
+        ThingType {
+            doomednum: 10060,
+            name: "Afrit".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 80,
+        },
+        ThingType {
+            doomednum: 31,
+            name: "Green Chaos Serpent".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 250,
+        },
+        ThingType {
+            doomednum: 8080,
+            name: "Brown Chaos Serpent".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 250,
+        },
+        ThingType {
+            doomednum: 34,
+            name: "Reiver".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 150,
+        },
+        ThingType {
+            doomednum: 10011,
+            name: "Buried Reiver".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 150,
+        },
+        ThingType {
+            doomednum: 254,
+            name: "Death Wyvern".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 640,
+        },
+        ThingType {
+            doomednum: 107,
+            name: "Centaur".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 200,
+        },
+        ThingType {
+            doomednum: 115,
+            name: "Slaughtaur".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 250,
+        },
+        ThingType {
+            doomednum: 114,
+            name: "Dark Bishop".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 130,
+        },
+        ThingType {
+            doomednum: 8020,
+            name: "Wendigo".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 120,
+        },
+        ThingType {
+            doomednum: 121,
+            name: "Stalker".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 90,
+        },
+        ThingType {
+            doomednum: 120,
+            name: "Stalker Leader".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 90,
+        },
+        ThingType {
+            doomednum: 10080,
+            name: "Heresiarch".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 5000,
+        },
+        ThingType {
+            doomednum: 10100,
+            name: "Zedek".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 800,
+        },
+        ThingType {
+            doomednum: 10101,
+            name: "Traductus".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 800,
+        },
+        ThingType {
+            doomednum: 10102,
+            name: "Menelkir".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 800,
+        },
+        ThingType {
+            doomednum: 10200,
+            name: "Korax".to_string(),
+            category: ThingCategory::Monster,
+            hit_points: 5000,
+        },
+        ThingType {
+            doomednum: 8010,
+            name: "Timon's Axe".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 123,
+            name: "Hammer of Retribution".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 10,
+            name: "Serpent Staff".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8009,
+            name: "Firestorm".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 53,
+            name: "Frost Shards".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8040,
+            name: "Arc of Death".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 12,
+            name: "Quietus Hilt".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 13,
+            name: "Quietus Crosspiece".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 16,
+            name: "Quietus Blade".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 18,
+            name: "Wraithverge Stub".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 19,
+            name: "Wraithverge Crosspiece".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 20,
+            name: "Wraithverge Head".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 21,
+            name: "Bloodscourge Stub".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 22,
+            name: "Bloodscourge Shaft".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 23,
+            name: "Bloodscourge Head".to_string(),
+            category: ThingCategory::Weapon,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 122,
+            name: "Blue Mana".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 124,
+            name: "Green Mana".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8004,
+            name: "Combined Mana".to_string(),
+            category: ThingCategory::Ammo,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 81,
+            name: "Crystal Vial".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 82,
+            name: "Quartz Flask".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 32,
+            name: "Mystic Urn".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8005,
+            name: "Mesh Armor".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8006,
+            name: "Falcon Shield".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8007,
+            name: "Platinum Helmet".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8008,
+            name: "Amulet of Warding".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 84,
+            name: "Icon of the Defender".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 30,
+            name: "Porkalator".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 33,
+            name: "Torch".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 36,
+            name: "Chaos Device".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 83,
+            name: "Wings of Wrath".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 86,
+            name: "Dark Servant".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8041,
+            name: "Mystic Ambit Incant".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8000,
+            name: "Flechette".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 10040,
+            name: "Banishment Device".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 10110,
+            name: "Disc of Repulsion".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8003,
+            name: "Krater of Might".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8002,
+            name: "Boots of Speed".to_string(),
+            category: ThingCategory::Item,
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8030,
+            name: "Steel Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Other),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8031,
+            name: "Cave Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Other),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8032,
+            name: "Axe Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Other),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8033,
+            name: "Fire Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Red),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8034,
+            name: "Emerald Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Green),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8035,
+            name: "Dungeon Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Other),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8036,
+            name: "Silver Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Other),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8037,
+            name: "Rusted Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Other),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8038,
+            name: "Horn Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Other),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8039,
+            name: "Swamp Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Blue),
+            hit_points: 0,
+        },
+        ThingType {
+            doomednum: 8200,
+            name: "Castle Key".to_string(),
+            category: ThingCategory::Key(KeyColour::Yellow),
+            hit_points: 0,
+        },
     ]
 }
@@ -4,7 +4,9 @@ use owo_colors::OwoColorize;
 use reqwest::StatusCode;
 use zip::ZipArchive;
 
-use crate::{data, db, doomworld_api::DoomworldFile, files, menu_profiles, paths};
+use crate::{
+    data, db, doomworld_api::DoomworldFile, files, hooks, menu_app_settings, menu_profiles, paths,
+};
 
 // Germany      - https://www.quaddicted.com/files/idgames/
 // Sweden       - https://ftpmirror1.infania.net/pub/idgames/
@@ -42,6 +44,7 @@ pub fn download_and_extract_map_files(
     }
 
     let mut map_count = 0;
+    let mut downloaded_map_paths: Vec<String> = Vec::new();
 
     // Loop through the files and download/extract them
     for doomworld_file in doomworld_files {
@@ -84,8 +87,14 @@ pub fn download_and_extract_map_files(
             // Go through what was extracted look for maps
             for extracted_file in extracted_files {
                 if files::map_file_extension(&extracted_file)? {
-                    // Check if it's in the db...
-                    let existing_map = db::get_map_by_path(&extracted_file);
+                    // Check if it's in the db, by path or as a copy somewhere else...
+                    let file_hashes = files::get_file_hashes(&extracted_file);
+                    let existing_map = db::get_map_by_path(&extracted_file).or_else(|e| {
+                        file_hashes
+                            .sha1
+                            .as_deref()
+                            .map_or(Err(e), db::get_map_by_sha1)
+                    });
 
                     // ...handle accordingly
                     match existing_map {
@@ -99,9 +108,11 @@ pub fn download_and_extract_map_files(
                         }
                         Err(_) => {
                             // Doesn't exist, so add it and prompt for profile
+                            downloaded_map_paths.push(extracted_file.clone());
                             add_map_and_create_optional_profile(
                                 &doomworld_file,
                                 extracted_file,
+                                file_hashes,
                                 &mut map_count,
                             )?;
                         }
@@ -115,6 +126,20 @@ pub fn download_and_extract_map_files(
         return Err(eyre::eyre!("No maps were downloaded"));
     }
 
+    // Let a post-download hook know what was added
+    let hook_context = hooks::HookContext {
+        map_paths: downloaded_map_paths,
+        download_folder: Some(download_file_path.display().to_string()),
+        ..Default::default()
+    };
+    if let Err(e) = hooks::run_hooks(
+        hooks::HookPoint::PostDownload,
+        &[app_settings.post_download_hook],
+        &hook_context,
+    ) {
+        log::error!("{}", e);
+    }
+
     let result_message = format!("Successfully added {} Maps", map_count);
     log::info!("{}", result_message.green());
     inquire::Text::new("Press any key to continue...").prompt_skippable()?;
@@ -125,6 +150,7 @@ pub fn download_and_extract_map_files(
 fn add_map_and_create_optional_profile(
     doomworld_file: &DoomworldFile,
     extracted_file: String,
+    file_hashes: files::FileHashes,
     map_count: &mut i32,
 ) -> Result<(), eyre::Error> {
     let map = data::Map {
@@ -134,12 +160,16 @@ fn add_map_and_create_optional_profile(
         path: extracted_file,
         doomworld_id: Some(doomworld_file.id),
         doomworld_url: Some(doomworld_file.url.clone()),
+        sha1: file_hashes.sha1,
+        md5: file_hashes.md5,
+        ..Default::default()
     };
 
     let add_result = db::add_map(&map)?;
     let add_map_id: i32 = add_result.last_insert_rowid().try_into().unwrap();
     log::info!("Added Map: {}", map.simple_display().blue());
     log::debug!("  Map {:?}", map);
+    menu_app_settings::update_map_contents(add_map_id, &map.path)?;
 
     *map_count += 1;
 
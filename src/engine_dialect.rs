@@ -0,0 +1,587 @@
+use std::path::Path;
+
+use strum_macros::Display;
+
+use crate::{
+    data,
+    doom_data::{self, GameEngineType},
+};
+
+// Engines share a lot of command line arguments, but not all of them. Each Play Setting
+// is spelt here per Engine, and anything an Engine doesn't understand is dropped
+// rather than passed through (some Engines refuse to start with unknown arguments).
+
+#[derive(Clone, Debug, PartialEq, Display)]
+pub enum PlaySetting {
+    #[strum(serialize = "Compatibility Level")]
+    CompLevel,
+    #[strum(serialize = "Config File")]
+    ConfigFile,
+    #[strum(serialize = "Fast Monsters")]
+    FastMonsters,
+    #[strum(serialize = "No Monsters")]
+    NoMonsters,
+    #[strum(serialize = "Respawn Monsters")]
+    RespawnMonsters,
+    #[strum(serialize = "Warp to Level")]
+    Warp,
+    Skill,
+    Turbo,
+    Timer,
+    #[strum(serialize = "Screen Width")]
+    Width,
+    #[strum(serialize = "Screen Height")]
+    Height,
+    #[strum(serialize = "Full Screen")]
+    FullScreen,
+    Windowed,
+    #[strum(serialize = "Record Demo")]
+    RecordDemo,
+    #[strum(serialize = "Play Demo")]
+    PlayDemo,
+    #[strum(serialize = "Time Demo")]
+    TimeDemo,
+    #[strum(serialize = "Host Netgame")]
+    HostNetgame,
+    #[strum(serialize = "Join Netgame")]
+    JoinNetgame,
+    #[strum(serialize = "Network Port")]
+    NetPort,
+    Deathmatch,
+    #[strum(serialize = "AltDeath")]
+    AltDeath,
+}
+
+pub struct PlaySettingsArgs {
+    pub args: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl PlaySettingsArgs {
+    fn add(
+        &mut self,
+        game_engine_type: &GameEngineType,
+        setting: PlaySetting,
+        setting_args: Vec<String>,
+    ) {
+        if is_setting_supported(game_engine_type, &setting) {
+            self.args.extend(setting_args);
+        } else {
+            self.warnings.push(format!(
+                "Play Setting '{}' is not supported by {}, ignoring '{}'",
+                setting,
+                game_engine_type,
+                setting_args.join(" ")
+            ));
+        }
+    }
+}
+
+pub fn is_setting_supported(game_engine_type: &GameEngineType, setting: &PlaySetting) -> bool {
+    match game_engine_type {
+        GameEngineType::GzDoom => !matches!(setting, PlaySetting::CompLevel),
+        GameEngineType::Woof | GameEngineType::Unknown => true,
+        // PrBoom+ games are hosted by its separate game server, clients only take
+        // a -net address with the port folded in
+        GameEngineType::PrBoomPlus => {
+            !matches!(setting, PlaySetting::HostNetgame | PlaySetting::NetPort)
+        }
+        GameEngineType::CrispyDoom => !matches!(setting, PlaySetting::CompLevel),
+        // Eternity's netcode is not usable, so only the game mode is passed through
+        GameEngineType::EternityEngine => !matches!(
+            setting,
+            PlaySetting::CompLevel
+                | PlaySetting::Turbo
+                | PlaySetting::Timer
+                | PlaySetting::HostNetgame
+                | PlaySetting::JoinNetgame
+                | PlaySetting::NetPort
+        ),
+        // Doom Retro only takes a handful of arguments, and has no demo support
+        GameEngineType::DoomRetro => matches!(
+            setting,
+            PlaySetting::ConfigFile
+                | PlaySetting::FastMonsters
+                | PlaySetting::NoMonsters
+                | PlaySetting::RespawnMonsters
+                | PlaySetting::Warp
+                | PlaySetting::Skill
+        ),
+    }
+}
+
+pub fn get_play_settings_args(
+    game_engine_type: &GameEngineType,
+    play_settings: &data::PlaySettings,
+) -> PlaySettingsArgs {
+    let mut result = match &play_settings.comp_level {
+        Some(comp_level) => get_comp_level_args(game_engine_type, comp_level),
+        None => PlaySettingsArgs {
+            args: Vec::new(),
+            warnings: Vec::new(),
+        },
+    };
+    if let Some(config_file) = &play_settings.config_file {
+        result.add(
+            game_engine_type,
+            PlaySetting::ConfigFile,
+            vec!["-config".to_string(), config_file.to_string()],
+        );
+    }
+    if play_settings.fast_monsters {
+        result.add(
+            game_engine_type,
+            PlaySetting::FastMonsters,
+            vec!["-fast".to_string()],
+        );
+    }
+    if play_settings.no_monsters {
+        result.add(
+            game_engine_type,
+            PlaySetting::NoMonsters,
+            vec!["-nomonsters".to_string()],
+        );
+    }
+    if play_settings.respawn_monsters {
+        result.add(
+            game_engine_type,
+            PlaySetting::RespawnMonsters,
+            vec!["-respawn".to_string()],
+        );
+    }
+    if let Some(warp) = &play_settings.warp {
+        let mut warp_args = vec!["-warp".to_string()];
+        warp_args.extend(warp.split_whitespace().map(|arg| arg.to_string()));
+        result.add(game_engine_type, PlaySetting::Warp, warp_args);
+    }
+    if let Some(skill) = play_settings.skill {
+        result.add(
+            game_engine_type,
+            PlaySetting::Skill,
+            vec!["-skill".to_string(), skill.to_string()],
+        );
+    }
+    if let Some(turbo) = play_settings.turbo {
+        result.add(
+            game_engine_type,
+            PlaySetting::Turbo,
+            vec!["-turbo".to_string(), turbo.to_string()],
+        );
+    }
+    if let Some(timer) = play_settings.timer {
+        result.add(
+            game_engine_type,
+            PlaySetting::Timer,
+            vec!["-timer".to_string(), timer.to_string()],
+        );
+    }
+
+    // Eternity takes the screen size as a single geometry argument
+    if *game_engine_type == GameEngineType::EternityEngine
+        && (play_settings.width.is_some() || play_settings.height.is_some())
+    {
+        if let (Some(width), Some(height)) = (play_settings.width, play_settings.height) {
+            result.add(
+                game_engine_type,
+                PlaySetting::Width,
+                vec!["-geom".to_string(), format!("{}x{}", width, height)],
+            );
+        } else {
+            result.warnings.push(format!(
+                "{} needs both Screen Width and Screen Height set, ignoring screen size",
+                game_engine_type
+            ));
+        }
+    } else {
+        if let Some(width) = play_settings.width {
+            result.add(
+                game_engine_type,
+                PlaySetting::Width,
+                vec!["-width".to_string(), width.to_string()],
+            );
+        }
+        if let Some(height) = play_settings.height {
+            result.add(
+                game_engine_type,
+                PlaySetting::Height,
+                vec!["-height".to_string(), height.to_string()],
+            );
+        }
+    }
+
+    if play_settings.full_screen {
+        result.add(
+            game_engine_type,
+            PlaySetting::FullScreen,
+            match game_engine_type {
+                GameEngineType::GzDoom => vec!["+vid_fullscreen".to_string(), "1".to_string()],
+                _ => vec!["-fullscreen".to_string()],
+            },
+        );
+    }
+    if play_settings.windowed {
+        result.add(
+            game_engine_type,
+            PlaySetting::Windowed,
+            match game_engine_type {
+                GameEngineType::GzDoom => vec!["+vid_fullscreen".to_string(), "0".to_string()],
+                _ => vec!["-window".to_string()],
+            },
+        );
+    }
+
+    result
+}
+
+// Demos play back with the Compatibility Level they were recorded with, and no other settings
+pub fn get_comp_level_args(
+    game_engine_type: &GameEngineType,
+    comp_level: &data::CompLevel,
+) -> PlaySettingsArgs {
+    let mut result = PlaySettingsArgs {
+        args: Vec::new(),
+        warnings: Vec::new(),
+    };
+    result.add(
+        game_engine_type,
+        PlaySetting::CompLevel,
+        vec![
+            "-complevel".to_string(),
+            get_comp_level_value(game_engine_type, comp_level),
+        ],
+    );
+    result
+}
+
+pub fn get_demo_args(
+    game_engine_type: &GameEngineType,
+    demo_action: &data::DemoAction,
+    demo_path: &str,
+) -> PlaySettingsArgs {
+    let mut result = PlaySettingsArgs {
+        args: Vec::new(),
+        warnings: Vec::new(),
+    };
+    let (setting, argument) = match demo_action {
+        data::DemoAction::Record => (PlaySetting::RecordDemo, "-record"),
+        data::DemoAction::Play => (PlaySetting::PlayDemo, "-playdemo"),
+        data::DemoAction::TimeDemo => (PlaySetting::TimeDemo, "-timedemo"),
+    };
+    result.add(
+        game_engine_type,
+        setting,
+        vec![argument.to_string(), demo_path.to_string()],
+    );
+
+    result
+}
+
+pub fn get_netgame_args(
+    game_engine_type: &GameEngineType,
+    netgame: &data::Netgame,
+) -> PlaySettingsArgs {
+    let mut result = PlaySettingsArgs {
+        args: Vec::new(),
+        warnings: Vec::new(),
+    };
+    match &netgame.net_role {
+        data::NetRole::Host { players } => match game_engine_type {
+            // Chocolate Doom descendants wait for players to connect, there is no count
+            GameEngineType::CrispyDoom | GameEngineType::Woof => {
+                result.add(
+                    game_engine_type,
+                    PlaySetting::HostNetgame,
+                    vec!["-server".to_string()],
+                );
+                result.warnings.push(format!(
+                    "{} does not take a player count, the game starts when the host begins it",
+                    game_engine_type
+                ));
+            }
+            _ => result.add(
+                game_engine_type,
+                PlaySetting::HostNetgame,
+                vec!["-host".to_string(), players.to_string()],
+            ),
+        },
+        data::NetRole::Join { address } => {
+            let join_args = match game_engine_type {
+                GameEngineType::PrBoomPlus => vec![
+                    "-net".to_string(),
+                    match netgame.port {
+                        Some(port) => format!("{}:{}", address, port),
+                        None => address.to_string(),
+                    },
+                ],
+                GameEngineType::CrispyDoom | GameEngineType::Woof => {
+                    vec!["-connect".to_string(), address.to_string()]
+                }
+                _ => vec!["-join".to_string(), address.to_string()],
+            };
+            result.add(game_engine_type, PlaySetting::JoinNetgame, join_args);
+        }
+    }
+
+    if let Some(port) = netgame.port {
+        // PrBoom+ already has the port in the -net address when joining
+        let port_in_address = *game_engine_type == GameEngineType::PrBoomPlus
+            && matches!(netgame.net_role, data::NetRole::Join { .. });
+        if !port_in_address {
+            result.add(
+                game_engine_type,
+                PlaySetting::NetPort,
+                vec!["-port".to_string(), port.to_string()],
+            );
+        }
+    }
+
+    match netgame.game_mode {
+        data::NetGameMode::Coop => {}
+        data::NetGameMode::Deathmatch => result.add(
+            game_engine_type,
+            PlaySetting::Deathmatch,
+            vec!["-deathmatch".to_string()],
+        ),
+        data::NetGameMode::AltDeath => result.add(
+            game_engine_type,
+            PlaySetting::AltDeath,
+            vec!["-altdeath".to_string()],
+        ),
+    }
+
+    result
+}
+
+pub fn get_resource_args(game_engine_type: &GameEngineType, map_paths: &[String]) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    let mut deh_patches: Vec<String> = Vec::new();
+    let mut bex_patches: Vec<String> = Vec::new();
+    for map_path in map_paths {
+        let extension = Path::new(map_path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            doom_data::EXT_DEH => deh_patches.push(map_path.clone()),
+            // GZDoom and Crispy Doom read BEX extensions through their DeHackEd loader
+            doom_data::EXT_BEX => match game_engine_type {
+                GameEngineType::GzDoom | GameEngineType::CrispyDoom => {
+                    deh_patches.push(map_path.clone())
+                }
+                _ => bex_patches.push(map_path.clone()),
+            },
+            _ => files.push(map_path.clone()),
+        }
+    }
+
+    let mut args: Vec<String> = Vec::new();
+    for (argument, paths) in [
+        ("-file", files),
+        ("-deh", deh_patches),
+        ("-bex", bex_patches),
+    ] {
+        if !paths.is_empty() {
+            args.push(argument.to_string());
+            args.extend(paths);
+        }
+    }
+
+    args
+}
+
+fn get_comp_level_value(game_engine_type: &GameEngineType, comp_level: &data::CompLevel) -> String {
+    match game_engine_type {
+        // Woof! only knows the broad families, by name
+        GameEngineType::Woof => match comp_level {
+            data::CompLevel::Boom
+            | data::CompLevel::BoomV201
+            | data::CompLevel::BoomV202
+            | data::CompLevel::LxDoom => "boom".to_string(),
+            data::CompLevel::Mbf
+            | data::CompLevel::PrBoomV203
+            | data::CompLevel::PrBoomV210
+            | data::CompLevel::PrBoomV211
+            | data::CompLevel::PrBoomV23
+            | data::CompLevel::PrBoomV24
+            | data::CompLevel::PrBoomPlus => "mbf".to_string(),
+            data::CompLevel::Mbf21 => "mbf21".to_string(),
+            _ => "vanilla".to_string(),
+        },
+        _ => (comp_level.clone() as i32).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data,
+        doom_data::GameEngineType,
+        engine_dialect::{
+            get_netgame_args, get_play_settings_args, get_resource_args, is_setting_supported,
+            PlaySetting,
+        },
+    };
+
+    #[test]
+    fn test_get_play_settings_args_gzdoom_drops_comp_level() {
+        // Arrange
+        let play_settings = data::PlaySettings {
+            comp_level: Some(data::CompLevel::Mbf21),
+            fast_monsters: true,
+            full_screen: true,
+            ..Default::default()
+        };
+
+        // Act
+        let actual = get_play_settings_args(&GameEngineType::GzDoom, &play_settings);
+
+        // Assert
+        assert_eq!(actual.args, vec!["-fast", "+vid_fullscreen", "1"]);
+        assert_eq!(actual.warnings.len(), 1);
+        assert!(actual.warnings[0].contains("-complevel 21"));
+    }
+
+    #[test]
+    fn test_get_play_settings_args_woof_comp_level_by_name() {
+        // Arrange
+        let play_settings = data::PlaySettings {
+            comp_level: Some(data::CompLevel::BoomV202),
+            skill: Some(4),
+            ..Default::default()
+        };
+
+        // Act
+        let actual = get_play_settings_args(&GameEngineType::Woof, &play_settings);
+
+        // Assert
+        assert_eq!(actual.args, vec!["-complevel", "boom", "-skill", "4"]);
+        assert!(actual.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_get_play_settings_args_eternity_geometry() {
+        // Arrange
+        let play_settings = data::PlaySettings {
+            warp: Some("1 4".to_string()),
+            width: Some(1280),
+            height: Some(720),
+            ..Default::default()
+        };
+
+        // Act
+        let actual = get_play_settings_args(&GameEngineType::EternityEngine, &play_settings);
+
+        // Assert
+        assert_eq!(actual.args, vec!["-warp", "1", "4", "-geom", "1280x720"]);
+        assert!(actual.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_is_setting_supported_doom_retro() {
+        assert!(is_setting_supported(
+            &GameEngineType::DoomRetro,
+            &PlaySetting::Warp
+        ));
+        assert!(!is_setting_supported(
+            &GameEngineType::DoomRetro,
+            &PlaySetting::Turbo
+        ));
+    }
+
+    #[test]
+    fn test_get_netgame_args_gzdoom_host_deathmatch() {
+        // Arrange
+        let netgame = data::Netgame {
+            net_role: data::NetRole::Host { players: 4 },
+            game_mode: data::NetGameMode::Deathmatch,
+            port: Some(5030),
+            time_limit: None,
+        };
+
+        // Act
+        let actual = get_netgame_args(&GameEngineType::GzDoom, &netgame);
+
+        // Assert
+        assert_eq!(
+            actual.args,
+            vec!["-host", "4", "-port", "5030", "-deathmatch"]
+        );
+        assert!(actual.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_get_netgame_args_prboom_join_folds_port() {
+        // Arrange
+        let netgame = data::Netgame {
+            net_role: data::NetRole::Join {
+                address: "192.168.0.10".to_string(),
+            },
+            game_mode: data::NetGameMode::AltDeath,
+            port: Some(5030),
+            time_limit: None,
+        };
+
+        // Act
+        let actual = get_netgame_args(&GameEngineType::PrBoomPlus, &netgame);
+
+        // Assert
+        assert_eq!(actual.args, vec!["-net", "192.168.0.10:5030", "-altdeath"]);
+        assert!(actual.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_get_netgame_args_crispy_host_warns_player_count() {
+        // Arrange
+        let netgame = data::Netgame {
+            net_role: data::NetRole::Host { players: 3 },
+            game_mode: data::NetGameMode::Coop,
+            port: None,
+            time_limit: None,
+        };
+
+        // Act
+        let actual = get_netgame_args(&GameEngineType::CrispyDoom, &netgame);
+
+        // Assert
+        assert_eq!(actual.args, vec!["-server"]);
+        assert_eq!(actual.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_get_resource_args_splits_patches() {
+        // Arrange
+        let map_paths = vec![
+            "/maps/valiant.wad".to_string(),
+            "/maps/valiant.deh".to_string(),
+            "/maps/eviternity.bex".to_string(),
+        ];
+
+        // Act
+        let actual = get_resource_args(&GameEngineType::Woof, &map_paths);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                "-file",
+                "/maps/valiant.wad",
+                "-deh",
+                "/maps/valiant.deh",
+                "-bex",
+                "/maps/eviternity.bex"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_resource_args_crispy_loads_bex_as_deh() {
+        // Arrange
+        let map_paths = vec!["/maps/PATCH.BEX".to_string()];
+
+        // Act
+        let actual = get_resource_args(&GameEngineType::CrispyDoom, &map_paths);
+
+        // Assert
+        assert_eq!(actual, vec!["-deh", "/maps/PATCH.BEX"]);
+    }
+}
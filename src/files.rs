@@ -4,11 +4,14 @@ use std::{
     path::Path,
 };
 
+use eyre::Context;
 use log::debug;
+use md5::Md5;
 use regex::Regex;
+use sha1::{Digest, Sha1};
 use strsim::levenshtein;
 
-use crate::{constants, data, doom_data, finder, paths};
+use crate::{archive, constants, data, doom_data, finder, paths};
 
 pub fn get_map_readme_file_name(map_path: &str) -> Result<Option<String>, eyre::Report> {
     let path = Path::new(map_path);
@@ -103,7 +106,7 @@ fn find_txt_files(directory: &Path) -> Result<Vec<String>, eyre::Report> {
     Ok(txt_files)
 }
 
-fn check_readme_line(line: &str, key: &str) -> Option<String> {
+pub fn check_readme_line(line: &str, key: &str) -> Option<String> {
     if line.to_lowercase().starts_with(key) {
         let parts: Vec<&str> = line.split(':').collect();
         if parts.len() > 1 {
@@ -145,11 +148,13 @@ pub fn get_version_from_exe_name(
 ) -> Result<data::FileVersion, eyre::Report> {
     match game_engine_type {
         doom_data::GameEngineType::PrBoomPlus => Ok(finder::get_prboom_file_version(exe_name)?),
+        // Chocolate Doom based Engines print their version and exit
+        doom_data::GameEngineType::CrispyDoom | doom_data::GameEngineType::Woof => {
+            Ok(finder::get_version_argument_file_version(exe_name)?)
+        }
         doom_data::GameEngineType::GzDoom
-        | doom_data::GameEngineType::CrispyDoom
         | doom_data::GameEngineType::EternityEngine
-        | doom_data::GameEngineType::DoomRetro
-        | doom_data::GameEngineType::Woof => Ok(finder::get_file_version(exe_name)?),
+        | doom_data::GameEngineType::DoomRetro => Ok(finder::get_file_version(exe_name)?),
         doom_data::GameEngineType::Unknown => Err(eyre::eyre!("Unknown game engine type")),
     }
 }
@@ -194,6 +199,16 @@ pub fn get_internal_wad_type_from_file_name(
     )))
 }
 
+// Maps that don't name the IWAD they need are taken to be for Doom
+pub fn get_required_internal_wad_type(required_iwad: Option<&str>) -> doom_data::InternalWadType {
+    required_iwad
+        .and_then(|required_iwad| {
+            get_internal_wad_type_from_file_name(doom_data::get_internal_wad_list(), required_iwad)
+                .ok()
+        })
+        .unwrap_or(doom_data::InternalWadType::Unknown)
+}
+
 fn is_valid_wad(file: &str, wad_identifier: &[u8; 4]) -> Result<bool, eyre::Report> {
     let path = Path::new(file);
     let extension = path.extension().unwrap_or_default();
@@ -232,6 +247,114 @@ pub fn map_file_extension(file: &str) -> Result<bool, eyre::Report> {
     if valid_extension && extension.to_ascii_lowercase() == doom_data::EXT_WAD {
         return is_pwad(file);
     }
+    if extension == doom_data::EXT_PK3 || extension == doom_data::EXT_PKE {
+        return Ok(archive::is_valid_archive(file));
+    }
 
     Ok(valid_extension)
 }
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileHashes {
+    pub sha1: Option<String>,
+    pub md5: Option<String>,
+}
+
+// SHA-1 finds copies of the same file, MD5 is what idgames publishes. Folders, such as macOS
+// app bundles, and unreadable files get no hashes
+pub fn get_file_hashes(file_path: &str) -> FileHashes {
+    if !paths::file_exists(file_path) {
+        return FileHashes::default();
+    }
+    match read_file_hashes(file_path) {
+        Ok(file_hashes) => file_hashes,
+        Err(e) => {
+            log::warn!("{:?}", e);
+            FileHashes::default()
+        }
+    }
+}
+
+fn read_file_hashes(file_path: &str) -> Result<FileHashes, eyre::Report> {
+    let mut file = File::open(file_path).wrap_err(format!("Failed to open '{}'", file_path))?;
+    let mut sha1 = Sha1::new();
+    let mut md5 = Md5::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .wrap_err(format!("Failed to read '{}'", file_path))?;
+        if read == 0 {
+            break;
+        }
+        sha1.update(&buffer[..read]);
+        md5.update(&buffer[..read]);
+    }
+
+    Ok(FileHashes {
+        sha1: Some(format!("{:x}", sha1.finalize())),
+        md5: Some(format!("{:x}", md5.finalize())),
+    })
+}
+
+// Groups of paths that share a SHA-1, in the order they were first seen
+pub fn group_duplicates(file_hashes: &[(String, String)]) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for (path, sha1) in file_hashes {
+        match groups.iter_mut().find(|(group_sha1, _)| group_sha1 == sha1) {
+            Some((_, paths)) => paths.push(path.clone()),
+            None => groups.push((sha1.clone(), vec![path.clone()])),
+        }
+    }
+    groups.retain(|(_, paths)| paths.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::files::{get_file_hashes, group_duplicates, FileHashes};
+
+    #[test]
+    fn test_get_file_hashes() {
+        // Act
+        let actual = get_file_hashes("./test-data/levels.wad");
+
+        // Assert
+        assert_eq!(
+            actual,
+            FileHashes {
+                sha1: Some("730c959fb405e4eae1f2e7b4943a45d88e797e69".to_string()),
+                md5: Some("f174c79be52f1a410517e5f7cf5519a7".to_string()),
+            }
+        );
+        assert_eq!(get_file_hashes("./test-data"), FileHashes::default());
+    }
+
+    #[test]
+    fn test_group_duplicates() {
+        // Arrange
+        let file_hashes: Vec<(String, String)> = [
+            ("/maps/sunlust.wad", "aa"),
+            ("/maps/valiant.wad", "bb"),
+            ("/maps/!dcli-downloads/sunlust/SUNLUST.WAD", "aa"),
+        ]
+        .iter()
+        .map(|(path, sha1)| (path.to_string(), sha1.to_string()))
+        .collect();
+
+        // Act
+        let actual = group_duplicates(&file_hashes);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![(
+                "aa".to_string(),
+                vec![
+                    "/maps/sunlust.wad".to_string(),
+                    "/maps/!dcli-downloads/sunlust/SUNLUST.WAD".to_string()
+                ]
+            )]
+        );
+    }
+}
@@ -1,95 +1,17 @@
-use std::{env, fs::File, path::Path};
+use std::{
+    env,
+    fs::{self, File},
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
 
 use eyre::Context;
 use plist::Value;
-use powershell_script::PsScriptBuilder;
 use regex::Regex;
 
-use crate::{constants, data, paths};
-
-fn run_powershell_cmd(powershell_cmd: &str) -> Result<Vec<String>, eyre::Report> {
-    if env::consts::OS != constants::OS_WINDOWS {
-        return Err(eyre::eyre!(format!(
-            "PowerShell is only supported on Windows, not on '{}'",
-            env::consts::OS
-        )));
-    }
-
-    let ps = PsScriptBuilder::new()
-        .no_profile(true)
-        .non_interactive(true)
-        .hidden(false)
-        .print_commands(false)
-        .build();
-
-    let output = ps.run(powershell_cmd).wrap_err(format!(
-        "Failed to run powershell command '{}'",
-        powershell_cmd
-    ))?;
-
-    let stdout_result = &output.stdout();
-    match stdout_result {
-        None => Err(eyre::eyre!(format!(
-            "No stdout from PowerShell, command was '{}'",
-            powershell_cmd
-        ),)),
-        Some(stdout_text) => Ok(stdout_text.split("\r\n").map(|s| s.to_string()).collect()),
-    }
-}
-
-fn get_property_from_stdout(stdout_strings: Vec<String>, property_name: &str) -> String {
-    let binding = "".to_string();
-    let property = stdout_strings
-        .iter()
-        .find(|s| s.starts_with(property_name))
-        .unwrap_or(&binding);
-    let binding = property.replace(property_name, "");
-    let property_value = binding.trim();
-    property_value.to_string()
-}
-
-fn get_windows_file_version_information(
-    full_path: &str,
-) -> Result<(String, String, String, String), eyre::Report> {
-    let stdout_result = run_powershell_cmd(&format!(
-        r#"(Get-Item "{}").VersionInfo.FileVersionRaw | Format-List -Property Major, Minor, Build, Revision"#,
-        full_path
-    ));
-
-    match stdout_result {
-        Ok(stdout_strings) => {
-            let major = get_property_from_stdout(stdout_strings.clone(), "Major    : ");
-            let minor = get_property_from_stdout(stdout_strings.clone(), "Minor    : ");
-            let build = get_property_from_stdout(stdout_strings.clone(), "Build    : ");
-            let revision = get_property_from_stdout(stdout_strings, "Revision : ");
-
-            Ok((major, minor, build, revision))
-        }
-        Err(e) => Err(e),
-    }
-}
-
-fn get_windows_file_description_information(full_path: &str) -> Result<String, eyre::Report> {
-    let stdout_result = run_powershell_cmd(&format!(
-        r#"(Get-Item "{}").VersionInfo | Format-List -Property FileDescription, ProductName"#,
-        full_path
-    ));
-
-    match stdout_result {
-        Ok(stdout_strings) => {
-            let app_name = get_property_from_stdout(stdout_strings.clone(), "FileDescription :");
-            if !app_name.is_empty() {
-                return Ok(app_name);
-            };
-            let app_name = get_property_from_stdout(stdout_strings, "ProductName     :");
-            if !app_name.is_empty() {
-                return Ok(app_name);
-            };
-            Ok(paths::extract_file_name(full_path).to_string())
-        }
-        Err(e) => Err(e),
-    }
-}
+use crate::{constants, data, paths, pe_version};
 
 fn get_macos_file_version_information(full_path: &str) -> Result<(String, String), eyre::Report> {
     // Construct path to Info.plist
@@ -119,12 +41,11 @@ fn get_macos_file_version_information(full_path: &str) -> Result<(String, String
     Ok((version_str.to_string(), app_name_str.to_string()))
 }
 
-fn parse_file_version_to_u32(version: &str) -> Option<(String, u32, u32, u32, Option<u32>, String)> {
+fn parse_file_version_to_u32(
+    version: &str,
+) -> Option<(String, u32, u32, u32, Option<u32>, String)> {
     // Split the version string into parts separated by '.'
-    let mut parts: Vec<_> = version
-        .split('.')
-        .map(|part| part.to_string())
-        .collect();
+    let mut parts: Vec<_> = version.split('.').map(|part| part.to_string()).collect();
 
     // Extract the prefix and suffix from the first and last parts
     let mut prefix = String::new();
@@ -138,7 +59,12 @@ fn parse_file_version_to_u32(version: &str) -> Option<(String, u32, u32, u32, Op
     }
 
     // Does the build/revision contain a suffix?
-    if let Some(index) = parts.last().unwrap().chars().position(|c| c.is_alphabetic()) {
+    if let Some(index) = parts
+        .last()
+        .unwrap()
+        .chars()
+        .position(|c| c.is_alphabetic())
+    {
         let (pre, post) = parts.last_mut().unwrap().split_at(index);
         suffix = post.to_string();
         *parts.last_mut().unwrap() = pre.to_string(); // update the parts array with just the number
@@ -164,21 +90,208 @@ fn parse_file_version_to_u32(version: &str) -> Option<(String, u32, u32, u32, Op
     Some((prefix, major, minor, build, revision, suffix))
 }
 
+fn parse_version_output(output: &str) -> Option<data::FileVersion> {
+    // Engines print something like "dsda-doom v0.27.5", "Crispy Doom 6.0" or "GZDoom g4.11.3 - 2023-10-23"
+    let re =
+        Regex::new(r"(?:^|\s)([a-zA-Z]?)(\d+)\.(\d+)(?:\.(\d+))?(?:\.(\d+))?([a-zA-Z]*)").unwrap();
+
+    for line in output.lines() {
+        if let Some(captures) = re.captures(line) {
+            let prefix = captures.get(1).map_or("", |m| m.as_str());
+            let app_name = line[..captures.get(0).unwrap().start()].trim();
+            return Some(data::FileVersion {
+                app_name: app_name.to_string(),
+                // A "v" is just short for version, so it isn't kept
+                prefix: if prefix.eq_ignore_ascii_case("v") {
+                    "".to_string()
+                } else {
+                    prefix.to_string()
+                },
+                major: captures[2].parse::<u32>().unwrap_or(0),
+                minor: captures[3].parse::<u32>().unwrap_or(0),
+                build: captures
+                    .get(4)
+                    .map_or(0, |m| m.as_str().parse::<u32>().unwrap_or(0)),
+                revision: captures
+                    .get(5)
+                    .map(|m| m.as_str().parse::<u32>().unwrap_or(0)),
+                suffix: captures.get(6).map_or("", |m| m.as_str()).to_string(),
+            });
+        }
+    }
+
+    None
+}
+
+fn get_path_version(full_path: &str) -> Option<data::FileVersion> {
+    // AppImages and unpacked releases often carry the version, such as
+    // "GZDoom-4.11.3-x86_64.AppImage" or "/opt/gzdoom-4.11.3/gzdoom"
+    let path = Path::new(full_path);
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let folder_name = path
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    [file_name, folder_name]
+        .iter()
+        .find_map(|name| parse_version_output(&name.replace(['-', '_'], " ")))
+}
+
+fn run_package_query(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn get_package_version(full_path: &str) -> Option<String> {
+    // "gzdoom: /usr/games/gzdoom"
+    if let Some(owner) = run_package_query("dpkg-query", &["-S", full_path]) {
+        let package = owner
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if let Some(version) = run_package_query("dpkg-query", &["-W", "-f=${Version}", &package]) {
+            return Some(version);
+        }
+    }
+
+    if let Some(version) =
+        run_package_query("rpm", &["-qf", "--queryformat", "%{VERSION}", full_path])
+    {
+        return Some(version);
+    }
+
+    // "/usr/bin/gzdoom is owned by gzdoom 4.11.3-1"
+    run_package_query("pacman", &["-Qo", full_path])
+        .and_then(|owner| owner.split_whitespace().last().map(|s| s.to_string()))
+}
+
+fn parse_package_version(version: &str) -> Option<data::FileVersion> {
+    // Drop any epoch, "1:4.11.3-1" is version "4.11.3"
+    let version = version.rsplit(':').next().unwrap_or(version);
+    parse_version_output(&version.replace(['-', '_', '+', '~'], " "))
+}
+
+fn get_linux_file_version(full_path: &str) -> Result<data::FileVersion, eyre::Report> {
+    // Not every Engine or Editor knows "--version", some open a window instead, so
+    // the version comes from the file name or the package that installed it
+    let file_version = get_path_version(full_path).or_else(|| {
+        get_package_version(full_path).and_then(|version| parse_package_version(&version))
+    });
+
+    match file_version {
+        Some(mut file_version) => {
+            file_version.app_name = paths::extract_file_name(full_path);
+            Ok(file_version)
+        }
+        None => Err(eyre::eyre!(format!(
+            "Unable to find a version for '{}' in its file name or package details",
+            full_path,
+        ))),
+    }
+}
+
+pub fn get_version_argument_file_version(
+    full_path: &str,
+) -> Result<data::FileVersion, eyre::Report> {
+    if env::consts::OS != constants::OS_LINUX {
+        return get_file_version(full_path);
+    }
+
+    run_version_argument(full_path, "--version")
+}
+
+fn run_version_argument(
+    full_path: &str,
+    version_argument: &str,
+) -> Result<data::FileVersion, eyre::Report> {
+    // Only for Engines that print their version and exit. The wait is a safety net, as
+    // a stopped wrapper script can leave the real Engine running.
+    // Output goes to a file rather than a pipe, so a stopped Engine's child processes
+    // can't hold the pipe open
+    let output_path = paths::get_full_path(
+        &paths::get_temp_dir(),
+        &format!("dcli_version_{}.txt", std::process::id()),
+    );
+    let output_file = paths::create_file(&output_path)?;
+    let mut child = Command::new(full_path)
+        .arg(version_argument)
+        .stdin(Stdio::null())
+        .stdout(output_file.try_clone()?)
+        .stderr(output_file)
+        .spawn()
+        .wrap_err(format!(
+            "Failed to run '{} {}'",
+            full_path, version_argument
+        ))?;
+
+    let started = Instant::now();
+    while child.try_wait()?.is_none() {
+        if started.elapsed() > Duration::from_secs(constants::VERSION_CHECK_TIMEOUT_SECONDS) {
+            log::debug!(
+                "Stopping '{}', it did not exit after '{}'",
+                full_path,
+                version_argument
+            );
+            child.kill()?;
+            child.wait()?;
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let output_text = String::from_utf8_lossy(&fs::read(&output_path)?).to_string();
+    paths::delete_file(&output_path)?;
+
+    match parse_version_output(&output_text) {
+        Some(mut file_version) => {
+            if file_version.app_name.is_empty() {
+                file_version.app_name = paths::extract_file_name(full_path);
+            }
+            Ok(file_version)
+        }
+        None => Err(eyre::eyre!(format!(
+            "Unable find version in output from '{} {}'",
+            full_path, version_argument,
+        ))),
+    }
+}
 
 pub fn get_file_version(full_path: &str) -> Result<data::FileVersion, eyre::Report> {
-    if env::consts::OS == constants::OS_WINDOWS {
-        let (major, minor, build, revision) = get_windows_file_version_information(full_path)?;
-        let app_name = get_windows_file_description_information(full_path)?;
-
-        return Ok(data::FileVersion {
-            app_name: app_name.to_string(),
-            prefix: "".to_string(),
-            major: major.parse::<u32>().unwrap_or(0),
-            minor: minor.parse::<u32>().unwrap_or(0),
-            build: build.parse::<u32>().unwrap_or(0),
-            revision: Some(revision.parse::<u32>().unwrap_or(0)),
-            suffix: "".to_string(),
-        });
+    // Windows executables carry their own version details, which can be read on any OS. Those
+    // without are still added, as version 0.0.0.0
+    if pe_version::is_pe_file(full_path) {
+        return Ok(
+            pe_version::get_pe_file_version(full_path).unwrap_or_else(|e| {
+                log::warn!(
+                    "Unable to read the version of '{}', using 0.0.0.0: {}",
+                    full_path,
+                    e
+                );
+                data::FileVersion {
+                    app_name: paths::extract_file_name(full_path),
+                    prefix: "".to_string(),
+                    major: 0,
+                    minor: 0,
+                    build: 0,
+                    revision: Some(0),
+                    suffix: "".to_string(),
+                }
+            }),
+        );
     }
 
     if env::consts::OS == constants::OS_MACOS {
@@ -205,60 +318,42 @@ pub fn get_file_version(full_path: &str) -> Result<data::FileVersion, eyre::Repo
         }
     }
 
+    if env::consts::OS == constants::OS_LINUX {
+        return get_linux_file_version(full_path);
+    }
+
     Err(eyre::eyre!(format!(
-        "get_file_version is only supported on Windows and macOS, not on '{}'",
+        "get_file_version is only supported on Windows, macOS and Linux, not on '{}'",
         env::consts::OS
     )))
 }
 
 pub fn get_prboom_file_version(full_path: &str) -> Result<data::FileVersion, eyre::Report> {
+    if env::consts::OS == constants::OS_LINUX {
+        return get_version_argument_file_version(full_path);
+    }
+
     if env::consts::OS != constants::OS_WINDOWS {
         return Err(eyre::eyre!(format!(
-            "prboom_file_version is only supported on Windows, not on '{}'",
+            "prboom_file_version is only supported on Windows and Linux, not on '{}'",
             env::consts::OS
         )));
     }
 
-    let stdout_result = run_powershell_cmd(&format!(r#"{} -v"#, full_path));
-    match stdout_result {
-        Ok(stdout_strings) => {
-            let input = stdout_strings[0].clone();
-
-            // Create a regular expression to match the version pattern
-            let re = Regex::new(r"v(\d+)\.(\d+)\.(\d+)").unwrap();
-
-            // Use the regex to capture major, minor, and patch versions
-            if let Some(captures) = re.captures(&input) {
-                let major = captures[1].parse::<u32>().unwrap_or(0);
-                let minor = captures[2].parse::<u32>().unwrap_or(0);
-                let build = captures[3].parse::<u32>().unwrap_or(0);
-
-                // Extract the app name (assuming it's the part before " v")
-                let app_name = input.split(" v").next().unwrap();
-
-                Ok(data::FileVersion {
-                    app_name: app_name.to_string(),
-                    prefix: "".to_string(),
-                    major,
-                    minor,
-                    build,
-                    revision: None,
-                    suffix: "".to_string(),
-                })
-            } else {
-                Err(eyre::eyre!(format!(
-                    "Unable find version in output from '{}",
-                    input,
-                )))
-            }
-        }
-        Err(e) => Err(e),
-    }
+    // Builds with a version resource can be read without running them, others print
+    // their version with "-v"
+    pe_version::get_pe_file_version(full_path).or_else(|e| {
+        log::debug!("Running '{} -v', no version resource: {:?}", full_path, e);
+        run_version_argument(full_path, "-v")
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::finder::parse_file_version_to_u32;
+    use crate::finder::{
+        get_file_version, get_path_version, parse_file_version_to_u32, parse_package_version,
+        parse_version_output,
+    };
 
     #[test]
     fn test_parse_file_version_to_u32_happy_path_simple_number() {
@@ -319,4 +414,76 @@ mod tests {
         let actual_unwrapped = actual.unwrap();
         assert_eq!(actual_unwrapped, expected);
     }
+
+    #[test]
+    fn test_parse_version_output_dsda() {
+        // Arrange
+        let output = "dsda-doom v0.27.5\n";
+
+        // Act
+        let actual = parse_version_output(output);
+
+        // Assert
+        let actual_unwrapped = actual.unwrap();
+        assert_eq!(actual_unwrapped.app_name, "dsda-doom");
+        assert_eq!(actual_unwrapped.display_version(), "0.27.5");
+    }
+
+    #[test]
+    fn test_parse_version_output_crispy_and_gzdoom() {
+        // Arrange
+        let crispy_output = "Crispy Doom 6.0\nCopyright(C) 1993-1996 Id Software, Inc.\n";
+        let gzdoom_output = "GZDoom g4.11.3 - 2023-10-23 10:08:41\nCompiled on Oct 23 2023\n";
+
+        // Act
+        let actual_crispy = parse_version_output(crispy_output).unwrap();
+        let actual_gzdoom = parse_version_output(gzdoom_output).unwrap();
+
+        // Assert
+        assert_eq!(actual_crispy.app_name, "Crispy Doom");
+        assert_eq!(actual_crispy.display_version(), "6.0.0");
+        assert_eq!(actual_gzdoom.app_name, "GZDoom");
+        assert_eq!(actual_gzdoom.display_version(), "g4.11.3");
+    }
+
+    #[test]
+    fn test_get_path_version_from_folder_name() {
+        // Arrange
+        let full_path = "/opt/gzdoom-g4.11.3/gzdoom";
+
+        // Act
+        let actual = get_path_version(full_path);
+
+        // Assert
+        assert_eq!(actual.unwrap().display_version(), "g4.11.3");
+        assert!(get_path_version("/usr/games/gzdoom").is_none());
+    }
+
+    #[test]
+    fn test_parse_package_version() {
+        // Arrange
+        let dpkg_version = "1:4.11.3-1";
+        let pacman_version = "0.27.5-2";
+
+        // Act
+        let actual_dpkg = parse_package_version(dpkg_version);
+        let actual_pacman = parse_package_version(pacman_version);
+
+        // Assert
+        assert_eq!(actual_dpkg.unwrap().display_version(), "4.11.3");
+        assert_eq!(actual_pacman.unwrap().display_version(), "0.27.5");
+    }
+
+    #[test]
+    fn test_get_file_version_no_version_resource() {
+        // Act
+        let actual = get_file_version("./test-data/no_version.exe").unwrap();
+
+        // Assert
+        assert_eq!(actual.app_name, "no_version.exe");
+        assert_eq!(
+            (actual.major, actual.minor, actual.build, actual.revision),
+            (0, 0, 0, Some(0))
+        );
+    }
 }
@@ -0,0 +1,166 @@
+use std::{env, process::Command};
+
+use eyre::Context;
+use owo_colors::OwoColorize;
+use strum_macros::Display;
+
+use crate::constants;
+
+// Hooks are shell commands run around a Doom session, set globally in App Settings and
+// per Profile. What dcli knows at that point is passed to them as environment variables.
+
+const ENV_HOOK: &str = "DCLI_HOOK";
+const ENV_PROFILE_NAME: &str = "DCLI_PROFILE_NAME";
+const ENV_ENGINE_PATH: &str = "DCLI_ENGINE_PATH";
+const ENV_IWAD_PATH: &str = "DCLI_IWAD_PATH";
+const ENV_MAP_PATHS: &str = "DCLI_MAP_PATHS";
+const ENV_SESSION_DURATION: &str = "DCLI_SESSION_DURATION";
+const ENV_EXIT_CODE: &str = "DCLI_EXIT_CODE";
+const ENV_DOWNLOAD_FOLDER: &str = "DCLI_DOWNLOAD_FOLDER";
+
+#[derive(Clone, Debug, PartialEq, Display)]
+pub enum HookPoint {
+    #[strum(serialize = "Pre-Launch")]
+    PreLaunch,
+    #[strum(serialize = "Post-Exit")]
+    PostExit,
+    #[strum(serialize = "Post-Download")]
+    PostDownload,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HookContext {
+    pub profile_name: Option<String>,
+    pub engine_path: Option<String>,
+    pub iwad_path: Option<String>,
+    pub map_paths: Vec<String>,
+    pub session_duration: Option<i64>,
+    pub exit_code: Option<i32>,
+    pub download_folder: Option<String>,
+}
+
+pub fn has_hooks(hooks: &[Option<String>]) -> bool {
+    hooks.iter().flatten().any(|hook| !hook.trim().is_empty())
+}
+
+pub fn get_hook_environment(
+    hook_point: &HookPoint,
+    context: &HookContext,
+) -> Vec<(String, String)> {
+    let mut environment = vec![(ENV_HOOK.to_string(), hook_point.to_string())];
+    let mut add = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            environment.push((key.to_string(), value));
+        }
+    };
+
+    add(ENV_PROFILE_NAME, context.profile_name.clone());
+    add(ENV_ENGINE_PATH, context.engine_path.clone());
+    add(ENV_IWAD_PATH, context.iwad_path.clone());
+    // Map paths are joined the same way as PATH, so they can be split up by the hook
+    add(
+        ENV_MAP_PATHS,
+        env::join_paths(&context.map_paths)
+            .ok()
+            .map(|map_paths| map_paths.to_string_lossy().to_string())
+            .filter(|map_paths| !map_paths.is_empty()),
+    );
+    add(
+        ENV_SESSION_DURATION,
+        context
+            .session_duration
+            .map(|duration| duration.to_string()),
+    );
+    add(
+        ENV_EXIT_CODE,
+        context.exit_code.map(|exit_code| exit_code.to_string()),
+    );
+    add(ENV_DOWNLOAD_FOLDER, context.download_folder.clone());
+
+    environment
+}
+
+pub fn run_hooks(
+    hook_point: HookPoint,
+    hooks: &[Option<String>],
+    context: &HookContext,
+) -> Result<(), eyre::Report> {
+    let environment = get_hook_environment(&hook_point, context);
+    for hook in hooks.iter().flatten() {
+        if hook.trim().is_empty() {
+            continue;
+        }
+
+        log::info!("Running {} Hook '{}'", hook_point, hook.magenta());
+        let mut cmd = get_shell_command(hook);
+        let exit_status = cmd
+            .envs(environment.clone())
+            .status()
+            .wrap_err(format!("Failed to run {} Hook '{}'", hook_point, hook))?;
+        if !exit_status.success() {
+            return Err(eyre::eyre!(
+                "{} Hook '{}' exited with '{}'",
+                hook_point,
+                hook,
+                exit_status
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn get_shell_command(hook: &str) -> Command {
+    if env::consts::OS == constants::OS_WINDOWS {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(hook);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(hook);
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hooks::{get_hook_environment, has_hooks, HookContext, HookPoint};
+
+    #[test]
+    fn test_get_hook_environment_post_exit() {
+        // Arrange
+        let context = HookContext {
+            profile_name: Some("Coop Scythe".to_string()),
+            engine_path: Some("/usr/bin/gzdoom".to_string()),
+            map_paths: vec!["/maps/scythe.wad".to_string()],
+            session_duration: Some(125),
+            exit_code: Some(0),
+            ..Default::default()
+        };
+
+        // Act
+        let actual = get_hook_environment(&HookPoint::PostExit, &context);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                ("DCLI_HOOK".to_string(), "Post-Exit".to_string()),
+                ("DCLI_PROFILE_NAME".to_string(), "Coop Scythe".to_string()),
+                (
+                    "DCLI_ENGINE_PATH".to_string(),
+                    "/usr/bin/gzdoom".to_string()
+                ),
+                ("DCLI_MAP_PATHS".to_string(), "/maps/scythe.wad".to_string()),
+                ("DCLI_SESSION_DURATION".to_string(), "125".to_string()),
+                ("DCLI_EXIT_CODE".to_string(), "0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_has_hooks_ignores_blank() {
+        assert!(!has_hooks(&[None, Some("  ".to_string())]));
+        assert!(has_hooks(&[None, Some("backup.sh".to_string())]));
+    }
+}
@@ -0,0 +1,353 @@
+use std::{env, fs, path::Path, process::Command};
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{
+    doom_data::{self, OperatingSystem},
+    paths,
+};
+
+// Finds folders that are likely to hold IWADs, so they can be searched along with the folder
+// picked in init. Covers Steam and GOG installs, the DOOMWADDIR and DOOMWADPATH variables
+// read by most source ports, and the folders Linux distributions install IWADs to.
+
+const ENV_DOOMWADDIR: &str = "DOOMWADDIR";
+const ENV_DOOMWADPATH: &str = "DOOMWADPATH";
+const STEAM_LIBRARY_FOLDERS: &str = "libraryfolders.vdf";
+const STEAM_APPS_FOLDER: &str = "steamapps";
+const STEAM_COMMON_FOLDER: &str = "common";
+const GOG_REGISTRY_KEY: &str = r"HKLM\SOFTWARE\WOW6432Node\GOG.com\Games";
+const HEROIC_GOG_INSTALLED: &str = "gog_store/installed.json";
+
+#[derive(Clone, Debug, PartialEq)]
+struct SteamAppManifest {
+    app_id: String,
+    name: String,
+    install_dir: String,
+}
+
+pub fn find_iwad_folders() -> Vec<String> {
+    let mut folders: Vec<String> = Vec::new();
+    folders.extend(get_environment_folders());
+    folders.extend(
+        doom_data::LINUX_IWAD_FOLDERS
+            .iter()
+            .filter(|_| doom_data::get_operating_system() == OperatingSystem::Linux)
+            .map(|folder| paths::resolve_path(folder)),
+    );
+    folders.extend(get_steam_folders());
+    folders.extend(get_gog_folders());
+
+    let mut result: Vec<String> = Vec::new();
+    for folder in folders {
+        if paths::folder_exists(&folder) && !result.contains(&folder) {
+            result.push(folder);
+        }
+    }
+    result
+}
+
+fn get_environment_folders() -> Vec<String> {
+    let mut folders: Vec<String> = Vec::new();
+    if let Some(folder) = env::var_os(ENV_DOOMWADDIR) {
+        folders.push(folder.to_string_lossy().to_string());
+    }
+    if let Some(path) = env::var_os(ENV_DOOMWADPATH) {
+        folders.extend(env::split_paths(&path).map(|folder| folder.display().to_string()));
+    }
+    folders
+}
+
+fn get_steam_roots() -> Vec<String> {
+    match doom_data::get_operating_system() {
+        OperatingSystem::Windows => {
+            let program_files = env::var("ProgramFiles(x86)")
+                .unwrap_or_else(|_| r"C:\Program Files (x86)".to_string());
+            vec![Path::new(&program_files)
+                .join("Steam")
+                .display()
+                .to_string()]
+        }
+        OperatingSystem::Linux => vec![
+            paths::resolve_path("~/.steam/steam"),
+            paths::resolve_path("~/.local/share/Steam"),
+            paths::resolve_path("~/.var/app/com.valvesoftware.Steam/.local/share/Steam"),
+        ],
+        OperatingSystem::MacOs => vec![paths::resolve_path("~/Library/Application Support/Steam")],
+    }
+}
+
+fn get_steam_folders() -> Vec<String> {
+    let steam_apps = doom_data::get_steam_app_list();
+    let mut folders: Vec<String> = Vec::new();
+
+    for steam_root in get_steam_roots() {
+        let library_folders_path = Path::new(&steam_root)
+            .join(STEAM_APPS_FOLDER)
+            .join(STEAM_LIBRARY_FOLDERS);
+        let Ok(text) = fs::read_to_string(&library_folders_path) else {
+            continue;
+        };
+
+        for library in parse_library_folders(&text) {
+            let steam_apps_folder = Path::new(&library).join(STEAM_APPS_FOLDER);
+            for steam_app in &steam_apps {
+                let manifest_path =
+                    steam_apps_folder.join(format!("appmanifest_{}.acf", steam_app.app_id));
+                let Some(manifest) = fs::read_to_string(&manifest_path)
+                    .ok()
+                    .and_then(|text| parse_app_manifest(&text))
+                else {
+                    continue;
+                };
+                log::debug!("Found Steam app {}: {:?}", steam_app.name, manifest);
+                folders.push(
+                    steam_apps_folder
+                        .join(STEAM_COMMON_FOLDER)
+                        .join(&manifest.install_dir)
+                        .display()
+                        .to_string(),
+                );
+            }
+        }
+    }
+
+    folders
+}
+
+fn get_gog_folders() -> Vec<String> {
+    let mut folders: Vec<String> = Vec::new();
+
+    if doom_data::get_operating_system() == OperatingSystem::Windows {
+        match Command::new("reg")
+            .args(["query", GOG_REGISTRY_KEY, "/s"])
+            .output()
+        {
+            Ok(output) => folders.extend(
+                parse_gog_registry(&String::from_utf8_lossy(&output.stdout))
+                    .into_iter()
+                    .filter(|(game_name, _)| is_gog_game(game_name))
+                    .map(|(_, path)| path),
+            ),
+            Err(e) => log::debug!("Unable to read GOG registry: {}", e),
+        }
+    }
+
+    // Heroic names each install folder after the game, as do the GOG Linux installers
+    let heroic_configs = [
+        "~/.config/heroic",
+        "~/.var/app/com.heroicgameslauncher.hgl/config/heroic",
+        "~/Library/Application Support/heroic",
+    ];
+    for heroic_config in heroic_configs {
+        let installed_path =
+            Path::new(&paths::resolve_path(heroic_config)).join(HEROIC_GOG_INSTALLED);
+        if let Ok(text) = fs::read_to_string(installed_path) {
+            folders.extend(
+                parse_heroic_installed(&text)
+                    .into_iter()
+                    .filter(|path| is_gog_game(&paths::extract_file_name(path))),
+            );
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(paths::resolve_path("~/GOG Games")) {
+        folders.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path().display().to_string())
+                .filter(|path| is_gog_game(&paths::extract_file_name(path))),
+        );
+    }
+
+    folders
+}
+
+fn is_gog_game(game_name: &str) -> bool {
+    let game_name = game_name.to_lowercase();
+    doom_data::GOG_GAME_TITLES
+        .iter()
+        .any(|title| game_name.starts_with(&title.to_lowercase()))
+}
+
+// Reads "key" "value" pairs from Valve's KeyValues text format, nesting is ignored
+fn get_vdf_values(text: &str) -> Vec<(String, String)> {
+    let pair_re = Regex::new(r#"(?m)^\s*"([^"]*)"\s+"((?:[^"\\]|\\.)*)""#).unwrap();
+    pair_re
+        .captures_iter(text)
+        .map(|captures| (captures[1].to_string(), captures[2].replace(r"\\", r"\")))
+        .collect()
+}
+
+// Newer files give each library a "path" key, older ones use numbered keys: "1" "D:\\Steam"
+fn parse_library_folders(text: &str) -> Vec<String> {
+    get_vdf_values(text)
+        .into_iter()
+        .filter(|(key, value)| {
+            key.eq_ignore_ascii_case("path")
+                || (key.chars().all(|c| c.is_ascii_digit())
+                    && !value.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(|(_, value)| value)
+        .collect()
+}
+
+fn parse_app_manifest(text: &str) -> Option<SteamAppManifest> {
+    let values = get_vdf_values(text);
+    let get_value = |key: &str| {
+        values
+            .iter()
+            .find(|(value_key, _)| value_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.clone())
+    };
+
+    Some(SteamAppManifest {
+        app_id: get_value("appid")?,
+        name: get_value("name").unwrap_or_default(),
+        install_dir: get_value("installdir")?,
+    })
+}
+
+// Output of 'reg query <key> /s', a line per key then indented 'name  REG_SZ  value' lines
+fn parse_gog_registry(text: &str) -> Vec<(String, String)> {
+    let mut result: Vec<(String, String)> = Vec::new();
+    let mut game_name: Option<String> = None;
+    let mut path: Option<String> = None;
+
+    for line in text.lines().chain(std::iter::once("HKEY_END")) {
+        if line.starts_with("HKEY_") {
+            if let (Some(game_name), Some(path)) = (game_name.take(), path.take()) {
+                result.push((game_name, path));
+            }
+            continue;
+        }
+        let Some((name, value)) = line.trim().split_once("REG_SZ") else {
+            continue;
+        };
+        match name.trim().to_lowercase().as_str() {
+            "gamename" => game_name = Some(value.trim().to_string()),
+            "path" => path = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn parse_heroic_installed(text: &str) -> Vec<String> {
+    let Ok(json) = serde_json::from_str::<Value>(text) else {
+        return Vec::new();
+    };
+    json["installed"]
+        .as_array()
+        .map(|installed| {
+            installed
+                .iter()
+                .filter_map(|game| game["install_path"].as_str())
+                .map(|install_path| install_path.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::iwad_discovery::{
+        is_gog_game, parse_app_manifest, parse_gog_registry, parse_heroic_installed,
+        parse_library_folders, SteamAppManifest,
+    };
+
+    #[test]
+    fn test_parse_library_folders() {
+        // Arrange
+        let text = fs::read_to_string("./test-data/libraryfolders.vdf").unwrap();
+
+        // Act
+        let actual = parse_library_folders(&text);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                "/home/doomguy/.local/share/Steam".to_string(),
+                r"D:\SteamLibrary".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_library_folders_old_format() {
+        // Arrange
+        let text = "\"LibraryFolders\"\n{\n\t\"TimeNextStatsReport\"\t\t\"1234567890\"\n\t\"1\"\t\t\"E:\\\\Games\\\\Steam\"\n}\n";
+
+        // Act
+        let actual = parse_library_folders(text);
+
+        // Assert
+        assert_eq!(actual, vec![r"E:\Games\Steam".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_app_manifest() {
+        // Arrange
+        let text = fs::read_to_string("./test-data/appmanifest_2300.acf").unwrap();
+
+        // Act
+        let actual = parse_app_manifest(&text);
+
+        // Assert
+        assert_eq!(
+            actual,
+            Some(SteamAppManifest {
+                app_id: "2300".to_string(),
+                name: "DOOM II".to_string(),
+                install_dir: "Doom 2".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_gog_registry() {
+        // Arrange
+        let text = fs::read_to_string("./test-data/gog_reg_query.txt").unwrap();
+
+        // Act
+        let actual: Vec<(String, String)> = parse_gog_registry(&text)
+            .into_iter()
+            .filter(|(game_name, _)| is_gog_game(game_name))
+            .collect();
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                ("DOOM II".to_string(), r"C:\GOG Games\DOOM II".to_string()),
+                (
+                    "Heretic: Shadow of the Serpent Riders".to_string(),
+                    r"C:\GOG Games\Heretic".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_heroic_installed() {
+        // Arrange
+        let text = fs::read_to_string("./test-data/heroic_installed.json").unwrap();
+
+        // Act
+        let actual = parse_heroic_installed(&text);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                "/home/doomguy/Games/Heroic/DOOM + DOOM II".to_string(),
+                "/home/doomguy/Games/Heroic/Cyberpunk 2077".to_string(),
+            ]
+        );
+    }
+}
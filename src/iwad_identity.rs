@@ -0,0 +1,260 @@
+use crate::{
+    doom_data::{self, InternalWadType},
+    files, wad,
+};
+
+// Works out which game and release an IWAD is from its contents, so renamed files like
+// doom2_bfg.wad or the Unity DOOM2.WAD are not taken at their file name. Known releases are
+// matched by MD5, anything else (patched, repacked or newer) by the lumps it contains.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IwadIdentity {
+    pub internal_wad_type: InternalWadType,
+    pub release: Option<String>,
+}
+
+// The MD5 comes from the scan, which hashes every file it finds
+pub fn identify_iwad(iwad_path: &str, md5: Option<&str>) -> Option<IwadIdentity> {
+    if let Some(identity) = md5.and_then(find_release_by_md5) {
+        return Some(identity);
+    }
+
+    match wad::read_wad_directory(iwad_path) {
+        Ok(wad_directory) => {
+            let lump_names: Vec<&str> = wad_directory
+                .lumps
+                .iter()
+                .map(|lump| lump.name.as_str())
+                .collect();
+            if let Some(identity) = identify_by_lumps(&lump_names) {
+                return Some(identity);
+            }
+        }
+        Err(e) => log::debug!("Unable to read IWAD directory: {}", e),
+    }
+
+    // Last resort, trust the file name
+    files::get_internal_wad_type_from_file_name(doom_data::get_internal_wad_list(), iwad_path)
+        .ok()
+        .map(|internal_wad_type| IwadIdentity {
+            internal_wad_type,
+            release: None,
+        })
+}
+
+fn find_release_by_md5(md5: &str) -> Option<IwadIdentity> {
+    doom_data::get_iwad_release_list()
+        .into_iter()
+        .find(|release| release.md5.eq_ignore_ascii_case(md5))
+        .map(|release| IwadIdentity {
+            internal_wad_type: release.internal_wad_type,
+            release: Some(release.release),
+        })
+}
+
+// Checked most specific first, every Doom IWAD has E1M1 or MAP01 but only some have E4M1
+fn identify_by_lumps(lump_names: &[&str]) -> Option<IwadIdentity> {
+    let has = |name: &str| lump_names.contains(&name);
+    let identity = |internal_wad_type: InternalWadType, release: Option<&str>| {
+        Some(IwadIdentity {
+            internal_wad_type,
+            release: release.map(|release| release.to_string()),
+        })
+    };
+
+    if has("FREEDOOM") {
+        return if has("FREEDM") {
+            identity(InternalWadType::FreeDm, None)
+        } else if has("MAP01") {
+            identity(InternalWadType::FreedoomPhase2, None)
+        } else {
+            identity(InternalWadType::FreedoomPhase1, None)
+        };
+    }
+    if has("ENDSTRF") {
+        return if has("MAP01") {
+            identity(InternalWadType::Strife, None)
+        } else {
+            identity(InternalWadType::StrifeVoices, None)
+        };
+    }
+    if has("WINNOWR") || has("MAP60") {
+        return if has("MAP60") {
+            identity(InternalWadType::HexenDeathkings, None)
+        } else {
+            identity(InternalWadType::Hexen, None)
+        };
+    }
+    if has("MUS_E1M1") {
+        return if has("EXTENDED") {
+            identity(
+                InternalWadType::Heretic,
+                Some("Shadow of the Serpent Riders"),
+            )
+        } else if has("E2M1") {
+            identity(InternalWadType::Heretic, None)
+        } else {
+            identity(InternalWadType::HereticShareware, None)
+        };
+    }
+
+    // Games built on the Doom engine reuse its level slots, so are told apart by their own
+    // lumps, the same ones GZDoom's IWADINFO looks for
+    if has("E1M1") && has("CYCLA1") && has("FLMBA1") {
+        return identity(InternalWadType::ChexQuest3, None);
+    }
+    if has("E1M1") && has("W94_1") && has("POSSH0M0") {
+        return identity(InternalWadType::ChexQuest, None);
+    }
+    if has("MAP01") && has("HACX-R") {
+        return identity(InternalWadType::Hacx, None);
+    }
+    if has("MAP01") && has("0HAWK01") && has("0CARA3") && has("0NOSE1") {
+        return identity(InternalWadType::Harmony, None);
+    }
+    if has("E1M1") && has("REKCREDS") {
+        return identity(InternalWadType::Rekkr, None);
+    }
+
+    // The BFG Edition replaced the title and help screens, DMENUPIC is its menu background
+    let bfg_release = has("DMENUPIC").then_some("BFG Edition");
+    if has("MAP01") {
+        return if has("CAVERN5") {
+            identity(InternalWadType::Tnt, None)
+        } else if has("CAMO1") {
+            identity(InternalWadType::Plutonia, None)
+        } else {
+            identity(InternalWadType::Doom2, bfg_release)
+        };
+    }
+    if has("E4M1") {
+        return identity(
+            InternalWadType::Doom,
+            bfg_release.or(Some("The Ultimate Doom")),
+        );
+    }
+    if has("E2M1") {
+        return identity(InternalWadType::Doom, bfg_release);
+    }
+    if has("E1M1") {
+        return identity(InternalWadType::DoomShareware, None);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        doom_data::InternalWadType,
+        iwad_identity::{find_release_by_md5, identify_by_lumps, identify_iwad, IwadIdentity},
+    };
+
+    #[test]
+    fn test_find_release_by_md5() {
+        // Act
+        let actual = find_release_by_md5("C3BEA40570C23E511A7ED3EBCD9865F7");
+
+        // Assert
+        assert_eq!(
+            actual,
+            Some(IwadIdentity {
+                internal_wad_type: InternalWadType::Doom2,
+                release: Some("BFG Edition".to_string()),
+            })
+        );
+        assert_eq!(
+            find_release_by_md5("00000000000000000000000000000000"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_identify_by_lumps() {
+        // Arrange
+        let cases: Vec<(Vec<&str>, InternalWadType, Option<&str>)> = vec![
+            (
+                vec!["PLAYPAL", "MAP01", "DMENUPIC"],
+                InternalWadType::Doom2,
+                Some("BFG Edition"),
+            ),
+            (
+                vec!["E1M1", "E4M1"],
+                InternalWadType::Doom,
+                Some("The Ultimate Doom"),
+            ),
+            (vec!["E1M1"], InternalWadType::DoomShareware, None),
+            (vec!["MAP01", "CAVERN5"], InternalWadType::Tnt, None),
+            (
+                vec!["FREEDOOM", "MAP01"],
+                InternalWadType::FreedoomPhase2,
+                None,
+            ),
+            (
+                vec!["E1M1", "MUS_E1M1", "E2M1", "EXTENDED"],
+                InternalWadType::Heretic,
+                Some("Shadow of the Serpent Riders"),
+            ),
+            (
+                vec!["MAP01", "MAP40", "WINNOWR"],
+                InternalWadType::Hexen,
+                None,
+            ),
+            (vec!["MAP01", "ENDSTRF"], InternalWadType::Strife, None),
+        ];
+
+        for (lump_names, internal_wad_type, release) in cases {
+            // Act
+            let actual = identify_by_lumps(&lump_names);
+
+            // Assert
+            assert_eq!(
+                actual,
+                Some(IwadIdentity {
+                    internal_wad_type,
+                    release: release.map(|release| release.to_string()),
+                }),
+                "{:?}",
+                lump_names
+            );
+        }
+    }
+
+    #[test]
+    fn test_identify_iwad_doom_engine_games() {
+        // Arrange
+        let cases = vec![
+            ("./test-data/iwad_chex.wad", InternalWadType::ChexQuest),
+            ("./test-data/iwad_chex3.wad", InternalWadType::ChexQuest3),
+            ("./test-data/iwad_hacx.wad", InternalWadType::Hacx),
+            ("./test-data/iwad_harmony.wad", InternalWadType::Harmony),
+            ("./test-data/iwad_rekkr.wad", InternalWadType::Rekkr),
+            (
+                "./test-data/iwad_freedoom1.wad",
+                InternalWadType::FreedoomPhase1,
+            ),
+            (
+                "./test-data/iwad_freedoom2.wad",
+                InternalWadType::FreedoomPhase2,
+            ),
+            ("./test-data/iwad_freedm.wad", InternalWadType::FreeDm),
+        ];
+
+        for (iwad_path, internal_wad_type) in cases {
+            // Act
+            // An MD5 that isn't a known release, as for a renamed or repacked file
+            let actual = identify_iwad(iwad_path, Some("00000000000000000000000000000000"));
+
+            // Assert
+            assert_eq!(
+                actual,
+                Some(IwadIdentity {
+                    internal_wad_type,
+                    release: None,
+                }),
+                "{}",
+                iwad_path
+            );
+        }
+    }
+}
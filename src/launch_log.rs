@@ -0,0 +1,140 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    time::SystemTime,
+};
+
+use chrono::Local;
+use eyre::Context;
+use walkdir::WalkDir;
+
+use crate::paths;
+
+// Engine output for each launch is written to its own file, grouped in a folder per
+// Profile. Only the most recent logs for a Profile are kept.
+
+const LAUNCH_LOG_FOLDER: &str = "launch-logs";
+const LAUNCH_LOG_EXTENSION: &str = "log";
+const NO_PROFILE_FOLDER: &str = "no-profile";
+const LAUNCH_LOGS_TO_KEEP: usize = 10;
+pub const LAUNCH_LOG_TAIL_LINES: usize = 20;
+
+pub fn get_launch_log_root() -> String {
+    paths::get_full_path(&paths::get_app_data_dir(), LAUNCH_LOG_FOLDER)
+}
+
+pub fn create_launch_log(profile_name: Option<&str>) -> Result<(String, File), eyre::Report> {
+    let log_folder = paths::get_full_path(
+        &get_launch_log_root(),
+        &get_launch_log_folder_name(profile_name),
+    );
+    paths::create_folder(&log_folder).wrap_err(format!(
+        "Failed to create Launch Log folder '{}'",
+        log_folder
+    ))?;
+
+    // Milliseconds keep two launches in the same second from writing to the same log
+    let log_path = paths::get_full_path(
+        &log_folder,
+        &format!(
+            "launch_{}.{}",
+            Local::now().format("%Y%m%d_%H%M%S_%3f"),
+            LAUNCH_LOG_EXTENSION
+        ),
+    );
+    let log_file = paths::create_file(&log_path)
+        .wrap_err(format!("Failed to create Launch Log '{}'", log_path))?;
+
+    rotate_launch_logs(&log_folder)?;
+
+    Ok((log_path, log_file))
+}
+
+fn get_launch_log_folder_name(profile_name: Option<&str>) -> String {
+    match profile_name {
+        Some(profile_name) => paths::get_safe_file_name(profile_name),
+        None => NO_PROFILE_FOLDER.to_string(),
+    }
+}
+
+fn rotate_launch_logs(log_folder: &str) -> Result<(), eyre::Report> {
+    // Timestamped file names sort oldest first
+    let mut log_files = get_launch_log_files(log_folder);
+    log_files.sort();
+
+    if log_files.len() > LAUNCH_LOGS_TO_KEEP {
+        for log_file in &log_files[..log_files.len() - LAUNCH_LOGS_TO_KEEP] {
+            log::debug!("Removing old Launch Log '{}'", log_file.display());
+            fs::remove_file(log_file).wrap_err(format!(
+                "Failed to remove old Launch Log '{}'",
+                log_file.display()
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn get_launch_log_files(folder: &str) -> Vec<PathBuf> {
+    WalkDir::new(folder)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.file_type().is_file()
+                && entry
+                    .path()
+                    .extension()
+                    .is_some_and(|extension| extension == LAUNCH_LOG_EXTENSION)
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+pub fn get_last_launch_log() -> Option<String> {
+    get_launch_log_files(&get_launch_log_root())
+        .into_iter()
+        .max_by_key(|log_file| {
+            fs::metadata(log_file)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        })
+        .map(|log_file| log_file.display().to_string())
+}
+
+pub fn read_launch_log(log_path: &str) -> Result<String, eyre::Report> {
+    // Engines don't promise UTF-8 output
+    let bytes = fs::read(log_path).wrap_err(format!("Failed to read Launch Log '{}'", log_path))?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+pub fn get_tail(contents: &str, line_count: usize) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let start = lines.len().saturating_sub(line_count);
+    lines[start..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::launch_log::{get_launch_log_folder_name, get_tail};
+
+    #[test]
+    fn test_get_tail() {
+        // Arrange
+        let contents = "one\ntwo\nthree\nfour\n";
+
+        // Act
+        let actual = get_tail(contents, 2);
+
+        // Assert
+        assert_eq!(actual, "three\nfour");
+    }
+
+    #[test]
+    fn test_get_launch_log_folder_name() {
+        assert_eq!(
+            get_launch_log_folder_name(Some("Coop: Scythe 2")),
+            "Coop__Scythe_2"
+        );
+        assert_eq!(get_launch_log_folder_name(None), "no-profile");
+    }
+}
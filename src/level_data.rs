@@ -0,0 +1,506 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::File,
+    io::{BufReader, Cursor, Read, Seek},
+    path::Path,
+};
+
+use eyre::Context;
+use regex::Regex;
+use zip::ZipArchive;
+
+use crate::{
+    doom_data::{self, LevelFormat},
+    paths, wad,
+};
+
+// Reads the geometry and things of a single level, from a WAD or a level WAD inside a PK3.
+// Binary formats are described at https://doomwiki.org/wiki/WAD and UDMF at
+// https://doomwiki.org/wiki/UDMF
+
+const VERTEX_SIZE: usize = 4;
+const DOOM_LINEDEF_SIZE: usize = 14;
+const HEXEN_LINEDEF_SIZE: usize = 16;
+const SIDEDEF_SIZE: usize = 30;
+const SECTOR_SIZE: usize = 26;
+const DOOM_THING_SIZE: usize = 10;
+const HEXEN_THING_SIZE: usize = 20;
+const NO_SIDEDEF: u16 = 0xFFFF;
+const NO_TEXTURE: &str = "-";
+const TEXTURE_FIELDS: [&str; 5] = [
+    "texturetop",
+    "texturebottom",
+    "texturemiddle",
+    "texturefloor",
+    "textureceiling",
+];
+const TEXTURE_NAME_SIZE: usize = 8;
+
+// Doom sector 9 is a secret, Boom added a secret bit to its generalized sector types and
+// ZDoom has its own bit in Hexen format and UDMF
+const DOOM_SECRET_SPECIAL: u16 = 9;
+const BOOM_SECRET_FLAG: u16 = 0x80;
+const ZDOOM_SECRET_FLAG: u16 = 0x400;
+const DOOM_EXIT_SPECIALS: [u16; 3] = [11, 52, 197];
+const DOOM_SECRET_EXIT_SPECIALS: [u16; 3] = [51, 124, 198];
+// Teleport_NewMap, Teleport_EndGame and Exit_Normal, then Exit_Secret
+const HEXEN_EXIT_SPECIALS: [u16; 3] = [74, 75, 243];
+const HEXEN_SECRET_EXIT_SPECIALS: [u16; 1] = [244];
+const THING_EASY: u16 = 0x1;
+const THING_MEDIUM: u16 = 0x2;
+const THING_HARD: u16 = 0x4;
+const THING_MULTIPLAYER: u16 = 0x10;
+const THING_HEXEN_SINGLE: u16 = 0x100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineExit {
+    Normal,
+    Secret,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vertex {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Linedef {
+    pub v1: usize,
+    pub v2: usize,
+    pub front_sector: Option<usize>,
+    pub back_sector: Option<usize>,
+    pub exit: Option<LineExit>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sector {
+    pub secret: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thing {
+    pub x: f64,
+    pub y: f64,
+    pub thing_type: u16,
+    // Whether the thing appears on skills 1-2, 3 and 4-5
+    pub skills: [bool; 3],
+    pub single_player: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct LevelData {
+    pub vertexes: Vec<Vertex>,
+    pub linedefs: Vec<Linedef>,
+    pub sectors: Vec<Sector>,
+    pub things: Vec<Thing>,
+    // Wall textures and flats the level uses, in upper case
+    pub textures: BTreeSet<String>,
+}
+
+pub fn read_level(map_path: &str, lump_name: &str) -> Result<LevelData, eyre::Report> {
+    let extension = Path::new(map_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        doom_data::EXT_WAD => {
+            let file = File::open(map_path).wrap_err(format!("Failed to open '{}'", map_path))?;
+            read_level_from(&mut BufReader::new(file), Some(lump_name))
+        }
+        doom_data::EXT_PK3 | doom_data::EXT_PKE => {
+            let reader =
+                paths::open_file(map_path).wrap_err(format!("Failed to open '{}'", map_path))?;
+            let mut archive = ZipArchive::new(reader)
+                .wrap_err(format!("Failed to read archive '{}'", map_path))?;
+            let level_wad_name = format!("maps/{}.{}", lump_name, doom_data::EXT_WAD);
+            let Some(index) = (0..archive.len()).find(|&index| {
+                archive
+                    .name_for_index(index)
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&level_wad_name))
+            }) else {
+                return Err(eyre::eyre!(
+                    "Level '{}' not found in '{}'",
+                    lump_name,
+                    map_path
+                ));
+            };
+            let mut bytes = Vec::new();
+            archive.by_index(index)?.read_to_end(&mut bytes)?;
+            // The level takes its name from the file, whatever the marker inside says
+            read_level_from(&mut Cursor::new(bytes), None)
+        }
+        _ => Err(eyre::eyre!("Levels can't be read from '{}'", map_path)),
+    }
+    .wrap_err(format!("Failed to read level '{}'", lump_name))
+}
+
+// Reads the named level, or the first level when there is no name
+fn read_level_from<R: Read + Seek>(
+    reader: &mut R,
+    lump_name: Option<&str>,
+) -> Result<LevelData, eyre::Report> {
+    let wad_directory = wad::read_directory(reader)?;
+    let lump_name = match lump_name {
+        Some(lump_name) => lump_name.to_string(),
+        None => wad_directory
+            .get_levels()
+            .first()
+            .map(|level| level.lump_name.clone())
+            .ok_or_else(|| eyre::eyre!("No level found"))?,
+    };
+    let (level_format, level_lumps) = wad_directory
+        .get_level_lumps(&lump_name)
+        .ok_or_else(|| eyre::eyre!("Level '{}' not found", lump_name))?;
+
+    let mut read = |name: &str| -> Result<Vec<u8>, eyre::Report> {
+        match level_lumps.iter().find(|lump| lump.name == name) {
+            Some(lump) => wad::read_lump_from(reader, lump),
+            None => Ok(Vec::new()),
+        }
+    };
+
+    if level_format == LevelFormat::Udmf {
+        return Ok(parse_udmf(&String::from_utf8_lossy(&read("TEXTMAP")?)));
+    }
+    let things = read("THINGS")?;
+    let linedefs = read("LINEDEFS")?;
+    let sidedefs = read("SIDEDEFS")?;
+    let vertexes = read("VERTEXES")?;
+    let sectors = read("SECTORS")?;
+    Ok(parse_binary(
+        level_format,
+        &things,
+        &linedefs,
+        &sidedefs,
+        &vertexes,
+        &sectors,
+    ))
+}
+
+fn read_i16(bytes: &[u8], offset: usize) -> i16 {
+    i16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+// Texture names are up to eight characters, padded with zeros
+fn read_texture_name(bytes: &[u8], offset: usize) -> String {
+    let name = &bytes[offset..offset + TEXTURE_NAME_SIZE];
+    let length = name
+        .iter()
+        .position(|b| *b == 0)
+        .unwrap_or(TEXTURE_NAME_SIZE);
+    String::from_utf8_lossy(&name[..length]).to_uppercase()
+}
+
+fn add_texture(textures: &mut BTreeSet<String>, name: String) {
+    if !name.is_empty() && name != NO_TEXTURE {
+        textures.insert(name);
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn is_secret_sector(special: u16, is_zdoom: bool) -> bool {
+    if is_zdoom {
+        special & ZDOOM_SECRET_FLAG != 0
+    } else {
+        special == DOOM_SECRET_SPECIAL || special & BOOM_SECRET_FLAG != 0
+    }
+}
+
+// Hexen format and ZDoom UDMF levels use Hexen's line specials
+fn get_line_exit(special: u16, hexen_specials: bool) -> Option<LineExit> {
+    let (exits, secret_exits) = if hexen_specials {
+        (&HEXEN_EXIT_SPECIALS[..], &HEXEN_SECRET_EXIT_SPECIALS[..])
+    } else {
+        (&DOOM_EXIT_SPECIALS[..], &DOOM_SECRET_EXIT_SPECIALS[..])
+    };
+    if exits.contains(&special) {
+        Some(LineExit::Normal)
+    } else if secret_exits.contains(&special) {
+        Some(LineExit::Secret)
+    } else {
+        None
+    }
+}
+
+fn parse_binary(
+    level_format: LevelFormat,
+    things: &[u8],
+    linedefs: &[u8],
+    sidedefs: &[u8],
+    vertexes: &[u8],
+    sectors: &[u8],
+) -> LevelData {
+    let is_hexen = level_format == LevelFormat::Hexen;
+
+    let side_sectors: Vec<usize> = sidedefs
+        .chunks_exact(SIDEDEF_SIZE)
+        .map(|sidedef| read_u16(sidedef, 28) as usize)
+        .collect();
+    let get_sector = |sidedef: u16| {
+        (sidedef != NO_SIDEDEF)
+            .then(|| side_sectors.get(sidedef as usize).copied())
+            .flatten()
+    };
+
+    let linedef_size = if is_hexen {
+        HEXEN_LINEDEF_SIZE
+    } else {
+        DOOM_LINEDEF_SIZE
+    };
+    let linedefs = linedefs
+        .chunks_exact(linedef_size)
+        .map(|linedef| {
+            // Hexen format has a one byte special and five arguments where Doom has a tag
+            let (special, sides_offset) = if is_hexen {
+                (linedef[6] as u16, 12)
+            } else {
+                (read_u16(linedef, 6), 10)
+            };
+            Linedef {
+                v1: read_u16(linedef, 0) as usize,
+                v2: read_u16(linedef, 2) as usize,
+                front_sector: get_sector(read_u16(linedef, sides_offset)),
+                back_sector: get_sector(read_u16(linedef, sides_offset + 2)),
+                exit: get_line_exit(special, is_hexen),
+            }
+        })
+        .collect();
+
+    let thing_size = if is_hexen {
+        HEXEN_THING_SIZE
+    } else {
+        DOOM_THING_SIZE
+    };
+    let things = things
+        .chunks_exact(thing_size)
+        .map(|thing| {
+            // Hexen format starts with a thing id, and has a height after x and y
+            let (position_offset, type_offset) = if is_hexen { (2, 10) } else { (0, 6) };
+            let flags = read_u16(thing, type_offset + 2);
+            // Doom marks multiplayer only things, Hexen marks the ones in single player
+            let single_player = if is_hexen {
+                flags & THING_HEXEN_SINGLE != 0
+            } else {
+                flags & THING_MULTIPLAYER == 0
+            };
+            Thing {
+                x: read_i16(thing, position_offset) as f64,
+                y: read_i16(thing, position_offset + 2) as f64,
+                thing_type: read_u16(thing, type_offset),
+                skills: [
+                    flags & THING_EASY != 0,
+                    flags & THING_MEDIUM != 0,
+                    flags & THING_HARD != 0,
+                ],
+                single_player,
+            }
+        })
+        .collect();
+
+    // Sidedefs have upper, lower and middle textures, sectors a floor and ceiling flat
+    let mut textures = BTreeSet::new();
+    for sidedef in sidedefs.chunks_exact(SIDEDEF_SIZE) {
+        for offset in [4, 12, 20] {
+            add_texture(&mut textures, read_texture_name(sidedef, offset));
+        }
+    }
+    for sector in sectors.chunks_exact(SECTOR_SIZE) {
+        for offset in [4, 12] {
+            add_texture(&mut textures, read_texture_name(sector, offset));
+        }
+    }
+
+    LevelData {
+        vertexes: vertexes
+            .chunks_exact(VERTEX_SIZE)
+            .map(|vertex| Vertex {
+                x: read_i16(vertex, 0) as f64,
+                y: read_i16(vertex, 2) as f64,
+            })
+            .collect(),
+        linedefs,
+        sectors: sectors
+            .chunks_exact(SECTOR_SIZE)
+            .map(|sector| Sector {
+                secret: is_secret_sector(read_u16(sector, 22), is_hexen),
+            })
+            .collect(),
+        things,
+        textures,
+    }
+}
+
+// TEXTMAP is a list of blocks such as 'vertex { x = 0.0; y = 64.0; }', in the order their
+// index is counted from
+fn parse_udmf(text: &str) -> LevelData {
+    let comment_re = Regex::new(r"(?s)//[^\n]*|/\*.*?\*/").unwrap();
+    let namespace_re = Regex::new(r#"(?i)namespace\s*=\s*"([^"]*)"\s*;"#).unwrap();
+    let block_re = Regex::new(r"(?s)(\w+)\s*\{(.*?)\}").unwrap();
+    let field_re = Regex::new(r#"(\w+)\s*=\s*("[^"]*"|[^;]*);"#).unwrap();
+
+    let text = comment_re.replace_all(text, "");
+    // Only the Doom and Heretic namespaces keep Doom's line and sector specials
+    let is_zdoom = !namespace_re.captures(&text).is_some_and(|captures| {
+        let namespace = captures[1].to_lowercase();
+        namespace == "doom" || namespace == "heretic"
+    });
+
+    let mut vertexes: Vec<Vertex> = Vec::new();
+    let mut linedefs: Vec<(Linedef, Option<usize>, Option<usize>)> = Vec::new();
+    let mut side_sectors: Vec<usize> = Vec::new();
+    let mut sectors: Vec<Sector> = Vec::new();
+    let mut things: Vec<Thing> = Vec::new();
+    let mut textures: BTreeSet<String> = BTreeSet::new();
+
+    for block in block_re.captures_iter(&text) {
+        let fields: HashMap<String, String> = field_re
+            .captures_iter(&block[2])
+            .map(|field| (field[1].to_lowercase(), field[2].trim().to_string()))
+            .collect();
+        let number = |key: &str| {
+            fields
+                .get(key)
+                .and_then(|value| value.parse::<f64>().ok())
+                .unwrap_or(0.0)
+        };
+        let index = |key: &str| {
+            fields
+                .get(key)
+                .and_then(|value| value.parse::<i64>().ok())
+                .and_then(|value| usize::try_from(value).ok())
+        };
+
+        let flag = |key: &str| fields.get(key).is_some_and(|value| value == "true");
+        let block_textures: Vec<String> = TEXTURE_FIELDS
+            .iter()
+            .filter_map(|key| fields.get(*key))
+            .map(|value| value.trim_matches('"').to_uppercase())
+            .collect();
+        for texture in block_textures {
+            add_texture(&mut textures, texture);
+        }
+
+        match block[1].to_lowercase().as_str() {
+            "vertex" => vertexes.push(Vertex {
+                x: number("x"),
+                y: number("y"),
+            }),
+            "linedef" => linedefs.push((
+                Linedef {
+                    v1: index("v1").unwrap_or_default(),
+                    v2: index("v2").unwrap_or_default(),
+                    front_sector: None,
+                    back_sector: None,
+                    exit: get_line_exit(number("special") as u16, is_zdoom),
+                },
+                index("sidefront"),
+                index("sideback"),
+            )),
+            "sidedef" => side_sectors.push(index("sector").unwrap_or_default()),
+            "sector" => sectors.push(Sector {
+                secret: flag("secret") || is_secret_sector(number("special") as u16, is_zdoom),
+            }),
+            "thing" => things.push(Thing {
+                x: number("x"),
+                y: number("y"),
+                thing_type: number("type") as u16,
+                skills: [flag("skill2"), flag("skill3"), flag("skill4")],
+                single_player: flag("single"),
+            }),
+            _ => {}
+        }
+    }
+
+    // Sidedefs may come after the linedefs that use them
+    let get_sector = |sidedef: Option<usize>| sidedef.and_then(|s| side_sectors.get(s).copied());
+    LevelData {
+        vertexes,
+        linedefs: linedefs
+            .into_iter()
+            .map(|(linedef, side_front, side_back)| Linedef {
+                front_sector: get_sector(side_front),
+                back_sector: get_sector(side_back),
+                ..linedef
+            })
+            .collect(),
+        sectors,
+        things,
+        textures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::level_data::{parse_udmf, read_level, LineExit, Sector, Thing};
+
+    #[test]
+    fn test_read_level() {
+        // Act
+        let actual = read_level("./test-data/square.wad", "E1M1").unwrap();
+
+        // Assert
+        assert_eq!(actual.vertexes.len(), 8);
+        assert_eq!(actual.linedefs.len(), 9);
+        assert_eq!(
+            actual.sectors,
+            vec![Sector { secret: false }, Sector { secret: true }]
+        );
+        assert_eq!(actual.linedefs[2].front_sector, Some(0));
+        assert_eq!(actual.linedefs[2].back_sector, Some(1));
+        assert_eq!(actual.linedefs[5].exit, Some(LineExit::Normal));
+        assert_eq!(
+            actual.things[1],
+            Thing {
+                x: 192.0,
+                y: 192.0,
+                thing_type: 3001,
+                skills: [true, true, true],
+                single_player: true,
+            }
+        );
+        assert_eq!(actual.things[2].skills, [false, false, true]);
+        assert_eq!(
+            actual.textures.into_iter().collect::<Vec<String>>(),
+            vec!["CEIL3_5", "FLOOR4_8", "STARTAN3"]
+        );
+        assert!(read_level("./test-data/square.wad", "MAP01").is_err());
+    }
+
+    #[test]
+    fn test_parse_udmf() {
+        // Arrange
+        let text = r#"namespace = "zdoom";
+            // A comment { with braces }
+            vertex { x = 0.0; y = 0.0; }
+            vertex { x = 64.0; y = -32.5; }
+            linedef { v1 = 0; v2 = 1; sidefront = 0; special = 244; }
+            sidedef { sector = 0; texturemiddle = "STONE"; }
+            sector { texturefloor = "FLAT1"; secret = true; }
+            thing { x = 32.0; y = 16.0; type = 3004; skill1 = true; skill4 = true; single = true; }
+            thing { x = 48.0; y = 16.0; type = 3001; skill3 = true; coop = true; }"#;
+
+        // Act
+        let actual = parse_udmf(text);
+
+        // Assert
+        assert_eq!(actual.vertexes.len(), 2);
+        assert_eq!(actual.vertexes[1].y, -32.5);
+        assert_eq!(actual.linedefs[0].front_sector, Some(0));
+        assert_eq!(actual.linedefs[0].back_sector, None);
+        assert_eq!(actual.linedefs[0].exit, Some(LineExit::Secret));
+        assert_eq!(actual.sectors, vec![Sector { secret: true }]);
+        assert_eq!(
+            actual.textures.into_iter().collect::<Vec<String>>(),
+            vec!["FLAT1", "STONE"]
+        );
+        assert_eq!(actual.things[0].thing_type, 3004);
+        assert_eq!(actual.things[0].skills, [false, false, true]);
+        assert!(actual.things[0].single_player);
+        assert_eq!(actual.things[1].skills, [false, true, false]);
+        assert!(!actual.things[1].single_player);
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use crate::{
+    data::{LevelStats, MapStatsSummary, SkillTier},
+    doom_data::{ThingCategory, ThingType},
+    level_data::LevelData,
+};
+
+// Counts what a single player meets in a level, once for each Skill Tier. Things only placed
+// for multiplayer are left out, as are thing numbers the game doesn't know
+
+const SKILL_TIERS: [SkillTier; 3] = [SkillTier::Easy, SkillTier::Medium, SkillTier::Hard];
+
+pub fn get_level_stats(
+    lump_name: &str,
+    level_data: &LevelData,
+    thing_types: &[ThingType],
+) -> Vec<LevelStats> {
+    let thing_types: HashMap<u16, &ThingType> = thing_types
+        .iter()
+        .map(|thing_type| (thing_type.doomednum, thing_type))
+        .collect();
+    let secrets = level_data
+        .sectors
+        .iter()
+        .filter(|sector| sector.secret)
+        .count() as i32;
+
+    SKILL_TIERS
+        .iter()
+        .enumerate()
+        .map(|(tier, skill_tier)| {
+            let mut level_stats = LevelStats {
+                map_id: 0,
+                lump_name: lump_name.to_string(),
+                skill_tier: skill_tier.clone(),
+                monsters: 0,
+                monster_hit_points: 0,
+                items: 0,
+                weapons: 0,
+                ammo: 0,
+                keys: 0,
+                secrets,
+            };
+
+            let things = level_data
+                .things
+                .iter()
+                .filter(|thing| thing.single_player && thing.skills[tier])
+                .filter_map(|thing| thing_types.get(&thing.thing_type));
+            for thing_type in things {
+                match thing_type.category {
+                    ThingCategory::Player => {}
+                    ThingCategory::Monster => {
+                        level_stats.monsters += 1;
+                        level_stats.monster_hit_points += thing_type.hit_points as i32;
+                    }
+                    ThingCategory::Weapon => level_stats.weapons += 1,
+                    ThingCategory::Ammo => level_stats.ammo += 1,
+                    ThingCategory::Item => level_stats.items += 1,
+                    ThingCategory::Key(_) => level_stats.keys += 1,
+                }
+            }
+            level_stats
+        })
+        .collect()
+}
+
+pub fn summarise_map_stats(level_stats_list: &[LevelStats]) -> Vec<MapStatsSummary> {
+    let mut summaries: Vec<MapStatsSummary> = Vec::new();
+    for level_stats in level_stats_list
+        .iter()
+        .filter(|level_stats| level_stats.skill_tier == SkillTier::Hard)
+    {
+        let index = match summaries
+            .iter()
+            .position(|summary| summary.map_id == level_stats.map_id)
+        {
+            Some(index) => index,
+            None => {
+                summaries.push(MapStatsSummary {
+                    map_id: level_stats.map_id,
+                    levels: 0,
+                    monsters: 0,
+                    monster_hit_points: 0,
+                    secrets: 0,
+                });
+                summaries.len() - 1
+            }
+        };
+        let summary = &mut summaries[index];
+        summary.levels += 1;
+        summary.monsters += level_stats.monsters;
+        summary.monster_hit_points += level_stats.monster_hit_points;
+        summary.secrets += level_stats.secrets;
+    }
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data::SkillTier,
+        doom_data::{get_thing_type_list, InternalWadType},
+        level_data::read_level,
+        level_stats::{get_level_stats, summarise_map_stats},
+    };
+
+    #[test]
+    fn test_get_level_stats() {
+        // Arrange
+        let level_data = read_level("./test-data/square.wad", "E1M1").unwrap();
+        let thing_types = get_thing_type_list(&InternalWadType::Doom);
+
+        // Act
+        let actual = get_level_stats("E1M1", &level_data, &thing_types);
+
+        // Assert
+        assert_eq!(actual.len(), 3);
+        assert_eq!(actual[0].skill_tier, SkillTier::Easy);
+        assert_eq!(actual[0].monsters, 1);
+        assert_eq!(actual[0].monster_hit_points, 60);
+        assert_eq!(actual[1].monsters, 1);
+        assert_eq!(actual[2].skill_tier, SkillTier::Hard);
+        assert_eq!(actual[2].monsters, 2);
+        assert_eq!(actual[2].monster_hit_points, 90);
+        for level_stats in &actual {
+            assert_eq!(level_stats.lump_name, "E1M1");
+            assert_eq!(level_stats.weapons, 1);
+            assert_eq!(level_stats.keys, 1);
+            assert_eq!(level_stats.ammo, 0);
+            assert_eq!(level_stats.items, 0);
+            assert_eq!(level_stats.secrets, 1);
+        }
+    }
+
+    #[test]
+    fn test_summarise_map_stats() {
+        // Arrange
+        let level_data = read_level("./test-data/square.wad", "E1M1").unwrap();
+        let thing_types = get_thing_type_list(&InternalWadType::Doom);
+        let mut level_stats_list = get_level_stats("E1M1", &level_data, &thing_types);
+        level_stats_list.extend(get_level_stats("E1M2", &level_data, &thing_types));
+        for level_stats in &mut level_stats_list {
+            level_stats.map_id = 1;
+        }
+        let mut other_map = get_level_stats("MAP01", &level_data, &thing_types);
+        for level_stats in &mut other_map {
+            level_stats.map_id = 2;
+        }
+        level_stats_list.extend(other_map);
+
+        // Act
+        let actual = summarise_map_stats(&level_stats_list);
+
+        // Assert
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].map_id, 1);
+        assert_eq!(actual[0].levels, 2);
+        assert_eq!(actual[0].monsters, 4);
+        assert_eq!(actual[0].monster_hit_points, 180);
+        assert_eq!(actual[0].secrets, 2);
+        assert_eq!(actual[1].map_id, 2);
+        assert_eq!(actual[1].levels, 1);
+        assert_eq!(actual[1].monsters, 2);
+    }
+}
@@ -0,0 +1,255 @@
+use regex::Regex;
+
+use crate::{
+    data, db,
+    doom_data::{GameFamily, KeyLump},
+    mapinfo, paths, wad,
+};
+
+// The levels a Profile can warp to, taken from its Maps and IWAD, and the -warp value
+// that reaches each one. Engines only warp to ExMy and MAPxx slots, except Hexen which
+// warps by the MAPINFO warptrans number.
+
+pub fn get_profile_levels(profile_id: i32) -> Result<Vec<data::WarpLevel>, eyre::Report> {
+    let profile = db::get_profile_by_id(profile_id)?;
+
+    let mut map_levels: Vec<Vec<data::MapLevel>> = Vec::new();
+    let mut map_paths: Vec<String> = Vec::new();
+    for map_id in [
+        profile.map_id,
+        profile.map_id2,
+        profile.map_id3,
+        profile.map_id4,
+        profile.map_id5,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        map_levels.push(db::get_map_contents(map_id)?.levels);
+        map_paths.push(db::get_map_by_id(map_id)?.path);
+    }
+
+    // IWADs aren't scanned like Maps, so read the directory now
+    let mut iwad_levels = Vec::new();
+    let mut is_hexen = false;
+    let mut wad_paths = Vec::new();
+    if let Some(iwad_id) = profile.iwad_id {
+        let iwad = db::get_iwad_by_id(iwad_id)?;
+        is_hexen = iwad.get_game_family() == GameFamily::Hexen;
+        if paths::file_exists(&iwad.path) {
+            match wad::read_wad_directory(&iwad.path) {
+                Ok(wad_directory) => iwad_levels = wad_directory.get_levels(),
+                Err(e) => log::debug!("Unable to read levels from IWAD: {}", e),
+            }
+        }
+        wad_paths.push(iwad.path);
+    }
+
+    let levels = merge_levels(map_levels, iwad_levels);
+    if !is_hexen {
+        return Ok(levels);
+    }
+
+    wad_paths.extend(map_paths);
+    Ok(apply_warp_translations(
+        levels,
+        &get_warp_translations(&wad_paths),
+    ))
+}
+
+// Later WADs replace the MAPINFO of earlier ones, as they do in the Engine
+fn get_warp_translations(wad_paths: &[String]) -> Vec<(String, u32)> {
+    let mut result = Vec::new();
+    for wad_path in wad_paths {
+        let mapinfo_text = wad::read_wad_directory(wad_path).and_then(|wad_directory| {
+            match wad_directory.find_lump(&KeyLump::Mapinfo.to_string()) {
+                Some(lump) => Ok(Some(
+                    String::from_utf8_lossy(&wad::read_lump(wad_path, lump)?).to_string(),
+                )),
+                None => Ok(None),
+            }
+        });
+        match mapinfo_text {
+            Ok(Some(text)) => result = mapinfo::get_warp_translations(&text),
+            Ok(None) => {}
+            Err(e) => log::debug!("Unable to read MAPINFO from '{}': {}", wad_path, e),
+        }
+    }
+
+    result
+}
+
+// Hexen levels without a warptrans can't be warped to
+fn apply_warp_translations(
+    levels: Vec<data::WarpLevel>,
+    warp_translations: &[(String, u32)],
+) -> Vec<data::WarpLevel> {
+    levels
+        .into_iter()
+        .filter_map(|mut level| {
+            let (_, warptrans) = warp_translations
+                .iter()
+                .find(|(lump_name, _)| *lump_name == level.lump_name)?;
+            level.warp = warptrans.to_string();
+            Some(level)
+        })
+        .collect()
+}
+
+// Maps replace IWAD levels in the same slot, and a later Map's level name wins
+fn merge_levels(
+    map_levels: Vec<Vec<data::MapLevel>>,
+    iwad_levels: Vec<data::MapLevel>,
+) -> Vec<data::WarpLevel> {
+    let mut result: Vec<data::WarpLevel> = Vec::new();
+    for level in map_levels.into_iter().flatten().chain(iwad_levels) {
+        let Some(warp) = get_warp(&level.lump_name) else {
+            continue;
+        };
+        match result
+            .iter_mut()
+            .find(|warp_level| warp_level.lump_name == level.lump_name)
+        {
+            Some(warp_level) => {
+                if level.level_name.is_some() {
+                    warp_level.level_name = level.level_name;
+                }
+            }
+            None => result.push(data::WarpLevel {
+                lump_name: level.lump_name,
+                level_name: level.level_name,
+                warp,
+            }),
+        }
+    }
+
+    result
+}
+
+fn get_warp(lump_name: &str) -> Option<String> {
+    let episode_re = Regex::new(r"(?i)^E(\d)M(\d)$").unwrap();
+    let map_re = Regex::new(r"(?i)^MAP(\d{1,2})$").unwrap();
+
+    if let Some(captures) = episode_re.captures(lump_name) {
+        return Some(format!("{} {}", &captures[1], &captures[2]));
+    }
+    map_re
+        .captures(lump_name)
+        .and_then(|captures| captures[1].parse::<u32>().ok())
+        .map(|number| number.to_string())
+}
+
+// Accepts the slot (MAP07, E2M3), the -warp value (7, 2 3) or the level name (Dead Simple)
+pub fn find_warp_level<'a>(
+    levels: &'a [data::WarpLevel],
+    input: &str,
+) -> Option<&'a data::WarpLevel> {
+    let input = input.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let input_warp = get_warp(&input);
+
+    levels.iter().find(|level| {
+        level.lump_name.eq_ignore_ascii_case(&input)
+            || level.warp == input
+            || input_warp.as_ref() == Some(&level.warp)
+            || level
+                .level_name
+                .as_ref()
+                .is_some_and(|level_name| level_name.eq_ignore_ascii_case(&input))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data,
+        doom_data::LevelFormat,
+        levels::{apply_warp_translations, find_warp_level, get_warp, merge_levels},
+    };
+
+    fn map_level(lump_name: &str, level_name: Option<&str>) -> data::MapLevel {
+        data::MapLevel {
+            lump_name: lump_name.to_string(),
+            level_format: LevelFormat::Doom,
+            level_name: level_name.map(|level_name| level_name.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_get_warp() {
+        assert_eq!(get_warp("E2M3"), Some("2 3".to_string()));
+        assert_eq!(get_warp("MAP07"), Some("7".to_string()));
+        assert_eq!(get_warp("map32"), Some("32".to_string()));
+        assert_eq!(get_warp("TITLEMAP"), None);
+    }
+
+    #[test]
+    fn test_merge_levels_maps_replace_iwad() {
+        // Arrange
+        let map_levels = vec![vec![
+            map_level("MAP07", Some("Dead Simple")),
+            map_level("TITLEMAP", None),
+        ]];
+        let iwad_levels = vec![map_level("MAP01", None), map_level("MAP07", None)];
+
+        // Act
+        let actual = merge_levels(map_levels, iwad_levels);
+
+        // Assert
+        let display: Vec<String> = actual.iter().map(|level| level.to_string()).collect();
+        assert_eq!(display, vec!["MAP07 – Dead Simple", "MAP01"]);
+    }
+
+    #[test]
+    fn test_find_warp_level() {
+        // Arrange
+        let levels = merge_levels(
+            vec![vec![
+                map_level("E2M3", Some("Pandemonium")),
+                map_level("MAP07", Some("Dead Simple")),
+            ]],
+            vec![],
+        );
+
+        // Act
+        let actual: Vec<Option<&str>> = ["2  3", "map7", "7", "dead simple", "MAP08"]
+            .iter()
+            .map(|input| find_warp_level(&levels, input).map(|level| level.lump_name.as_str()))
+            .collect();
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                Some("E2M3"),
+                Some("MAP07"),
+                Some("MAP07"),
+                Some("MAP07"),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_warp_translations_hexen() {
+        // Arrange
+        let levels = merge_levels(
+            vec![],
+            vec![
+                map_level("MAP01", Some("Winnowing Hall")),
+                map_level("MAP13", Some("Shadow Wood")),
+                map_level("MAP41", None),
+            ],
+        );
+        let warp_translations = vec![("MAP01".to_string(), 1), ("MAP13".to_string(), 2)];
+
+        // Act
+        let actual = apply_warp_translations(levels, &warp_translations);
+
+        // Assert
+        let warps: Vec<(&str, &str)> = actual
+            .iter()
+            .map(|level| (level.lump_name.as_str(), level.warp.as_str()))
+            .collect();
+        assert_eq!(warps, vec![("MAP01", "1"), ("MAP13", "2")]);
+    }
+}
@@ -4,18 +4,32 @@ use clap::Parser;
 use color_eyre::eyre;
 use owo_colors::{colors::xterm, OwoColorize};
 
+mod archive;
 mod cli;
+mod complevel;
 mod constants;
 mod data;
 mod db;
+mod demo;
 mod doom_data;
 mod doomworld_api;
 mod downloader;
+mod engine_dialect;
 mod files;
 mod finder;
+mod hooks;
+mod iwad_discovery;
+mod iwad_identity;
+mod launch_log;
+mod level_data;
+mod level_stats;
+mod levels;
 mod log_config;
+mod map_preview;
+mod mapinfo;
 mod menu_app_settings;
 mod menu_common;
+mod menu_demos;
 mod menu_editor;
 mod menu_main;
 mod menu_maps;
@@ -23,8 +37,14 @@ mod menu_play_settings;
 mod menu_profiles;
 mod menu_queues;
 mod paths;
+mod pe_version;
+mod preflight;
+mod required_iwad;
 mod runner;
+mod terminal_graphics;
+mod title_screen;
 mod tui;
+mod wad;
 
 fn run() -> eyre::Result<String> {
     color_eyre::install()?;
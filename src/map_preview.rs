@@ -0,0 +1,440 @@
+use std::{fs, io::BufWriter};
+
+use eyre::Context;
+
+use crate::{
+    data,
+    doom_data::{self, KeyColour, ThingCategory},
+    files,
+    level_data::{self, LevelData, LineExit},
+    paths,
+};
+
+// Draws a top-down overview of a level, like the automap with everything revealed. Secret
+// sectors and exits stand out, and monsters, keys and the player start are marked. Previews
+// are cached by the Map's SHA-1, so a changed file gets a new preview.
+
+const MAP_PREVIEW_FOLDER: &str = "map-previews";
+const PREVIEW_SIZE: f64 = 1024.0;
+const PREVIEW_MARGIN: f64 = 16.0;
+
+type Colour = [u8; 3];
+
+const COLOUR_BACKGROUND: Colour = [0, 0, 0];
+const COLOUR_WALL: Colour = [200, 200, 200];
+const COLOUR_TWO_SIDED: Colour = [96, 96, 96];
+const COLOUR_SECRET: Colour = [255, 0, 255];
+const COLOUR_EXIT: Colour = [0, 220, 0];
+const COLOUR_SECRET_EXIT: Colour = [0, 200, 255];
+const COLOUR_MONSTER: Colour = [220, 40, 40];
+const COLOUR_PLAYER: Colour = [255, 255, 255];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+        colour: Colour,
+        width: u32,
+    },
+    // The title shows when hovering over an SVG marker
+    Circle {
+        centre: (f64, f64),
+        radius: f64,
+        colour: Colour,
+        title: String,
+    },
+    Square {
+        centre: (f64, f64),
+        size: f64,
+        colour: Colour,
+        title: String,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Preview {
+    width: u32,
+    height: u32,
+    shapes: Vec<Shape>,
+}
+
+pub fn get_map_preview_root() -> String {
+    paths::get_full_path(&paths::get_app_data_dir(), MAP_PREVIEW_FOLDER)
+}
+
+// Returns the path of the preview, drawing it when it isn't already cached
+pub fn get_map_preview(
+    map: &data::Map,
+    lump_name: &str,
+    format: &data::PreviewFormat,
+) -> Result<String, eyre::Report> {
+    let sha1 = match &map.sha1 {
+        Some(sha1) => sha1.clone(),
+        None => files::get_file_hashes(&map.path)
+            .sha1
+            .ok_or_else(|| eyre::eyre!("Unable to read Map '{}'", map.path))?,
+    };
+    let lump_name = lump_name.to_uppercase();
+    let preview_root = get_map_preview_root();
+    let preview_path = paths::get_full_path(
+        &preview_root,
+        &format!("{}-{}.{}", sha1, lump_name, format.get_extension()),
+    );
+    if paths::file_exists(&preview_path) {
+        log::debug!("Using cached Map preview '{}'", preview_path);
+        return Ok(preview_path);
+    }
+
+    let level_data = level_data::read_level(&map.path, &lump_name)?;
+    let internal_wad_type = files::get_required_internal_wad_type(map.iwad());
+    let preview = build_preview(
+        &level_data,
+        &doom_data::get_thing_type_list(&internal_wad_type),
+    )?;
+
+    paths::create_folder(&preview_root)
+        .wrap_err(format!("Failed to create folder '{}'", preview_root))?;
+    match format {
+        data::PreviewFormat::Svg => {
+            fs::write(&preview_path, render_svg(&preview)).map_err(eyre::Report::from)
+        }
+        data::PreviewFormat::Png => render_png(&preview, &preview_path),
+    }
+    .wrap_err(format!("Failed to write Map preview '{}'", preview_path))?;
+
+    Ok(preview_path)
+}
+
+fn get_key_colour(key_colour: &KeyColour) -> Colour {
+    match key_colour {
+        KeyColour::Blue => [64, 96, 255],
+        KeyColour::Yellow => [255, 220, 0],
+        KeyColour::Red => [255, 64, 64],
+        KeyColour::Green => [0, 200, 0],
+        KeyColour::Other => [255, 160, 0],
+    }
+}
+
+fn build_preview(
+    level_data: &LevelData,
+    thing_types: &[doom_data::ThingType],
+) -> Result<Preview, eyre::Report> {
+    let Some(first) = level_data.vertexes.first() else {
+        return Err(eyre::eyre!("Level has no vertexes to draw"));
+    };
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (first.x, first.x, first.y, first.y);
+    for vertex in &level_data.vertexes {
+        min_x = min_x.min(vertex.x);
+        max_x = max_x.max(vertex.x);
+        min_y = min_y.min(vertex.y);
+        max_y = max_y.max(vertex.y);
+    }
+    let scale = (PREVIEW_SIZE - PREVIEW_MARGIN * 2.0) / (max_x - min_x).max(max_y - min_y).max(1.0);
+    let width = ((max_x - min_x) * scale + PREVIEW_MARGIN * 2.0).ceil() as u32;
+    let height = ((max_y - min_y) * scale + PREVIEW_MARGIN * 2.0).ceil() as u32;
+    // Doom's y axis points up, an image's points down
+    let to_image = |x: f64, y: f64| {
+        (
+            (x - min_x) * scale + PREVIEW_MARGIN,
+            (max_y - y) * scale + PREVIEW_MARGIN,
+        )
+    };
+
+    let is_secret = |sector: Option<usize>| {
+        sector
+            .and_then(|sector| level_data.sectors.get(sector))
+            .is_some_and(|sector| sector.secret)
+    };
+    // Lines are drawn least important first, so exits and secrets end up on top
+    let mut lines: Vec<(u8, Shape)> = Vec::new();
+    for linedef in &level_data.linedefs {
+        let (Some(v1), Some(v2)) = (
+            level_data.vertexes.get(linedef.v1),
+            level_data.vertexes.get(linedef.v2),
+        ) else {
+            continue;
+        };
+        let (priority, colour, width) = match linedef.exit {
+            Some(LineExit::Normal) => (4, COLOUR_EXIT, 3),
+            Some(LineExit::Secret) => (4, COLOUR_SECRET_EXIT, 3),
+            None if is_secret(linedef.front_sector) || is_secret(linedef.back_sector) => {
+                (3, COLOUR_SECRET, 2)
+            }
+            None if linedef.back_sector.is_none() => (2, COLOUR_WALL, 1),
+            None => (1, COLOUR_TWO_SIDED, 1),
+        };
+        lines.push((
+            priority,
+            Shape::Line {
+                from: to_image(v1.x, v1.y),
+                to: to_image(v2.x, v2.y),
+                colour,
+                width,
+            },
+        ));
+    }
+    lines.sort_by_key(|(priority, _)| *priority);
+
+    let mut markers: Vec<(u8, Shape)> = Vec::new();
+    for thing in &level_data.things {
+        let Some(thing_type) = thing_types
+            .iter()
+            .find(|thing_type| thing_type.doomednum == thing.thing_type)
+        else {
+            continue;
+        };
+        let centre = to_image(thing.x, thing.y);
+        let title = thing_type.name.clone();
+        markers.push(match &thing_type.category {
+            ThingCategory::Weapon | ThingCategory::Ammo | ThingCategory::Item => continue,
+            ThingCategory::Monster => (
+                1,
+                Shape::Circle {
+                    centre,
+                    radius: 3.0,
+                    colour: COLOUR_MONSTER,
+                    title,
+                },
+            ),
+            ThingCategory::Key(key_colour) => (
+                2,
+                Shape::Square {
+                    centre,
+                    size: 8.0,
+                    colour: get_key_colour(key_colour),
+                    title,
+                },
+            ),
+            ThingCategory::Player => (
+                3,
+                Shape::Circle {
+                    centre,
+                    radius: 5.0,
+                    colour: COLOUR_PLAYER,
+                    title,
+                },
+            ),
+        });
+    }
+    markers.sort_by_key(|(priority, _)| *priority);
+
+    Ok(Preview {
+        width,
+        height,
+        shapes: lines
+            .into_iter()
+            .chain(markers)
+            .map(|(_, shape)| shape)
+            .collect(),
+    })
+}
+
+fn to_hex(colour: &Colour) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+fn render_svg(preview: &Preview) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        preview.width, preview.height
+    );
+    svg.push_str(&format!(
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        to_hex(&COLOUR_BACKGROUND)
+    ));
+    for shape in &preview.shapes {
+        svg.push_str(&match shape {
+            Shape::Line {
+                from,
+                to,
+                colour,
+                width,
+            } => format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\"/>\n",
+                from.0, from.1, to.0, to.1, to_hex(colour), width
+            ),
+            Shape::Circle {
+                centre,
+                radius,
+                colour,
+                title,
+            } => format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\"><title>{}</title></circle>\n",
+                centre.0, centre.1, radius, to_hex(colour), title
+            ),
+            Shape::Square {
+                centre,
+                size,
+                colour,
+                title,
+            } => format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{}</title></rect>\n",
+                centre.0 - size / 2.0,
+                centre.1 - size / 2.0,
+                size,
+                size,
+                to_hex(colour),
+                title
+            ),
+        });
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// A plain RGB canvas, enough to draw lines and markers without an image library
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, colour: Colour) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: colour.repeat((width * height) as usize),
+        }
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, size: f64, colour: Colour) {
+        let (left, top) = (
+            (x - size / 2.0).round() as i64,
+            (y - size / 2.0).round() as i64,
+        );
+        let size = size.round().max(1.0) as i64;
+        for py in top..top + size {
+            for px in left..left + size {
+                self.set_pixel(px, py, colour);
+            }
+        }
+    }
+
+    fn fill_circle(&mut self, x: f64, y: f64, radius: f64, colour: Colour) {
+        let r = radius.ceil() as i64;
+        let (cx, cy) = (x.round() as i64, y.round() as i64);
+        for py in cy - r..=cy + r {
+            for px in cx - r..=cx + r {
+                let (dx, dy) = ((px - cx) as f64, (py - cy) as f64);
+                if dx * dx + dy * dy <= radius * radius {
+                    self.set_pixel(px, py, colour);
+                }
+            }
+        }
+    }
+
+    // Bresenham's line, with a square brush for wider lines
+    fn draw_line(&mut self, from: (f64, f64), to: (f64, f64), width: u32, colour: Colour) {
+        let (mut x0, mut y0) = (from.0.round() as i64, from.1.round() as i64);
+        let (x1, y1) = (to.0.round() as i64, to.1.round() as i64);
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+        let mut error = dx + dy;
+        loop {
+            self.fill_rect(x0 as f64, y0 as f64, width as f64, colour);
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let error2 = error * 2;
+            if error2 >= dy {
+                error += dy;
+                x0 += sx;
+            }
+            if error2 <= dx {
+                error += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    fn set_pixel(&mut self, x: i64, y: i64, colour: Colour) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let offset = (y as usize * self.width as usize + x as usize) * 3;
+        self.pixels[offset..offset + 3].copy_from_slice(&colour);
+    }
+}
+
+fn render_png(preview: &Preview, png_path: &str) -> Result<(), eyre::Report> {
+    let mut canvas = Canvas::new(preview.width, preview.height, COLOUR_BACKGROUND);
+    for shape in &preview.shapes {
+        match shape {
+            Shape::Line {
+                from,
+                to,
+                colour,
+                width,
+            } => canvas.draw_line(*from, *to, *width, *colour),
+            Shape::Circle {
+                centre,
+                radius,
+                colour,
+                ..
+            } => canvas.fill_circle(centre.0, centre.1, *radius, *colour),
+            Shape::Square {
+                centre,
+                size,
+                colour,
+                ..
+            } => canvas.fill_rect(centre.0, centre.1, *size, *colour),
+        }
+    }
+    write_png(png_path, canvas.width, canvas.height, &canvas.pixels)
+}
+
+pub fn write_png(png_path: &str, width: u32, height: u32, rgb: &[u8]) -> Result<(), eyre::Report> {
+    let file = paths::create_file(png_path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgb)?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        doom_data::{self, InternalWadType},
+        level_data::read_level,
+        map_preview::{build_preview, render_svg, Shape, COLOUR_EXIT, COLOUR_SECRET},
+    };
+
+    #[test]
+    fn test_build_preview() {
+        // Arrange
+        let level_data = read_level("./test-data/square.wad", "E1M1").unwrap();
+        let thing_types = doom_data::get_thing_type_list(&InternalWadType::Doom2);
+
+        // Act
+        let actual = build_preview(&level_data, &thing_types).unwrap();
+
+        // Assert
+        assert_eq!((actual.width, actual.height), (1024, 826));
+        let line_colours: Vec<[u8; 3]> = actual
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                Shape::Line { colour, .. } => Some(*colour),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(line_colours.len(), 9);
+        // The alcove's four lines are secret, and the exit is drawn last
+        assert_eq!(
+            line_colours
+                .iter()
+                .filter(|colour| **colour == COLOUR_SECRET)
+                .count(),
+            4
+        );
+        assert_eq!(line_colours.last(), Some(&COLOUR_EXIT));
+        // The Imp, Shotgun Guy, Blue Keycard and Player 1 Start, but not the Shotgun
+        assert_eq!(actual.shapes.len(), 13);
+        assert!(render_svg(&actual).starts_with("<svg"));
+    }
+}
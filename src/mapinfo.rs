@@ -0,0 +1,187 @@
+use regex::Regex;
+
+use crate::{data, doom_data::KeyLump};
+
+// Pulls level names out of UMAPINFO and the ZDoom MAPINFO family. Only the names are read,
+// the rest of the lump is for the Engine. The formats are described at
+// https://doomwiki.org/wiki/UMAPINFO and https://zdoom.org/wiki/MAPINFO
+
+// UMAPINFO is the most likely to be read by whatever Engine is used, so its names win
+pub const LEVEL_NAME_LUMPS: [KeyLump; 3] = [KeyLump::Umapinfo, KeyLump::Zmapinfo, KeyLump::Mapinfo];
+
+fn get_level_names(key_lump: &KeyLump, text: &str) -> Vec<(String, String)> {
+    match key_lump {
+        KeyLump::Umapinfo => get_umapinfo_level_names(text),
+        KeyLump::Mapinfo | KeyLump::Zmapinfo => get_mapinfo_level_names(text),
+        _ => Vec::new(),
+    }
+}
+
+// Call in the order of LEVEL_NAME_LUMPS, a level keeps the first name it is given
+pub fn set_level_names(levels: &mut [data::MapLevel], key_lump: &KeyLump, text: &str) {
+    for (lump_name, level_name) in get_level_names(key_lump, text) {
+        if let Some(level) = levels
+            .iter_mut()
+            .find(|level| level.lump_name == lump_name && level.level_name.is_none())
+        {
+            level.level_name = Some(level_name);
+        }
+    }
+}
+
+// Hexen warps by a level's "warptrans" number rather than its slot:
+// map 2 "Seven Portals" followed by warptrans 2, or warptrans = 2 in the newer syntax
+pub fn get_warp_translations(text: &str) -> Vec<(String, u32)> {
+    let map_re = Regex::new(r#"(?i)^\s*map\s+(\w+)"#).unwrap();
+    let warptrans_re = Regex::new(r#"(?i)^\s*warptrans\s*=?\s*(\d+)"#).unwrap();
+
+    let mut result = Vec::new();
+    let mut lump_name: Option<String> = None;
+    for line in text.lines() {
+        if let Some(captures) = map_re.captures(line) {
+            lump_name = Some(match captures[1].parse::<u32>() {
+                Ok(number) => format!("MAP{:02}", number),
+                Err(_) => captures[1].to_uppercase(),
+            });
+        } else if let (Some(lump_name), Some(captures)) = (&lump_name, warptrans_re.captures(line))
+        {
+            if let Ok(warptrans) = captures[1].parse::<u32>() {
+                result.push((lump_name.clone(), warptrans));
+            }
+        }
+    }
+
+    result
+}
+
+// GAMEINFO names the IWAD a ZDoom mod was made for: IWAD = "doom2.wad"
+pub fn get_gameinfo_iwad(text: &str) -> Option<String> {
+    let iwad_re = Regex::new(r#"(?im)^\s*iwad\s*=\s*"([^"]+)""#).unwrap();
+    iwad_re
+        .captures(text)
+        .map(|captures| captures[1].trim().to_string())
+}
+
+// MAP MAP07 { levelname = "Dead Simple" }
+fn get_umapinfo_level_names(text: &str) -> Vec<(String, String)> {
+    let map_re = Regex::new(r#"(?is)\bmap\s+(\w+)\s*\{([^}]*)\}"#).unwrap();
+    let name_re = Regex::new(r#"(?i)\blevelname\s*=\s*"([^"]*)""#).unwrap();
+
+    map_re
+        .captures_iter(text)
+        .filter_map(|map| {
+            let level_name = name_re.captures(&map[2])?[1].trim().to_string();
+            Some((map[1].to_uppercase(), level_name))
+        })
+        .filter(|(_, level_name)| !level_name.is_empty())
+        .collect()
+}
+
+// map MAP07 "Dead Simple", Hexen style MAPINFO numbers its levels instead: map 1 "Winnowing Hall".
+// Names given with "lookup" are keys into a LANGUAGE lump, so are skipped
+fn get_mapinfo_level_names(text: &str) -> Vec<(String, String)> {
+    let map_re = Regex::new(r#"(?im)^\s*map\s+(\w+)\s+"([^"]*)""#).unwrap();
+
+    map_re
+        .captures_iter(text)
+        .map(|map| {
+            let lump_name = match map[1].parse::<u32>() {
+                Ok(number) => format!("MAP{:02}", number),
+                Err(_) => map[1].to_uppercase(),
+            };
+            (lump_name, map[2].trim().to_string())
+        })
+        .filter(|(_, level_name)| !level_name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        doom_data::KeyLump,
+        mapinfo::{get_gameinfo_iwad, get_level_names, get_warp_translations},
+    };
+
+    #[test]
+    fn test_get_level_names_umapinfo() {
+        // Arrange
+        let text = r#"
+            map MAP01
+            {
+                levelname = "Entryway"
+                music = "D_RUNNIN"
+            }
+
+            MAP MAP07 { LevelName = "Dead Simple" next = "MAP08" }
+            map MAP08 { music = "D_ROMERO" }
+        "#;
+
+        // Act
+        let actual = get_level_names(&KeyLump::Umapinfo, text);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                ("MAP01".to_string(), "Entryway".to_string()),
+                ("MAP07".to_string(), "Dead Simple".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_level_names_mapinfo() {
+        // Arrange
+        let text = r#"
+            map E1M1 "Hangar"
+            map E1M2 lookup "HUSTR_E1M2"
+            map 1 "Winnowing Hall"
+            {
+                next = 2
+            }
+        "#;
+
+        // Act
+        let actual = get_level_names(&KeyLump::Zmapinfo, text);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                ("E1M1".to_string(), "Hangar".to_string()),
+                ("MAP01".to_string(), "Winnowing Hall".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_gameinfo_iwad() {
+        // Arrange
+        let text = "// Needs Doom II\nGameInfo\n{\n    iwad = \"doom2.wad\"\n    load = \"extras.pk3\"\n}\n";
+
+        // Act
+        let actual = get_gameinfo_iwad(text);
+
+        // Assert
+        assert_eq!(actual, Some("doom2.wad".to_string()));
+    }
+
+    #[test]
+    fn test_get_warp_translations() {
+        // Arrange
+        let text = "map 1 \"Winnowing Hall\"\nwarptrans 1\nnext 2\n\nmap 13 \"Shadow Wood\"\nwarptrans 2\n\nmap 40 \"Bright Crucible\"\n{\n    warptrans = 3\n}\nmap 41 \"Hidden\"\n";
+
+        // Act
+        let actual = get_warp_translations(text);
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                ("MAP01".to_string(), 1),
+                ("MAP13".to_string(), 2),
+                ("MAP40".to_string(), 3),
+            ]
+        );
+    }
+}
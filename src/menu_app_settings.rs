@@ -1,16 +1,23 @@
+use std::{collections::BTreeSet, path::Path};
+
 use color_eyre::eyre;
 use eyre::Context;
 use inquire::{validator::Validation, InquireError};
 use owo_colors::{colors::xterm, OwoColorize};
 use strum_macros::Display;
-use tabled::settings::{object::Rows, Modify, Rotate, Style, Width};
+use tabled::{
+    builder::Builder,
+    settings::{object::Rows, Modify, Rotate, Style, Width},
+    Tabled,
+};
 
 use crate::{
-    constants,
+    archive, complevel, constants,
     data::{self},
     db,
     doom_data::{self},
-    doomworld_api, files, menu_profiles, paths, tui,
+    doomworld_api, files, iwad_discovery, iwad_identity, level_data, level_stats, menu_profiles,
+    paths, required_iwad, tui, wad,
 };
 
 #[derive(Clone, Debug, PartialEq, Display)]
@@ -86,6 +93,81 @@ pub fn cli_update_use_doomworld_api(use_doomworld_api: bool) -> Result<String, e
     ))
 }
 
+pub fn update_wait_for_engine() -> Result<String, eyre::Report> {
+    let mut app_settings = db::get_app_settings()?;
+    app_settings.wait_for_engine = !app_settings.wait_for_engine;
+
+    db::save_app_settings(app_settings.clone())?;
+    Ok(format!(
+        "Wait for Engine to Exit set to: {}",
+        app_settings.wait_for_engine
+    ))
+}
+
+pub fn cli_update_wait_for_engine(wait_for_engine: bool) -> Result<String, eyre::Report> {
+    let mut app_settings = db::get_app_settings()?;
+
+    if app_settings.wait_for_engine == wait_for_engine {
+        return Ok(format!(
+            "Wait for Engine to Exit is already set to: {}",
+            app_settings.wait_for_engine
+        ));
+    }
+
+    app_settings.wait_for_engine = wait_for_engine;
+    db::save_app_settings(app_settings.clone())?;
+    Ok(format!(
+        "Wait for Engine to Exit set to: {}",
+        app_settings.wait_for_engine
+    ))
+}
+
+pub fn update_hooks() -> Result<String, eyre::Report> {
+    let mut app_settings = db::get_app_settings()?;
+
+    app_settings.pre_launch_hook = inquire::Text::new("Enter a pre-launch hook (optional):")
+        .with_help_message("Shell command run before any Engine starts")
+        .with_default(&app_settings.pre_launch_hook.unwrap_or_default())
+        .prompt_skippable()?
+        .filter(|hook| !hook.trim().is_empty());
+    app_settings.post_exit_hook = inquire::Text::new("Enter a post-exit hook (optional):")
+        .with_help_message("Shell command run after any Engine exits, dcli waits for the Engine")
+        .with_default(&app_settings.post_exit_hook.unwrap_or_default())
+        .prompt_skippable()?
+        .filter(|hook| !hook.trim().is_empty());
+    app_settings.post_download_hook = inquire::Text::new("Enter a post-download hook (optional):")
+        .with_help_message("Shell command run after Maps are downloaded")
+        .with_default(&app_settings.post_download_hook.unwrap_or_default())
+        .prompt_skippable()?
+        .filter(|hook| !hook.trim().is_empty());
+
+    db::save_app_settings(app_settings)?;
+    Ok("Successfully updated Hooks".to_string())
+}
+
+pub fn cli_update_hooks(
+    pre_launch_hook: Option<String>,
+    post_exit_hook: Option<String>,
+    post_download_hook: Option<String>,
+) -> Result<String, eyre::Report> {
+    let mut app_settings = db::get_app_settings()?;
+
+    // An empty value clears the hook
+    if let Some(pre_launch_hook) = pre_launch_hook {
+        app_settings.pre_launch_hook = Some(pre_launch_hook).filter(|hook| !hook.trim().is_empty());
+    }
+    if let Some(post_exit_hook) = post_exit_hook {
+        app_settings.post_exit_hook = Some(post_exit_hook).filter(|hook| !hook.trim().is_empty());
+    }
+    if let Some(post_download_hook) = post_download_hook {
+        app_settings.post_download_hook =
+            Some(post_download_hook).filter(|hook| !hook.trim().is_empty());
+    }
+
+    db::save_app_settings(app_settings)?;
+    Ok("Successfully updated Hooks".to_string())
+}
+
 pub fn init() -> Result<String, eyre::Report> {
     db::create_db()?;
     let mut app_settings = db::get_app_settings()?;
@@ -185,6 +267,7 @@ pub fn cli_init(
     Ok("Successfully run init and app configured!".to_string())
 }
 
+#[allow(clippy::to_string_in_format_args)]
 pub fn init_engines(default_folder: &str, force: bool) -> Result<String, eyre::Report> {
     let engine_search_folder: String = if force {
         default_folder.to_string()
@@ -212,6 +295,15 @@ pub fn init_engines(default_folder: &str, force: bool) -> Result<String, eyre::R
 
     let engines_executables =
         paths::find_file_in_folders(&engine_search_folder, doom_engine_files, "Engines");
+    // Linux Engines have no extension, so skip any folders that share their name. macOS
+    // Engines are app bundles, which are folders
+    let engines_executables: Vec<String> = engines_executables
+        .into_iter()
+        .filter(|engine_executable| {
+            doom_data::get_operating_system() == doom_data::OperatingSystem::MacOs
+                || Path::new(engine_executable).is_file()
+        })
+        .collect();
     if engines_executables.is_empty() {
         return Err(eyre::eyre!(format!(
             "No Engine matches found using recursive search in folder - '{}'",
@@ -254,6 +346,8 @@ pub fn init_engines(default_folder: &str, force: bool) -> Result<String, eyre::R
                     internal_path: game_engine.internal_path.clone(),
                     version: file_version.display_version(),
                     game_engine_type: game_engine.game_engine_type,
+                    sha1: None,
+                    md5: None,
                 });
                 log::info!(
                     "  {}",
@@ -321,7 +415,8 @@ pub fn init_engines(default_folder: &str, force: bool) -> Result<String, eyre::R
     let mut count = 0;
 
     // Save engines to  engines table
-    for selection in selections {
+    for mut selection in selections {
+        let file_hashes = files::get_file_hashes(&selection.path);
         let existing_engine = db_engines
             .iter()
             .find(|e| e.path.to_lowercase() == selection.path.to_lowercase());
@@ -339,8 +434,13 @@ pub fn init_engines(default_folder: &str, force: bool) -> Result<String, eyre::R
                     );
                     db::update_engine_version(existing.id, &selection.version)?;
                 }
+                if existing.sha1 != file_hashes.sha1 {
+                    db::update_engine_hashes(existing.id, &file_hashes)?;
+                }
             }
             None => {
+                selection.sha1 = file_hashes.sha1;
+                selection.md5 = file_hashes.md5;
                 db::add_engine(&selection)?;
                 log::debug!("Added Engine: {:?}", selection);
                 log::info!("Added Engine: {}", selection.simple_display().blue());
@@ -378,29 +478,60 @@ pub fn init_iwads(default_folder: &str, force: bool) -> Result<String, eyre::Rep
         paths::resolve_path(&path)
     };
 
-    // TODO: User filter for exists (what do you want to search for?)
-    let iwad_list = doom_data::get_internal_wad_list();
-    let iwad_files = iwad_list
-        .iter()
-        .map(|e| e.file_name.as_str())
-        .collect::<Vec<&str>>();
+    // Also look where Steam, GOG and the Doom environment variables put IWADs
+    let mut search_folders = vec![iwad_search_folder.clone()];
+    let discovered_folders: Vec<String> = iwad_discovery::find_iwad_folders()
+        .into_iter()
+        .filter(|folder| !Path::new(folder).starts_with(&iwad_search_folder))
+        .collect();
+    if !discovered_folders.is_empty()
+        && (force
+            || inquire::Confirm::new(&format!(
+                "Also search {} folders found from Steam, GOG and DOOMWADDIR?",
+                discovered_folders.len()
+            ))
+            .with_default(true)
+            .with_help_message(&discovered_folders.join(", "))
+            .prompt()?)
+    {
+        search_folders.extend(discovered_folders);
+    }
 
-    let iwads = paths::find_file_in_folders(&iwad_search_folder, iwad_files, "IWADs");
-    if iwads.is_empty() {
-        return Err(eyre::eyre!(format!(
-            "No IWAD matches found using recursive search in folder - '{}'",
-            &iwad_search_folder
-        )));
+    // Search every WAD rather than the known file names, renamed IWADs are found by content
+    let mut iwads: Vec<String> = Vec::new();
+    for search_folder in &search_folders {
+        for iwad in paths::find_files_with_extensions_in_folders(
+            search_folder,
+            vec![doom_data::EXT_WAD],
+            "IWADs",
+        ) {
+            if !iwads.contains(&iwad) {
+                iwads.push(iwad);
+            }
+        }
     }
 
-    // Double check it's an IWAD (it should be, it's coming from the hardcoded InternalWad list)
     let mut confirmed_iwads: Vec<String> = vec![];
+    let mut iwad_identities: Vec<(iwad_identity::IwadIdentity, files::FileHashes)> = vec![];
     for iwad in iwads {
-        if !(files::is_iwad(&iwad)?) {
-            log::info!("Skipping non-IWAD file: {}", iwad);
+        if !files::is_iwad(&iwad).unwrap_or(false) {
+            log::debug!("Skipping non-IWAD file: {}", iwad);
             continue;
         }
-        confirmed_iwads.push(iwad);
+        let file_hashes = files::get_file_hashes(&iwad);
+        match iwad_identity::identify_iwad(&iwad, file_hashes.md5.as_deref()) {
+            Some(identity) => {
+                confirmed_iwads.push(iwad);
+                iwad_identities.push((identity, file_hashes));
+            }
+            None => log::info!("Skipping unrecognised IWAD: {}", iwad),
+        }
+    }
+    if confirmed_iwads.is_empty() {
+        return Err(eyre::eyre!(format!(
+            "No IWAD matches found using recursive search in folder - '{}'",
+            &iwad_search_folder
+        )));
     }
 
     // Work out the indexes of what is already selected
@@ -450,27 +581,52 @@ pub fn init_iwads(default_folder: &str, force: bool) -> Result<String, eyre::Rep
 
     let mut count = 0;
 
-    // Save engines to  engines table
+    // Save IWADs to iwads table
     for selection in selections {
-        let internal_wad_type =
-            files::get_internal_wad_type_from_file_name(iwad_list.clone(), &selection)?;
+        let Some((identity, file_hashes)) = confirmed_iwads
+            .iter()
+            .position(|iwad| *iwad == selection)
+            .map(|index| iwad_identities[index].clone())
+        else {
+            continue;
+        };
 
         let existing_iwad = db_iwads
             .iter()
             .find(|e| e.path.to_lowercase() == selection.to_lowercase());
 
         match existing_iwad {
-            Some(_) => {
-                log::info!(
-                    "IWAD already exists, no need to add: {}",
-                    selection.yellow()
-                );
+            Some(existing_iwad) => {
+                // A Custom IWAD keeps the name and game family it was registered with
+                if existing_iwad.internal_wad_type != doom_data::InternalWadType::Custom
+                    && (existing_iwad.internal_wad_type != identity.internal_wad_type
+                        || existing_iwad.release != identity.release)
+                {
+                    db::update_iwad_identity(
+                        existing_iwad.id,
+                        &identity.internal_wad_type,
+                        &identity.release,
+                    )?;
+                    log::info!("Updated IWAD release: {}", selection.yellow());
+                } else {
+                    log::info!(
+                        "IWAD already exists, no need to add: {}",
+                        selection.yellow()
+                    );
+                }
+                if existing_iwad.sha1 != file_hashes.sha1 {
+                    db::update_iwad_hashes(existing_iwad.id, &file_hashes)?;
+                }
             }
             None => {
                 let iwad = data::Iwad {
                     path: selection,
-                    internal_wad_type,
+                    internal_wad_type: identity.internal_wad_type,
+                    release: identity.release,
                     id: 0,
+                    sha1: file_hashes.sha1,
+                    md5: file_hashes.md5,
+                    ..Default::default()
                 };
 
                 db::add_iwad(&iwad)?;
@@ -489,6 +645,41 @@ pub fn init_iwads(default_folder: &str, force: bool) -> Result<String, eyre::Rep
     Ok(iwad_search_folder)
 }
 
+pub fn cli_add_custom_iwad(
+    path: &str,
+    name: &str,
+    game_family: doom_data::GameFamily,
+) -> Result<String, eyre::Report> {
+    if !files::is_iwad(path).unwrap_or(false) {
+        return Ok(format!(
+            "Cannot add IWAD '{}'. Not a WAD file with the IWAD identifier",
+            path
+        ));
+    }
+    if db::get_iwad_by_path(path).is_ok() {
+        return Ok(format!("Cannot add IWAD '{}'. IWAD already exists", path));
+    }
+
+    let file_hashes = files::get_file_hashes(path);
+    let iwad = data::Iwad {
+        id: 0,
+        path: path.to_string(),
+        internal_wad_type: doom_data::InternalWadType::Custom,
+        release: None,
+        name: Some(name.to_string()),
+        game_family: Some(game_family),
+        sha1: file_hashes.sha1,
+        md5: file_hashes.md5,
+    };
+    db::add_iwad(&iwad)?;
+    log::debug!("Added IWAD: {:?}", iwad);
+
+    Ok(format!(
+        "Successfully added IWAD - '{}'",
+        iwad.simple_display()
+    ))
+}
+
 pub fn init_maps(
     default_folder: &str,
     use_doomworld_api: bool,
@@ -525,8 +716,27 @@ pub fn init_maps(
 
     // Get what we have in the database
     let db_maps = db::get_maps()?;
+    // The same WAD is often in the Map folder and the downloads folder under another name.
+    // Maps added before hashing was introduced are hashed first, so their copies are caught
+    let mut known_sha1s: Vec<(String, String)> = Vec::new();
+    for db_map in &db_maps {
+        let sha1 = match &db_map.sha1 {
+            Some(sha1) => Some(sha1.clone()),
+            None => {
+                let file_hashes = files::get_file_hashes(&db_map.path);
+                if file_hashes.sha1.is_some() {
+                    db::update_map_hashes(db_map.id, &file_hashes)?;
+                }
+                file_hashes.sha1
+            }
+        };
+        if let Some(sha1) = sha1 {
+            known_sha1s.push((sha1, db_map.path.clone()));
+        }
+    }
 
     let mut map_count = 0;
+    let mut duplicate_count = 0;
 
     for map_path in map_paths {
         if files::is_iwad(&map_path)? {
@@ -544,10 +754,29 @@ pub fn init_maps(
             .find(|e| e.path.to_lowercase() == map_path.to_lowercase());
 
         match existing_map {
-            Some(_) => {
+            Some(existing_map) => {
                 log::info!("Map already exists, no need to add: {}", map_path.yellow());
+
+                // Maps read by an older dcli are read again to pick up what's new
+                if existing_map.contents_version < constants::MAP_CONTENTS_VERSION {
+                    update_map_contents(existing_map.id, &map_path)?;
+                }
             }
             None => {
+                let file_hashes = files::get_file_hashes(&map_path);
+                if let Some((_, duplicate_path)) = known_sha1s
+                    .iter()
+                    .find(|(sha1, _)| Some(sha1) == file_hashes.sha1.as_ref())
+                {
+                    log::info!(
+                        "Skipping Map, it is the same file as '{}': {}",
+                        duplicate_path,
+                        map_path.yellow()
+                    );
+                    duplicate_count += 1;
+                    continue;
+                }
+
                 log::info!("Getting details for Map: '{}'", map_path);
                 let (title, author, doomworld_id, doomworld_url) = if use_doomworld_api {
                     doomworld_api::lookup_map_from_doomworld_api(&map_path)?
@@ -573,11 +802,18 @@ pub fn init_maps(
                     path: map_path.clone(),
                     doomworld_id,
                     doomworld_url,
+                    sha1: file_hashes.sha1.clone(),
+                    md5: file_hashes.md5,
+                    ..Default::default()
                 };
 
-                db::add_map(&map)?;
+                let add_result = db::add_map(&map)?;
+                if let Some(sha1) = file_hashes.sha1 {
+                    known_sha1s.push((sha1, map_path.clone()));
+                }
                 log::info!("Added Map: {}", map.simple_display().blue());
                 log::debug!("  Map {:?}", map);
+                update_map_contents(add_result.last_insert_rowid() as i32, &map.path)?;
 
                 map_count += 1;
             }
@@ -588,10 +824,115 @@ pub fn init_maps(
         let result_message = format!("Successfully added {} Maps", map_count);
         log::info!("{}", result_message.green());
     }
+    if duplicate_count > 0 {
+        log::info!(
+            "Skipped {} duplicate Maps, use 'list duplicates' to see them",
+            duplicate_count
+        );
+    }
 
     Ok(map_search_folder)
 }
 
+// A Map that can't be read is still worth adding, so this only fails if the database does
+pub fn update_map_contents(map_id: i32, map_path: &str) -> Result<(), eyre::Report> {
+    let extension = Path::new(map_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let read_result = match extension.as_str() {
+        doom_data::EXT_WAD => wad::read_wad_contents(map_path),
+        doom_data::EXT_PK3 | doom_data::EXT_PKE => archive::read_archive_contents(map_path),
+        _ => return Ok(()),
+    };
+
+    match read_result {
+        Ok(mut map_contents) => {
+            log::debug!(
+                "  Found {} levels and {} key lumps",
+                map_contents.levels.len(),
+                map_contents.lumps.len()
+            );
+            // A WAD's readme sits next to it, a PK3's was read from inside the archive
+            if let Some(lines) = files::get_map_readme_file_name(map_path)
+                .ok()
+                .flatten()
+                .and_then(|readme| paths::lines_from_file("readme", &readme).ok())
+            {
+                let text = lines.join("\n");
+                map_contents
+                    .comp_level_evidence
+                    .extend(complevel::get_readme_evidence(&text));
+                map_contents.readme_game = map_contents
+                    .readme_game
+                    .or_else(|| required_iwad::get_readme_game(&text));
+            }
+
+            // GAMEINFO says which IWAD outright, otherwise it's worked out from the levels
+            let levels = read_map_levels(map_path, &map_contents);
+            if map_contents.required_iwad.is_none() {
+                let textures: BTreeSet<String> = levels
+                    .iter()
+                    .flat_map(|(_, level_data)| level_data.textures.iter().cloned())
+                    .collect();
+                map_contents.inferred_iwad =
+                    required_iwad::get_required_iwad(&map_contents, &textures);
+            }
+            map_contents.level_stats = get_map_level_stats(&map_contents, &levels);
+            db::save_map_contents(map_id, &map_contents)?;
+
+            // Only replace a title that was made up from the file name
+            if let Some(title) = map_contents.title {
+                let mut map = db::get_map_by_id(map_id)?;
+                if map.title == paths::extract_file_name(map_path)
+                    || map.title == constants::DEFAULT_UNKNOWN
+                {
+                    map.title = title;
+                    db::update_map(map)?;
+                }
+            }
+            Ok(())
+        }
+        Err(e) => {
+            log::warn!("Unable to read levels from Map '{}': {}", map_path, e);
+            db::update_map_contents_version(map_id)?;
+            Ok(())
+        }
+    }
+}
+
+// Levels that can't be read are left out rather than failing the whole Map
+fn read_map_levels(
+    map_path: &str,
+    map_contents: &data::MapContents,
+) -> Vec<(String, level_data::LevelData)> {
+    map_contents
+        .levels
+        .iter()
+        .filter_map(|level| {
+            level_data::read_level(map_path, &level.lump_name)
+                .inspect_err(|e| log::debug!("  Unable to read level '{}': {}", level.lump_name, e))
+                .ok()
+                .map(|level_data| (level.lump_name.clone(), level_data))
+        })
+        .collect()
+}
+
+fn get_map_level_stats(
+    map_contents: &data::MapContents,
+    levels: &[(String, level_data::LevelData)],
+) -> Vec<data::LevelStats> {
+    let internal_wad_type = files::get_required_internal_wad_type(map_contents.iwad());
+    let thing_types = doom_data::get_thing_type_list(&internal_wad_type);
+
+    levels
+        .iter()
+        .flat_map(|(lump_name, level_data)| {
+            level_stats::get_level_stats(lump_name, level_data, &thing_types)
+        })
+        .collect()
+}
+
 pub fn update_engines() -> Result<String, eyre::Report> {
     let mut app_settings = db::get_app_settings()?;
     let folder = init_engines(
@@ -679,6 +1020,14 @@ pub fn update_map_info() -> Result<String, eyre::Report> {
                 path: map.path.clone(),
                 doomworld_id,
                 doomworld_url,
+                required_iwad: map.required_iwad.clone(),
+                inferred_iwad: map.inferred_iwad.clone(),
+                contents_version: map.contents_version,
+                requires_zdoom: map.requires_zdoom,
+                comp_level: map.comp_level.clone(),
+                comp_level_confidence: map.comp_level_confidence.clone(),
+                sha1: map.sha1.clone(),
+                md5: map.md5.clone(),
             };
             db::update_map(update_map.clone())?;
             log::info!("  Updated Map: {}", update_map.simple_display().blue());
@@ -851,18 +1200,167 @@ pub fn list_iwads() -> Result<String, eyre::Report> {
     Ok(table)
 }
 
-pub fn list_maps() -> Result<String, eyre::Report> {
-    let maps = db::get_maps().wrap_err("Unable to maps listing".to_string())?;
+pub fn list_duplicates() -> Result<String, eyre::Report> {
+    let app_settings = db::get_app_settings()?;
+
+    // The downloads folder sits inside the Map folder, so is searched with it
+    let mut file_paths: Vec<String> = Vec::new();
+    if let Some(map_search_folder) = &app_settings.map_search_folder {
+        file_paths.extend(paths::find_files_with_extensions_in_folders(
+            map_search_folder,
+            doom_data::GAME_FILES.to_vec(),
+            "Maps",
+        ));
+    }
+    if let Some(iwad_search_folder) = &app_settings.iwad_search_folder {
+        file_paths.extend(paths::find_files_with_extensions_in_folders(
+            iwad_search_folder,
+            vec![doom_data::EXT_WAD],
+            "IWADs",
+        ));
+    }
+    file_paths.sort();
+    file_paths.dedup();
+
+    let file_hashes: Vec<(String, String)> = file_paths
+        .into_iter()
+        .filter_map(|file_path| {
+            let sha1 = files::get_file_hashes(&file_path).sha1?;
+            Some((file_path, sha1))
+        })
+        .collect();
+    let duplicates: Vec<data::DuplicateFiles> = files::group_duplicates(&file_hashes)
+        .into_iter()
+        .map(|(sha1, paths)| data::DuplicateFiles {
+            sha1,
+            paths: paths.join("\n"),
+        })
+        .collect();
+
+    if duplicates.is_empty() {
+        return Ok("No duplicate files found".to_string());
+    }
+
+    let table = tabled::Table::new(duplicates)
+        .with(Style::modern())
+        .to_string();
+    Ok(table)
+}
+
+pub fn list_maps(
+    list_type: data::ListType,
+    map_filter: Option<String>,
+) -> Result<String, eyre::Report> {
+    let mut maps = db::get_map_display_list().wrap_err("Unable to maps listing".to_string())?;
 
     if maps.is_empty() {
         return Ok("No Maps found".to_string());
     }
 
-    let table = tabled::Table::new(maps)
+    if let Some(map_filter) = map_filter {
+        let map_name = map_filter.to_lowercase();
+        maps.retain(|map| {
+            map.title.to_lowercase() == map_name
+                || paths::extract_file_name(&map.path).to_lowercase() == map_name
+        });
+        if maps.is_empty() {
+            return Ok(format!("No Map found for '{}'", map_filter));
+        }
+        return list_map_level_stats(&maps[0]);
+    }
+
+    if matches!(list_type, data::ListType::Summary) {
+        return Ok(tabled::Table::new(maps)
+            .with(Modify::new(Rows::new(1..)).with(Width::wrap(30)))
+            .with(Style::modern())
+            .to_string());
+    }
+
+    // Add the Hard Skill Tier totals of each Map to the maps table. Per level
+    // stats are listed by picking a single Map
+    let level_stats_list =
+        db::get_level_stats_list().wrap_err("Unable to get level stats listing".to_string())?;
+    let summaries = level_stats::summarise_map_stats(&level_stats_list);
+
+    let mut builder = Builder::default();
+    let mut headers: Vec<String> = data::MapDisplay::headers()
+        .into_iter()
+        .map(|header| header.to_string())
+        .collect();
+    headers.extend(
+        ["Levels", "UV Monsters", "UV Monster HP", "Secrets"].map(|header| header.to_string()),
+    );
+    builder.push_record(headers);
+    for map in maps {
+        let mut record: Vec<String> = map
+            .fields()
+            .into_iter()
+            .map(|field| field.to_string())
+            .collect();
+        match summaries.iter().find(|summary| summary.map_id == map.id) {
+            Some(summary) => record.extend([
+                summary.levels.to_string(),
+                summary.monsters.to_string(),
+                summary.monster_hit_points.to_string(),
+                summary.secrets.to_string(),
+            ]),
+            None => record.extend(vec![String::new(); 4]),
+        }
+        builder.push_record(record);
+    }
+    Ok(builder
+        .build()
+        .with(Modify::new(Rows::new(1..)).with(Width::wrap(30)))
+        .with(Style::modern())
+        .to_string())
+}
+
+fn list_map_level_stats(map: &data::MapDisplay) -> Result<String, eyre::Report> {
+    let table = tabled::Table::new(vec![map])
         .with(Modify::new(Rows::new(1..)).with(Width::wrap(30)))
         .with(Style::modern())
         .to_string();
-    Ok(table)
+
+    let level_stats_list: Vec<data::LevelStats> = db::get_level_stats_list()
+        .wrap_err("Unable to get level stats listing".to_string())?
+        .into_iter()
+        .filter(|level_stats| level_stats.map_id == map.id)
+        .collect();
+    if level_stats_list.is_empty() {
+        return Ok(table);
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record([
+        "Level",
+        "Skill",
+        "Monsters",
+        "Monster HP",
+        "Items",
+        "Weapons",
+        "Ammo",
+        "Keys",
+        "Secrets",
+    ]);
+    for level_stats in level_stats_list {
+        builder.push_record([
+            level_stats.lump_name,
+            level_stats.skill_tier.to_string(),
+            level_stats.monsters.to_string(),
+            level_stats.monster_hit_points.to_string(),
+            level_stats.items.to_string(),
+            level_stats.weapons.to_string(),
+            level_stats.ammo.to_string(),
+            level_stats.keys.to_string(),
+            level_stats.secrets.to_string(),
+        ]);
+    }
+    let stats_table = builder
+        .build()
+        .with(Modify::new(Rows::new(1..)).with(Width::wrap(30)))
+        .with(Style::modern())
+        .to_string();
+    Ok(format!("{}\n{}", table, stats_table))
 }
 
 pub fn list_app_settings() -> Result<String, eyre::Report> {
@@ -1,8 +1,10 @@
+use std::str::FromStr;
+
 use eyre::Context;
 use inquire::InquireError;
 use log::info;
 
-use crate::{data, db, tui};
+use crate::{constants, data, db, tui};
 
 fn pick_from_map_from_profile_map_ids(map_ids: data::MapIds) -> Result<i32, eyre::Report> {
     let map_list = db::get_maps_by_ids(map_ids)?;
@@ -112,6 +114,50 @@ pub fn get_map_id_from_pick_map(option_text: &str, error_str: &str) -> Result<i3
     Err(InquireError::OperationCanceled).wrap_err(error_str.to_string())
 }
 
+pub fn get_comp_level_selection(
+    prompt: &str,
+    comp_level: &Option<data::CompLevel>,
+) -> Result<Option<data::CompLevel>, eyre::Report> {
+    let selections = vec![
+        constants::MENU_NOT_SET.to_string(),
+        data::CompLevel::DoomV12.to_string(),
+        data::CompLevel::DoomV1666.to_string(),
+        data::CompLevel::DoomV19.to_string(),
+        data::CompLevel::UltimateDoom.to_string(),
+        data::CompLevel::FinalDoom.to_string(),
+        data::CompLevel::DosDoom.to_string(),
+        data::CompLevel::TasDoom.to_string(),
+        data::CompLevel::Boom.to_string(),
+        data::CompLevel::BoomV201.to_string(),
+        data::CompLevel::BoomV202.to_string(),
+        data::CompLevel::LxDoom.to_string(),
+        data::CompLevel::Mbf.to_string(),
+        data::CompLevel::PrBoomV203.to_string(),
+        data::CompLevel::PrBoomV210.to_string(),
+        data::CompLevel::PrBoomV211.to_string(),
+        data::CompLevel::PrBoomV23.to_string(),
+        data::CompLevel::PrBoomV24.to_string(),
+        data::CompLevel::PrBoomPlus.to_string(),
+        data::CompLevel::Mbf21.to_string(),
+    ];
+
+    let starting_cursor = match comp_level {
+        Some(c) => selections.iter().position(|x| x == &c.to_string()).unwrap(),
+        None => 0,
+    };
+
+    let comp_level = inquire::Select::new(prompt, selections)
+        .with_starting_cursor(starting_cursor)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .prompt()?;
+
+    if comp_level == constants::MENU_NOT_SET {
+        Ok(None)
+    } else {
+        Ok(Some(data::CompLevel::from_str(&comp_level).unwrap()))
+    }
+}
+
 pub fn get_map_selection(
     maps: Vec<data::Map>,
     default_maps: Vec<usize>,
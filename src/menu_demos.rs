@@ -0,0 +1,677 @@
+use std::{fs, io::Read, path::Path};
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use eyre::Context;
+use inquire::validator::Validation;
+use tabled::{
+    builder::Builder,
+    settings::{object::Rows, Modify, Style, Width},
+};
+use zip::ZipArchive;
+
+use crate::{data, db, demo, doom_data, paths, runner, tui};
+
+const ZIP_EXTENSION: &str = "zip";
+const NOTES_EXTENSION: &str = "txt";
+
+// A Demo found while importing, along with the text file that came with it. DSDA
+// uploads are a zip of the demo and a text file naming the WAD that was played.
+struct DemoFile {
+    file_name: String,
+    bytes: Vec<u8>,
+    notes: String,
+    // When the file was last written, which is as close to the recording as can be told
+    date_recorded: DateTime<Utc>,
+}
+
+pub fn pick_and_play_demo(
+    demo_action: data::DemoAction,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
+    let demos = db::get_demos()?;
+    if demos.is_empty() {
+        return Ok(
+            "Cannot Play Demo, there are no Demos found. Turn on Record Demo in Play Settings"
+                .to_string(),
+        );
+    }
+
+    let demo = inquire::Select::new(&format!("Pick the Demo for {}:", demo_action), demos)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .prompt_skippable()?;
+
+    match demo {
+        Some(demo) => runner::play_demo(&demo, demo_action, play_mode),
+        None => Ok("No Demo selected".to_string()),
+    }
+}
+
+pub fn cli_play_demo(
+    demo_name: &str,
+    demo_action: data::DemoAction,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
+    let demo = db::get_demo_by_name(demo_name)?;
+    runner::play_demo(&demo, demo_action, play_mode)
+}
+
+pub fn delete_demo() -> Result<String, eyre::Report> {
+    let demos = db::get_demos()?;
+    if demos.is_empty() {
+        return Ok("There are no Demos to delete".to_string());
+    }
+
+    let demo_selection = inquire::Select::new("Pick the Demo to Delete:", demos)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .prompt_skippable()?;
+
+    match demo_selection {
+        Some(demo) => {
+            if !inquire::Confirm::new(&format!(
+                "Are you sure you want to delete Demo '{}'? The Demo file will also be deleted",
+                demo.name
+            ))
+            .with_default(false)
+            .prompt()?
+            {
+                return Ok("Canceled Demo deletion".to_string());
+            }
+
+            db::delete_demo(demo.id)?;
+            if paths::file_exists(&demo.path) {
+                paths::delete_file(&demo.path)
+                    .wrap_err(format!("Failed to delete Demo file '{}'", demo.path))?;
+            }
+            Ok(format!("Successfully deleted Demo '{}'", demo.name))
+        }
+        None => Ok("No changes made to deleting Demo".to_string()),
+    }
+}
+
+pub fn list_demos() -> Result<String, eyre::Report> {
+    let demos = db::get_demos().wrap_err("Unable to get Demo listing".to_string())?;
+    if demos.is_empty() {
+        return Ok("There are no Demos to list".to_string());
+    }
+
+    let profiles = db::get_profiles()?;
+    let mut builder = Builder::default();
+    builder.push_record([
+        "Name",
+        "Profile",
+        "Warp",
+        "Skill",
+        "Compatibility Level",
+        "Date Recorded",
+        "Path",
+    ]);
+    for demo in demos {
+        let profile_name = profiles
+            .iter()
+            .find(|profile| Some(profile.id) == demo.profile_id)
+            .map(|profile| profile.name.clone());
+        builder.push_record([
+            demo.name,
+            data::display_option_string(&profile_name),
+            data::display_option_string(&demo.warp),
+            data::display_option_u8(&demo.skill),
+            data::display_option_comp_level(&demo.comp_level),
+            data::display_utc_datetime_to_local(&demo.date_recorded),
+            demo.path,
+        ]);
+    }
+
+    let mut table = builder.build();
+    Ok(table
+        .with(Modify::new(Rows::new(1..)).with(Width::wrap(30)))
+        .with(Style::modern())
+        .to_string())
+}
+
+pub fn import_demos() -> Result<String, eyre::Report> {
+    let folder = inquire::Text::new("Folder to import Demos from:")
+        .with_validator(|input: &str| {
+            let resolved_path = &paths::resolve_path(input);
+            if paths::folder_exists(resolved_path) {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    format!("Folder does not exist - '{}'", resolved_path).into(),
+                ))
+            }
+        })
+        .with_help_message("Demos can be .lmp files or zips downloaded from DSDA")
+        .prompt()?;
+
+    import_demos_from_folder(&paths::resolve_path(&folder))
+}
+
+pub fn cli_import_demos(folder: &str) -> Result<String, eyre::Report> {
+    if !paths::folder_exists(folder) {
+        return Ok(format!(
+            "Cannot import Demos. Folder does not exist - '{}'",
+            folder
+        ));
+    }
+
+    import_demos_from_folder(folder)
+}
+
+fn import_demos_from_folder(folder: &str) -> Result<String, eyre::Report> {
+    let engines = db::get_engines()?;
+    if engines.is_empty() {
+        return Ok("Cannot import Demos, there are no Engines. Please run 'init'".to_string());
+    }
+    let iwads = db::get_iwads()?;
+    if iwads.is_empty() {
+        return Ok("Cannot import Demos, there are no IWADs. Please run 'init'".to_string());
+    }
+    let maps = db::get_maps()?;
+    let app_settings = db::get_app_settings()?;
+    let mut existing_demo_paths: Vec<String> =
+        db::get_demos()?.into_iter().map(|demo| demo.path).collect();
+
+    let demo_files = get_demo_files(folder);
+    if demo_files.is_empty() {
+        return Ok(format!("No Demos found to import in '{}'", folder));
+    }
+
+    let mut imported_count = 0;
+    let mut created_profile_count = 0;
+    let mut skipped_count = 0;
+    for demo_file in demo_files {
+        let header = match demo::parse_demo_header(&demo_file.bytes) {
+            Ok(header) => header,
+            Err(e) => {
+                log::warn!("  Skipped Demo '{}' - {}", demo_file.file_name, e);
+                skipped_count += 1;
+                continue;
+            }
+        };
+
+        let map_ids = get_map_ids_from_notes(&maps, &demo_file.notes);
+        let iwad = pick_iwad(
+            &header,
+            &iwads,
+            app_settings.default_iwad_id,
+            &demo_file.notes,
+        );
+        let comp_level = header.get_comp_level(&iwad.internal_wad_type);
+
+        // Profiles are fetched each time, as an earlier Demo may have created one
+        let profiles = db::get_profiles()?;
+        let profile = match find_profile(&profiles, iwad.id, &map_ids, &comp_level) {
+            Some(profile) => profile.clone(),
+            None => {
+                let engine = pick_engine(&engines, app_settings.default_engine_id);
+                let profile = data::Profile {
+                    id: 0,
+                    name: get_profile_name(&profiles, &maps, &map_ids, iwad, &comp_level),
+                    engine_id: Some(engine.id),
+                    iwad_id: Some(iwad.id),
+                    map_id: map_ids.first().copied(),
+                    map_id2: map_ids.get(1).copied(),
+                    map_id3: map_ids.get(2).copied(),
+                    map_id4: map_ids.get(3).copied(),
+                    map_id5: map_ids.get(4).copied(),
+                    save_game: None,
+                    additional_arguments: None,
+                    date_created: Utc::now(),
+                    date_edited: Utc::now(),
+                    date_last_run: None,
+                    run_count: 0,
+                    comp_level: Some(comp_level.clone()),
+                    environment: None,
+                    working_directory: None,
+                    wrapper_command: None,
+                    pre_launch_hook: None,
+                    post_exit_hook: None,
+                };
+                let add_result = db::add_profile(profile)?;
+                let add_profile_id: i32 = add_result.last_insert_rowid().try_into().unwrap();
+                let profile = db::get_profile_by_id(add_profile_id)?;
+                log::info!("  Created Profile '{}'", profile.name);
+                created_profile_count += 1;
+                profile
+            }
+        };
+
+        let demo_folder = demo::get_demo_folder(Some(&profile.name));
+        let Some(demo_path) = get_import_path(&demo_folder, &demo_file, &existing_demo_paths)
+        else {
+            log::info!("  Skipped Demo '{}', already imported", demo_file.file_name);
+            skipped_count += 1;
+            continue;
+        };
+        paths::create_folder(&demo_folder)
+            .wrap_err(format!("Failed to create Demo folder '{}'", demo_folder))?;
+        fs::write(&demo_path, &demo_file.bytes)
+            .wrap_err(format!("Failed to save Demo '{}'", demo_path))?;
+        existing_demo_paths.push(demo_path.clone());
+
+        let demo = data::Demo {
+            id: 0,
+            name: paths::extract_file_name_no_extension(&demo_path),
+            path: demo_path,
+            profile_id: Some(profile.id),
+            engine_id: profile.engine_id.unwrap(),
+            iwad_id: profile.iwad_id.unwrap(),
+            map_id: profile.map_id,
+            map_id2: profile.map_id2,
+            map_id3: profile.map_id3,
+            map_id4: profile.map_id4,
+            map_id5: profile.map_id5,
+            warp: Some(header.get_warp(&iwad.internal_wad_type)),
+            skill: Some(header.skill),
+            date_recorded: demo_file.date_recorded,
+            comp_level: Some(comp_level),
+        };
+        db::add_demo(&demo)?;
+        log::info!(
+            "  Imported {} Demo '{}' to Profile '{}'",
+            header.demo_format,
+            demo.name,
+            profile.name
+        );
+        imported_count += 1;
+    }
+
+    Ok(format!(
+        "Successfully imported {} Demos, created {} Profiles and skipped {} Demos",
+        imported_count, created_profile_count, skipped_count
+    ))
+}
+
+// Demos from different folders or zips can share a file name, so a different Demo with the
+// same name gets a number added. None if this Demo has already been imported
+fn get_import_path(
+    demo_folder: &str,
+    demo_file: &DemoFile,
+    existing_demo_paths: &[String],
+) -> Option<String> {
+    let base_name = paths::extract_file_name_no_extension(&demo_file.file_name);
+    for index in 1.. {
+        let file_name = match index {
+            1 => format!("{}.{}", base_name, demo::DEMO_EXTENSION),
+            _ => format!("{}_{}.{}", base_name, index, demo::DEMO_EXTENSION),
+        };
+        let demo_path = paths::get_full_path(demo_folder, &file_name);
+        if !existing_demo_paths.contains(&demo_path) && !paths::file_exists(&demo_path) {
+            return Some(demo_path);
+        }
+        if fs::read(&demo_path).is_ok_and(|bytes| bytes == demo_file.bytes) {
+            return None;
+        }
+    }
+    None
+}
+
+fn get_demo_files(folder: &str) -> Vec<DemoFile> {
+    let mut demo_files: Vec<DemoFile> = Vec::new();
+    for file_path in paths::find_files_with_extensions_in_folders(
+        folder,
+        vec![demo::DEMO_EXTENSION, ZIP_EXTENSION],
+        "Demos",
+    ) {
+        let result = if has_extension(&file_path, ZIP_EXTENSION) {
+            get_demo_files_from_zip(&file_path)
+        } else {
+            get_demo_file(&file_path).map(|demo_file| vec![demo_file])
+        };
+        match result {
+            Ok(found_demo_files) => demo_files.extend(found_demo_files),
+            Err(e) => log::warn!("  Skipped '{}' - {}", file_path, e),
+        }
+    }
+    demo_files
+}
+
+fn get_demo_file(file_path: &str) -> Result<DemoFile, eyre::Report> {
+    let bytes = fs::read(file_path).wrap_err(format!("Failed to read Demo '{}'", file_path))?;
+
+    // Only trust notes written for this Demo, a folder may hold notes for many
+    let notes_path = Path::new(file_path).with_extension(NOTES_EXTENSION);
+    let notes = fs::read(notes_path)
+        .map(|notes| String::from_utf8_lossy(&notes).to_lowercase())
+        .unwrap_or_default();
+
+    Ok(DemoFile {
+        file_name: paths::extract_file_name(file_path),
+        bytes,
+        notes,
+        date_recorded: get_file_date(file_path).unwrap_or_else(Utc::now),
+    })
+}
+
+fn get_demo_files_from_zip(zip_path: &str) -> Result<Vec<DemoFile>, eyre::Report> {
+    let reader = paths::open_file(zip_path)?;
+    let mut archive = ZipArchive::new(reader)?;
+
+    let zip_date = get_file_date(zip_path).unwrap_or_else(Utc::now);
+    let mut demos: Vec<(String, Vec<u8>, DateTime<Utc>)> = Vec::new();
+    let mut notes = String::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let file_name = file.name().to_string();
+        if has_extension(&file_name, demo::DEMO_EXTENSION) {
+            let date_recorded = file
+                .last_modified()
+                .and_then(get_zip_entry_date)
+                .unwrap_or(zip_date);
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            demos.push((paths::extract_file_name(&file_name), bytes, date_recorded));
+        } else if has_extension(&file_name, NOTES_EXTENSION) {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            notes.push_str(&String::from_utf8_lossy(&bytes).to_lowercase());
+            notes.push('\n');
+        }
+    }
+
+    Ok(demos
+        .into_iter()
+        .map(|(file_name, bytes, date_recorded)| DemoFile {
+            file_name,
+            bytes,
+            notes: notes.clone(),
+            date_recorded,
+        })
+        .collect())
+}
+
+fn get_file_date(file_path: &str) -> Option<DateTime<Utc>> {
+    fs::metadata(file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(DateTime::<Utc>::from)
+}
+
+// Zip entries keep the local time they were zipped at
+fn get_zip_entry_date(date_time: zip::DateTime) -> Option<DateTime<Utc>> {
+    let naive_date_time = NaiveDate::from_ymd_opt(
+        date_time.year().into(),
+        date_time.month().into(),
+        date_time.day().into(),
+    )?
+    .and_hms_opt(
+        date_time.hour().into(),
+        date_time.minute().into(),
+        date_time.second().into(),
+    )?;
+    Local
+        .from_local_datetime(&naive_date_time)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+fn has_extension(file_path: &str, extension: &str) -> bool {
+    Path::new(file_path)
+        .extension()
+        .is_some_and(|file_extension| file_extension.eq_ignore_ascii_case(extension))
+}
+
+fn notes_mention(notes: &str, file_name: &str) -> bool {
+    // Match whole file names only, so "av.wad" isn't found in "sav.wad"
+    notes.match_indices(file_name).any(|(index, _)| {
+        !notes[..index]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+fn get_map_ids_from_notes(maps: &[data::Map], notes: &str) -> Vec<i32> {
+    let mut found_file_names: Vec<String> = Vec::new();
+    let mut map_ids: Vec<i32> = Vec::new();
+    for map in maps {
+        // ASSUMPTION CORNER: Like 'add-profile', the first Map with a matching file name wins
+        let file_name = paths::extract_file_name(&map.path).to_lowercase();
+        if map_ids.len() < 5
+            && !found_file_names.contains(&file_name)
+            && notes_mention(notes, &file_name)
+        {
+            found_file_names.push(file_name);
+            map_ids.push(map.id);
+        }
+    }
+    map_ids
+}
+
+fn pick_iwad<'a>(
+    header: &demo::DemoHeader,
+    iwads: &'a [data::Iwad],
+    default_iwad_id: Option<i32>,
+    notes: &str,
+) -> &'a data::Iwad {
+    // An IWAD named in the notes wins, otherwise go by the map numbering
+    if let Some(iwad) = iwads
+        .iter()
+        .find(|iwad| notes_mention(notes, &paths::extract_file_name(&iwad.path).to_lowercase()))
+    {
+        return iwad;
+    }
+
+    let candidates: Vec<&data::Iwad> = iwads
+        .iter()
+        .filter(|iwad| {
+            let episodic = matches!(
+                iwad.internal_wad_type,
+                doom_data::InternalWadType::Doom | doom_data::InternalWadType::DoomShareware
+            );
+            let non_episodic = matches!(
+                iwad.internal_wad_type,
+                doom_data::InternalWadType::Doom2
+                    | doom_data::InternalWadType::Tnt
+                    | doom_data::InternalWadType::Plutonia
+            );
+            match header.is_episodic() {
+                Some(true) => episodic,
+                Some(false) => non_episodic,
+                None => episodic || non_episodic,
+            }
+        })
+        .collect();
+
+    candidates
+        .iter()
+        .find(|iwad| Some(iwad.id) == default_iwad_id)
+        .or(candidates.first())
+        .copied()
+        .or_else(|| iwads.iter().find(|iwad| Some(iwad.id) == default_iwad_id))
+        .unwrap_or(&iwads[0])
+}
+
+fn pick_engine(engines: &[data::Engine], default_engine_id: Option<i32>) -> &data::Engine {
+    // Demos are usually recorded with dsda-doom, which needs the Compatibility Level
+    engines
+        .iter()
+        .find(|engine| engine.game_engine_type == doom_data::GameEngineType::PrBoomPlus)
+        .or_else(|| {
+            engines
+                .iter()
+                .find(|engine| Some(engine.id) == default_engine_id)
+        })
+        .unwrap_or(&engines[0])
+}
+
+fn find_profile<'a>(
+    profiles: &'a [data::Profile],
+    iwad_id: i32,
+    map_ids: &[i32],
+    comp_level: &data::CompLevel,
+) -> Option<&'a data::Profile> {
+    let mut map_ids = map_ids.to_vec();
+    map_ids.sort();
+    profiles.iter().find(|profile| {
+        let mut profile_map_ids: Vec<i32> = [
+            profile.map_id,
+            profile.map_id2,
+            profile.map_id3,
+            profile.map_id4,
+            profile.map_id5,
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .collect();
+        profile_map_ids.sort();
+
+        profile.iwad_id == Some(iwad_id)
+            && profile.comp_level.as_ref() == Some(comp_level)
+            && profile_map_ids == map_ids
+    })
+}
+
+fn get_profile_name(
+    profiles: &[data::Profile],
+    maps: &[data::Map],
+    map_ids: &[i32],
+    iwad: &data::Iwad,
+    comp_level: &data::CompLevel,
+) -> String {
+    let base_name = map_ids
+        .first()
+        .and_then(|map_id| maps.iter().find(|map| map.id == *map_id))
+        .map(|map| match map.title.trim().is_empty() {
+            true => paths::extract_file_name_no_extension(&map.path),
+            false => map.title.clone(),
+        })
+        .unwrap_or_else(|| paths::extract_file_name_no_extension(&iwad.path));
+    let profile_name = format!("{} - {}", base_name, comp_level);
+
+    let mut unique_name = profile_name.clone();
+    let mut count = 2;
+    while profiles
+        .iter()
+        .any(|profile| profile.name.eq_ignore_ascii_case(&unique_name))
+    {
+        unique_name = format!("{} ({})", profile_name, count);
+        count += 1;
+    }
+    unique_name
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::{
+        data, doom_data,
+        menu_demos::{find_profile, get_import_path, pick_engine, DemoFile},
+    };
+
+    fn get_profile(id: i32, iwad_id: i32, map_ids: [Option<i32>; 2]) -> data::Profile {
+        data::Profile {
+            id,
+            name: format!("Profile {}", id),
+            engine_id: Some(1),
+            iwad_id: Some(iwad_id),
+            map_id: map_ids[0],
+            map_id2: map_ids[1],
+            map_id3: None,
+            map_id4: None,
+            map_id5: None,
+            date_created: Utc::now(),
+            date_edited: Utc::now(),
+            date_last_run: None,
+            run_count: 0,
+            save_game: None,
+            additional_arguments: None,
+            comp_level: Some(data::CompLevel::BoomV202),
+            environment: None,
+            working_directory: None,
+            wrapper_command: None,
+            pre_launch_hook: None,
+            post_exit_hook: None,
+        }
+    }
+
+    fn get_demo_file(file_name: &str, bytes: &[u8]) -> DemoFile {
+        DemoFile {
+            file_name: file_name.to_string(),
+            bytes: bytes.to_vec(),
+            notes: String::new(),
+            date_recorded: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_pick_engine() {
+        // Arrange
+        let engine = |id: i32, game_engine_type: doom_data::GameEngineType| data::Engine {
+            id,
+            game_engine_type,
+            ..Default::default()
+        };
+        let engines = vec![
+            engine(1, doom_data::GameEngineType::GzDoom),
+            engine(2, doom_data::GameEngineType::Woof),
+            engine(3, doom_data::GameEngineType::PrBoomPlus),
+        ];
+        let no_prboom_plus = &engines[..2];
+
+        // Act
+        let actual_prboom_plus = pick_engine(&engines, Some(2));
+        let actual_default = pick_engine(no_prboom_plus, Some(2));
+        let actual_first = pick_engine(no_prboom_plus, None);
+
+        // Assert
+        assert_eq!(actual_prboom_plus.id, 3);
+        assert_eq!(actual_default.id, 2);
+        assert_eq!(actual_first.id, 1);
+    }
+
+    #[test]
+    fn test_find_profile() {
+        // Arrange
+        let profiles = vec![
+            get_profile(1, 10, [Some(20), None]),
+            get_profile(2, 10, [Some(20), Some(21)]),
+            get_profile(3, 11, [Some(21), Some(20)]),
+        ];
+
+        // Act
+        let actual_single_map = find_profile(&profiles, 10, &[20], &data::CompLevel::BoomV202);
+        let actual_map_order = find_profile(&profiles, 11, &[20, 21], &data::CompLevel::BoomV202);
+        let actual_comp_level = find_profile(&profiles, 10, &[20], &data::CompLevel::Mbf21);
+        let actual_no_maps = find_profile(&profiles, 10, &[], &data::CompLevel::BoomV202);
+
+        // Assert
+        assert_eq!(actual_single_map.map(|profile| profile.id), Some(1));
+        assert_eq!(actual_map_order.map(|profile| profile.id), Some(3));
+        assert!(actual_comp_level.is_none());
+        assert!(actual_no_maps.is_none());
+    }
+
+    #[test]
+    fn test_get_import_path() {
+        // Arrange
+        // ./test-data/imported.lmp has already been imported
+        let already_imported = get_demo_file("imported.lmp", b"DEMO\x01\x02");
+        let same_name = get_demo_file("imported.lmp", b"DEMO\x03\x04");
+        let in_same_import = get_demo_file("new.LMP", b"DEMO\x05\x06");
+        let existing_demo_paths = vec![
+            "./test-data/new.lmp".to_string(),
+            "./test-data/new_2.lmp".to_string(),
+        ];
+
+        // Act
+        let actual_already_imported =
+            get_import_path("./test-data", &already_imported, &existing_demo_paths);
+        let actual_same_name = get_import_path("./test-data", &same_name, &existing_demo_paths);
+        let actual_in_same_import =
+            get_import_path("./test-data", &in_same_import, &existing_demo_paths);
+
+        // Assert
+        assert_eq!(actual_already_imported, None);
+        assert_eq!(
+            actual_same_name,
+            Some("./test-data/imported_2.lmp".to_string())
+        );
+        assert_eq!(
+            actual_in_same_import,
+            Some("./test-data/new_3.lmp".to_string())
+        );
+    }
+}
@@ -118,6 +118,7 @@ pub fn list_editors() -> Result<String, eyre::Report> {
     Ok(table)
 }
 
+#[allow(clippy::to_string_in_format_args)]
 pub fn add_editor() -> Result<String, eyre::Report> {
     let app_settings = db::get_app_settings()?;
 
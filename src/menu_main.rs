@@ -3,12 +3,14 @@ use color_eyre::{
     eyre::{self},
     Result,
 };
+use inquire::validator::Validation;
 use owo_colors::{colors::xterm, OwoColorize};
 use uuid::Uuid;
 
 use crate::{
+    constants,
     data::{self, ProfileDisplay},
-    db, paths, runner, tui,
+    db, launch_log, levels, paths, runner, tui,
 };
 
 pub fn get_default_profile_text() -> Result<String, eyre::Report> {
@@ -53,37 +55,46 @@ pub fn get_last_profile_text() -> Result<String, eyre::Report> {
     ))
 }
 
-pub fn play_default_profile() -> Result<String, eyre::Report> {
+pub fn play_default_profile(play_mode: data::PlayMode) -> Result<String, eyre::Report> {
     let app_settings = db::get_app_settings()?;
 
     if app_settings.default_profile_id.is_none() {
         return Ok("No Default Profile found. Please set one".to_string());
     };
 
-    runner::play_from_profile(app_settings.default_profile_id.unwrap(), false)
+    runner::play_from_profile(app_settings.default_profile_id.unwrap(), false, play_mode)
 }
 
-pub fn play_last_profile() -> Result<String, eyre::Report> {
+pub fn play_last_profile(play_mode: data::PlayMode) -> Result<String, eyre::Report> {
     let app_settings = db::get_app_settings()?;
 
     if app_settings.last_profile_id.is_none() {
         return Ok("No Last Run Profile found. Run a profile to make it the last run".to_string());
     };
 
-    runner::play_from_profile(app_settings.last_profile_id.unwrap(), true)
+    runner::play_from_profile(app_settings.last_profile_id.unwrap(), true, play_mode)
 }
 
-pub fn pick_and_play_profile_on_name() -> Result<String, eyre::Report> {
-    pick_and_play_profile(db::get_profile_display_list(data::ProfileOrder::Name)?)
+pub fn pick_and_play_profile_on_name(play_mode: data::PlayMode) -> Result<String, eyre::Report> {
+    pick_and_play_profile(
+        db::get_profile_display_list(data::ProfileOrder::Name)?,
+        play_mode,
+    )
 }
 
-pub fn pick_and_play_profile_on_date_last_run() -> Result<String, eyre::Report> {
-    pick_and_play_profile(db::get_profile_display_list(
-        data::ProfileOrder::DateLastRun,
-    )?)
+pub fn pick_and_play_profile_on_date_last_run(
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
+    pick_and_play_profile(
+        db::get_profile_display_list(data::ProfileOrder::DateLastRun)?,
+        play_mode,
+    )
 }
 
-fn pick_and_play_profile(profile_list: Vec<ProfileDisplay>) -> Result<String, eyre::Report> {
+fn pick_and_play_profile(
+    profile_list: Vec<ProfileDisplay>,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
     if profile_list.is_empty() {
         return Ok(
             "Cannot Play Profile, there are no profiles found. Please create one".to_string(),
@@ -95,17 +106,149 @@ fn pick_and_play_profile(profile_list: Vec<ProfileDisplay>) -> Result<String, ey
         .prompt_skippable()?;
 
     match profile {
-        Some(profile) => runner::play_from_profile(profile.id, true),
+        Some(profile) => runner::play_from_profile(profile.id, true, play_mode),
         None => Ok("No profile selected".to_string()),
     }
 }
 
-pub fn cli_play_selected_profile(profile_name: &str) -> Result<String, eyre::Report> {
+pub fn pick_and_play_profile_at_level(play_mode: data::PlayMode) -> Result<String, eyre::Report> {
+    let profile_list = db::get_profile_display_list(data::ProfileOrder::Name)?;
+    if profile_list.is_empty() {
+        return Ok(
+            "Cannot Play Profile, there are no profiles found. Please create one".to_string(),
+        );
+    }
+    let profile = inquire::Select::new("Pick the Profile you want to Play:", profile_list)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .with_formatter(&|i| i.value.simple_display())
+        .prompt_skippable()?;
+    let Some(profile) = profile else {
+        return Ok("No profile selected".to_string());
+    };
+
+    let profile_levels = levels::get_profile_levels(profile.id)?;
+    if profile_levels.is_empty() {
+        return Ok(format!(
+            "No levels to warp to found in the IWAD or Maps of Profile '{}'",
+            profile.name
+        ));
+    }
+    let level = inquire::Select::new("Pick the Level to warp to:", profile_levels)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .prompt_skippable()?;
+
+    match level {
+        Some(level) => {
+            runner::play_from_profile_with_overrides(profile.id, None, Some(level.warp), play_mode)
+        }
+        None => Ok("No level selected".to_string()),
+    }
+}
+
+pub fn cli_play_selected_profile(
+    profile_name: &str,
+    net_role: Option<data::NetRole>,
+    warp: Option<String>,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
     let profile = db::get_profile_by_name(profile_name)?;
-    runner::play_from_profile(profile.id, true)
+
+    // Only warp to levels the Profile actually loads
+    let warp = match warp {
+        Some(warp) => {
+            let profile_levels = levels::get_profile_levels(profile.id)?;
+            if profile_levels.is_empty() {
+                return Err(eyre::eyre!(
+                    "Play aborted, no levels found in the IWAD or Maps of Profile '{}'",
+                    profile_name
+                ));
+            }
+            match levels::find_warp_level(&profile_levels, &warp) {
+                Some(warp_level) => Some(warp_level.warp.clone()),
+                None => {
+                    return Err(eyre::eyre!(
+                        "Play aborted, level '{}' not found in Profile '{}'. Levels are: {}",
+                        warp,
+                        profile_name,
+                        profile_levels
+                            .iter()
+                            .map(|warp_level| warp_level.lump_name.clone())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ))
+                }
+            }
+        }
+        None => None,
+    };
+
+    runner::play_from_profile_with_overrides(profile.id, net_role, warp, play_mode)
 }
 
-pub fn pick_and_play_map() -> Result<String, eyre::Report> {
+pub fn pick_and_play_netgame(
+    host: bool,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
+    let profile_list = db::get_profile_display_list(data::ProfileOrder::Name)?;
+    if profile_list.is_empty() {
+        return Ok(
+            "Cannot Play Netgame, there are no profiles found. Please create one".to_string(),
+        );
+    }
+    let profile = inquire::Select::new("Pick the Profile you want to Play:", profile_list)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .with_formatter(&|i| i.value.simple_display())
+        .prompt_skippable()?;
+    let Some(profile) = profile else {
+        return Ok("No profile selected".to_string());
+    };
+
+    let netgame_preset =
+        db::get_netgame_preset(profile.id)?.unwrap_or_else(|| data::NetgamePreset::new(profile.id));
+    let net_role = if host {
+        let players = inquire::CustomType::<u8>::new("Enter number of Players to host:")
+            .with_validator(|input: &u8| {
+                if (2..=constants::MAX_NETGAME_PLAYERS).contains(input) {
+                    Ok(Validation::Valid)
+                } else {
+                    Ok(Validation::Invalid(
+                        "Players is not within the range".into(),
+                    ))
+                }
+            })
+            .with_default(netgame_preset.players)
+            .with_help_message(&format!("Range is 2 to {}", constants::MAX_NETGAME_PLAYERS))
+            .prompt_skippable()?;
+        match players {
+            Some(players) => data::NetRole::Host { players },
+            None => return Ok("No changes made, Netgame not started".to_string()),
+        }
+    } else {
+        let address = inquire::Text::new("Enter address to join:")
+            .with_default(&netgame_preset.address.unwrap_or_default())
+            .prompt_skippable()?;
+        match address {
+            Some(address) => data::NetRole::Join { address },
+            None => return Ok("No changes made, Netgame not joined".to_string()),
+        }
+    };
+
+    runner::play_from_profile_with_overrides(profile.id, Some(net_role), None, play_mode)
+}
+
+pub fn pick_and_show_netgame_command() -> Result<String, eyre::Report> {
+    let host = inquire::Confirm::new("Host the Netgame? Pick 'No' to join one")
+        .with_default(true)
+        .prompt_skippable()?;
+    match host {
+        Some(host) => {
+            pick_and_play_netgame(host, data::PlayMode::DryRun(data::DryRunFormat::Shell))
+        }
+        None => Ok("No changes made, Netgame command not shown".to_string()),
+    }
+}
+
+pub fn pick_and_play_map(play_mode: data::PlayMode) -> Result<String, eyre::Report> {
     let engine_list = db::get_engines()?;
     if engine_list.is_empty() {
         return Ok("There are no Engines to select. Please run 'init'".to_string());
@@ -184,9 +327,11 @@ pub fn pick_and_play_map() -> Result<String, eyre::Report> {
     let additional_arguments =
         inquire::Text::new("Enter any additional arguments (optional):").prompt_skippable()?;
 
-    if inquire::Confirm::new("Autosave these options as a Profile?")
-        .with_default(false)
-        .prompt()?
+    // No need to offer an autosave when only showing the command
+    if play_mode == data::PlayMode::Launch
+        && inquire::Confirm::new("Autosave these options as a Profile?")
+            .with_default(false)
+            .prompt()?
     {
         let wad_name = match map_selection {
             None => paths::extract_file_name(&iwad_selection.path),
@@ -214,18 +359,28 @@ pub fn pick_and_play_map() -> Result<String, eyre::Report> {
             date_edited: Utc::now(),
             date_last_run: None,
             run_count: 0,
+            comp_level: None,
+            environment: None,
+            working_directory: None,
+            wrapper_command: None,
+            pre_launch_hook: None,
+            post_exit_hook: None,
         };
         let add_result = db::add_profile(profile)?;
         let add_profile_id: i32 = add_result.last_insert_rowid().try_into().unwrap();
 
-        runner::play_from_profile(add_profile_id, true)
+        runner::play_from_profile(add_profile_id, true, play_mode)
     } else {
         runner::play_from_engine_iwad_and_map(
             engine_selection.id,
             iwad_selection.id,
             data::map_ids_from_options(map_id, None, None, None, None),
-            save_game,
-            additional_arguments,
+            data::PlayOptions {
+                save_game,
+                additional_arguments,
+                ..Default::default()
+            },
+            play_mode,
         )
     }
 }
@@ -248,10 +403,10 @@ pub fn play_queue_top() -> Result<String, eyre::Report> {
     }
 
     let queue_top = queue_items.first().unwrap();
-    runner::play_from_profile(queue_top.profile_id, true)
+    runner::play_from_profile(queue_top.profile_id, true, data::PlayMode::Launch)
 }
 
-pub fn pick_and_play_queue() -> Result<String, eyre::Report> {
+pub fn pick_and_play_queue(play_mode: data::PlayMode) -> Result<String, eyre::Report> {
     let queue_display_list = db::get_queue_display_list()?;
     if queue_display_list.is_empty() {
         return Ok("There are no Queues to select".to_string());
@@ -280,5 +435,25 @@ pub fn pick_and_play_queue() -> Result<String, eyre::Report> {
             .with_page_size(tui::MENU_PAGE_SIZE)
             .prompt()?;
 
-    runner::play_from_profile(queue_selection.id, true)
+    runner::play_from_profile(queue_selection.id, true, play_mode)
+}
+
+pub fn view_last_launch_log() -> Result<String, eyre::Report> {
+    match launch_log::get_last_launch_log() {
+        Some(log_path) => {
+            let launch_log = launch_log::read_launch_log(&log_path)?;
+            if launch_log.trim().is_empty() {
+                return Ok(format!("No Engine output in Launch Log '{}'", log_path));
+            }
+            Ok(format!(
+                "Launch Log '{}'\n{}",
+                log_path.magenta(),
+                launch_log
+            ))
+        }
+        None => Ok(format!(
+            "No Launch Logs found in '{}'",
+            launch_log::get_launch_log_root()
+        )),
+    }
 }
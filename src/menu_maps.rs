@@ -1,4 +1,7 @@
-use crate::{data, db, doomworld_api, downloader, menu_common, runner, tui};
+use crate::{
+    data, db, doomworld_api, downloader, map_preview, menu_common, paths, runner,
+    terminal_graphics, title_screen, tui,
+};
 use eyre::Context;
 use owo_colors::OwoColorize;
 
@@ -71,6 +74,124 @@ pub fn view_from_pick_map() -> Result<String, eyre::Report> {
     view_map_readme_from_map_id(map_id)
 }
 
+pub fn cli_map_preview(
+    map: &str,
+    lump_name: &str,
+    format: data::PreviewFormat,
+) -> Result<String, eyre::Report> {
+    let map = find_map(map)?;
+
+    let preview_path = map_preview::get_map_preview(&map, lump_name, &format)?;
+    Ok(format!(
+        "Preview of {} in '{}' saved to '{}'",
+        lump_name.to_uppercase(),
+        map.title,
+        preview_path
+    ))
+}
+
+pub fn view_map_preview() -> Result<String, eyre::Report> {
+    let map_id = menu_common::get_map_id_from_pick_map(
+        "Pick the Map to preview:",
+        "Canceled viewing Map Preview",
+    )?;
+    let map = db::get_map_by_id(map_id)?;
+
+    let levels = db::get_map_contents(map_id)?.levels;
+    if levels.is_empty() {
+        return Ok(format!("Map '{}' has no levels to preview", map.title));
+    }
+    let level = inquire::Select::new("Pick the level to preview:", levels)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .prompt_skippable()?
+        .ok_or_else(|| eyre::eyre!("Canceled viewing Map Preview"))?;
+
+    let preview_path =
+        map_preview::get_map_preview(&map, &level.lump_name, &data::PreviewFormat::Svg)?;
+    runner::open_file(
+        &preview_path,
+        &format!("the preview of {} in '{}'", level.lump_name, map.title),
+    )
+}
+
+// Matches the full path, or the file name as add-profile does
+fn find_map(map: &str) -> Result<data::Map, eyre::Report> {
+    let map_list = db::get_maps()?;
+    map_list
+        .into_iter()
+        .find(|x| {
+            x.path.eq_ignore_ascii_case(&paths::resolve_path(map))
+                || paths::extract_file_name(&x.path).eq_ignore_ascii_case(map)
+        })
+        .ok_or_else(|| eyre::eyre!("Map not found - '{}'", map))
+}
+
+pub fn view_title_screen() -> Result<String, eyre::Report> {
+    let map_id = menu_common::get_map_id_from_pick_map(
+        "Pick the Map to view the Title Screen of:",
+        "Canceled viewing Title Screen",
+    )?;
+    let map = db::get_map_by_id(map_id)?;
+
+    let lumps = title_screen::get_title_screen_lumps(&map.path);
+    let lump = match lumps.len() {
+        0 => {
+            return Ok(format!(
+                "Map '{}' has no Title Screen of its own",
+                map.title
+            ))
+        }
+        1 => lumps[0].clone(),
+        _ => inquire::Select::new("Pick the graphic to view:", lumps)
+            .with_page_size(tui::MENU_PAGE_SIZE)
+            .prompt_skippable()?
+            .ok_or_else(|| eyre::eyre!("Canceled viewing Title Screen"))?,
+    };
+
+    let result = show_title_screen(&map, &lump)?;
+    // The menu clears the screen when it returns
+    if terminal_graphics::get_terminal_graphics().is_some() {
+        inquire::Text::new("Press any key to continue...").prompt_skippable()?;
+    }
+    Ok(result)
+}
+
+pub fn cli_title_screen(
+    map: &str,
+    lump: Option<data::TitleScreenLump>,
+) -> Result<String, eyre::Report> {
+    let map = find_map(map)?;
+
+    let lump = match lump {
+        Some(lump) => lump,
+        None => title_screen::get_title_screen_lumps(&map.path)
+            .into_iter()
+            .next()
+            .ok_or_else(|| eyre::eyre!("Map '{}' has no Title Screen of its own", map.title))?,
+    };
+    show_title_screen(&map, &lump)
+}
+
+// Terminals that can draw images show it straight away, it is saved next to the Map either way
+fn show_title_screen(
+    map: &data::Map,
+    lump: &data::TitleScreenLump,
+) -> Result<String, eyre::Report> {
+    let (title_screen_path, image) = title_screen::write_title_screen(map, lump)?;
+
+    if let Some(terminal_graphics) = terminal_graphics::get_terminal_graphics() {
+        let png_bytes = std::fs::read(&title_screen_path)
+            .wrap_err(format!("Failed to read '{}'", title_screen_path))?;
+        terminal_graphics::draw_image(&terminal_graphics, &png_bytes, &image)?;
+    }
+    Ok(format!(
+        "{} of '{}' saved to '{}'",
+        lump,
+        map.title,
+        title_screen_path.magenta()
+    ))
+}
+
 fn search_dooomworld_and_download(
     nice_name: &str,
     search_type: &str,
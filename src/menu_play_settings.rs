@@ -1,46 +1,15 @@
-use std::str::FromStr;
-
 use eyre::Context;
 use inquire::validator::Validation;
 use tabled::settings::{object::Rows, Modify, Rotate, Style, Width};
 
-use crate::{constants, data, db, paths, tui};
+use crate::{data, db, levels, menu_common, paths, tui};
 
 pub fn update_comp_level() -> Result<String, eyre::Report> {
-    let selections = vec![
-        constants::MENU_NOT_SET.to_string(),
-        data::CompLevel::DoomV12.to_string(),
-        data::CompLevel::DoomV1666.to_string(),
-        data::CompLevel::DoomV19.to_string(),
-        data::CompLevel::UltimateDoom.to_string(),
-        data::CompLevel::FinalDoom.to_string(),
-        data::CompLevel::DosDoom.to_string(),
-        data::CompLevel::TasDoom.to_string(),
-        data::CompLevel::Boom.to_string(),
-        data::CompLevel::BoomV201.to_string(),
-        data::CompLevel::BoomV202.to_string(),
-        data::CompLevel::LxDoom.to_string(),
-        data::CompLevel::Mbf.to_string(),
-        data::CompLevel::PrBoomPlus.to_string(),
-        data::CompLevel::Mbf21.to_string(),
-    ];
-
     let mut play_settings = db::get_play_settings()?;
-    let starting_cursor = match play_settings.comp_level {
-        Some(ref c) => selections.iter().position(|x| x == &c.to_string()).unwrap(),
-        None => 0,
-    };
-
-    let comp_level = inquire::Select::new("Select a Compatibility Level:", selections)
-        .with_starting_cursor(starting_cursor)
-        .with_page_size(tui::MENU_PAGE_SIZE)
-        .prompt()?;
-
-    if comp_level == constants::MENU_NOT_SET {
-        play_settings.comp_level = None;
-    } else {
-        play_settings.comp_level = Some(data::CompLevel::from_str(&comp_level).unwrap());
-    }
+    play_settings.comp_level = menu_common::get_comp_level_selection(
+        "Select a Compatibility Level:",
+        &play_settings.comp_level,
+    )?;
     db::save_play_settings(play_settings.clone())?;
 
     Ok(format!(
@@ -185,6 +154,32 @@ pub fn cli_set_respawn_monsters(respawn_monsters: bool) -> Result<String, eyre::
 
 pub fn update_warp_to_level() -> Result<String, eyre::Error> {
     let mut play_settings = db::get_play_settings()?;
+
+    // Offer the levels in the Default Profile, falling back to typing a value
+    let profile_levels = match db::get_app_settings()?.default_profile_id {
+        Some(profile_id) => levels::get_profile_levels(profile_id)?,
+        None => Vec::new(),
+    };
+    if !profile_levels.is_empty() {
+        let starting_cursor = profile_levels
+            .iter()
+            .position(|level| Some(&level.warp) == play_settings.warp.as_ref())
+            .unwrap_or(0);
+        let level = inquire::Select::new("Pick the Level to warp to:", profile_levels)
+            .with_page_size(tui::MENU_PAGE_SIZE)
+            .with_starting_cursor(starting_cursor)
+            .with_help_message("Levels in the Default Profile. Press Esc to enter a Warp value")
+            .prompt_skippable()?;
+        if let Some(level) = level {
+            play_settings.warp = Some(level.warp.clone());
+            db::save_play_settings(play_settings)?;
+            return Ok(format!(
+                "Successfully updated Warp to '{}' for '{}'",
+                level.warp, level
+            ));
+        }
+    }
+
     let warp = inquire::Text::new("Enter Warp value:")
         .with_default(&play_settings.warp.unwrap_or("".to_string()))
         .with_help_message(&format!(
@@ -466,6 +461,31 @@ pub fn cli_set_windowed(windowed: bool) -> Result<String, eyre::Error> {
     ))
 }
 
+pub fn update_record_demo() -> Result<String, eyre::Error> {
+    let mut play_settings = db::get_play_settings()?;
+    play_settings.record_demo = inquire::Confirm::new("Enable Record Demo?")
+        .with_default(play_settings.record_demo)
+        .with_help_message("Demos are named after the Profile, Warp, Skill and time recorded")
+        .prompt()?;
+    db::save_play_settings(play_settings.clone())?;
+
+    Ok(format!(
+        "Successfully updated Record Demo as '{}'",
+        &play_settings.record_demo
+    ))
+}
+
+pub fn cli_set_record_demo(record_demo: bool) -> Result<String, eyre::Error> {
+    let mut play_settings = db::get_play_settings()?;
+    play_settings.record_demo = record_demo;
+    db::save_play_settings(play_settings.clone())?;
+
+    Ok(format!(
+        "Successfully updated Record Demo as '{}'",
+        &play_settings.record_demo
+    ))
+}
+
 pub fn update_additional_arguments() -> Result<String, eyre::Error> {
     let mut play_settings = db::get_play_settings()?;
     play_settings.additional_arguments = inquire::Text::new("Enter any Additional Arguments:")
@@ -7,7 +7,10 @@ use tabled::{
     settings::{object::Rows, Modify, Style, Width},
 };
 
-use crate::{constants, data, db, menu_app_settings, menu_common, menu_queues, paths, tui};
+use crate::{
+    complevel, constants, data, db, doom_data, files, menu_app_settings, menu_common, menu_queues,
+    paths, required_iwad, runner, tui,
+};
 
 pub fn add_profile(
     map_id: Option<i32>,
@@ -31,10 +34,6 @@ pub fn add_profile(
         Some(ref i) => engines.iter().position(|x| x.id == *i).unwrap(),
         None => 0,
     };
-    let iwad_starting_cursor = match app_settings.default_iwad_id {
-        Some(ref i) => iwads.iter().position(|x| x.id == *i).unwrap(),
-        None => 0,
-    };
 
     let profile_name = inquire::Text::new("Enter a name for your Profile:")
         .with_validator(|input: &str| {
@@ -64,12 +63,6 @@ pub fn add_profile(
         .with_formatter(&|i| i.value.simple_display())
         .prompt()?;
 
-    let iwad_selection = inquire::Select::new("Pick the IWAD you want to use:", iwads)
-        .with_starting_cursor(iwad_starting_cursor)
-        .with_page_size(tui::MENU_PAGE_SIZE)
-        .with_formatter(&|i| i.value.simple_display())
-        .prompt()?;
-
     let (map_id, map_id2, map_id3, map_id4, map_id5) = match map_id {
         Some(map_id) => (Some(map_id), None, None, None, None),
         None => {
@@ -84,6 +77,62 @@ pub fn add_profile(
         }
     };
 
+    // Start on an IWAD the selected Maps were made for, or the Default IWAD
+    let required_iwad = get_required_iwad_type(&[map_id, map_id2, map_id3, map_id4, map_id5])?;
+    let iwad_starting_cursor = required_iwad
+        .as_ref()
+        .and_then(|required| get_suggested_iwad_position(&iwads, required))
+        .or_else(|| {
+            app_settings
+                .default_iwad_id
+                .and_then(|default_iwad_id| iwads.iter().position(|x| x.id == default_iwad_id))
+        })
+        .unwrap_or(0);
+    let iwad_prompt = match &required_iwad {
+        Some(required) => format!(
+            "Pick the IWAD you want to use (the Maps are for {}):",
+            required
+        ),
+        None => "Pick the IWAD you want to use:".to_string(),
+    };
+    let iwad_selection = loop {
+        let iwad = inquire::Select::new(&iwad_prompt, iwads.clone())
+            .with_starting_cursor(iwad_starting_cursor)
+            .with_page_size(tui::MENU_PAGE_SIZE)
+            .with_formatter(&|i| i.value.simple_display())
+            .prompt()?;
+        match &required_iwad {
+            Some(required) if !required_iwad::can_play(&iwad, required) => {
+                log::warn!(
+                    "{}",
+                    get_incompatible_iwad_warning(&iwad, required).yellow()
+                );
+                if inquire::Confirm::new("Use this IWAD anyway?")
+                    .with_default(false)
+                    .prompt()?
+                {
+                    break iwad;
+                }
+            }
+            _ => break iwad,
+        }
+    };
+
+    // Start on the Compatibility Level recommended for the selected Maps
+    let recommended_comp_level = get_recommended_comp_level(
+        &[map_id, map_id2, map_id3, map_id4, map_id5],
+        data::CompLevelConfidence::Low,
+    )?;
+    let comp_level_prompt = match &recommended_comp_level {
+        Some(comp_level) => format!(
+            "Pick the Compatibility Level for this Profile (recommended: {}):",
+            comp_level
+        ),
+        None => "Pick the Compatibility Level for this Profile:".to_string(),
+    };
+    let comp_level =
+        menu_common::get_comp_level_selection(&comp_level_prompt, &recommended_comp_level)?;
+
     let save_game =
         inquire::Text::new("Enter save game file name you want to automatically load (optional):")
             .with_help_message("For example with GZDoom 'save26.zds'")
@@ -92,6 +141,8 @@ pub fn add_profile(
     let additional_arguments =
         inquire::Text::new("Enter any additional arguments (optional):").prompt_skippable()?;
 
+    let launch_environment = get_launch_environment(&data::LaunchEnvironment::default())?;
+
     let profile = data::Profile {
         id: 0,
         name: profile_name.clone(),
@@ -108,6 +159,12 @@ pub fn add_profile(
         date_edited: Utc::now(),
         date_last_run: None,
         run_count: 0,
+        comp_level,
+        environment: launch_environment.environment,
+        working_directory: launch_environment.working_directory,
+        wrapper_command: launch_environment.wrapper_command,
+        pre_launch_hook: launch_environment.pre_launch_hook,
+        post_exit_hook: launch_environment.post_exit_hook,
     };
     let add_result = db::add_profile(profile.clone())?;
     let add_profile_id: i32 = add_result.last_insert_rowid().try_into().unwrap();
@@ -145,8 +202,7 @@ pub fn cli_add_profile(
     engine: &str,
     iwad: &str,
     maps_in: Option<Vec<String>>,
-    save_game: Option<String>,
-    args: Option<Vec<String>>,
+    profile_options: data::ProfileOptions,
 ) -> Result<String, eyre::Report> {
     let engines = db::get_engines()?;
     if engines.is_empty() {
@@ -245,7 +301,21 @@ pub fn cli_add_profile(
         }
     };
 
-    let additional_arguments = args.map(|args_unwrapped| args_unwrapped.join(" "));
+    if let Some(required) = get_required_iwad_type(&map_ids)? {
+        if !required_iwad::can_play(iwad_selection, &required) {
+            log::warn!(
+                "{}",
+                get_incompatible_iwad_warning(iwad_selection, &required).yellow()
+            );
+        }
+    }
+
+    let launch_environment = profile_options.launch_environment;
+    if let Some(environment) = &launch_environment.environment {
+        if let Err(e) = runner::parse_environment(environment) {
+            return Ok(format!("Cannot add Profile '{}'. {}", name, e));
+        }
+    }
 
     let profile = data::Profile {
         id: 0,
@@ -257,12 +327,22 @@ pub fn cli_add_profile(
         map_id3: map_ids[2],
         map_id4: map_ids[3],
         map_id5: map_ids[4],
-        save_game,
-        additional_arguments,
+        save_game: profile_options.save_game,
+        additional_arguments: profile_options.additional_arguments,
         date_created: Utc::now(),
         date_edited: Utc::now(),
         date_last_run: None,
         run_count: 0,
+        comp_level: match profile_options.comp_level {
+            Some(data::CompLevel::NotSet) => None,
+            Some(comp_level) => Some(comp_level),
+            None => get_recommended_comp_level(&map_ids, data::CompLevelConfidence::Medium)?,
+        },
+        environment: launch_environment.environment,
+        working_directory: launch_environment.working_directory,
+        wrapper_command: launch_environment.wrapper_command,
+        pre_launch_hook: launch_environment.pre_launch_hook,
+        post_exit_hook: launch_environment.post_exit_hook,
     };
     db::add_profile(profile.clone())?;
 
@@ -272,6 +352,114 @@ pub fn cli_add_profile(
     ))
 }
 
+fn get_recommended_comp_level(
+    map_ids: &[Option<i32>],
+    min_confidence: data::CompLevelConfidence,
+) -> Result<Option<data::CompLevel>, eyre::Report> {
+    let maps = map_ids
+        .iter()
+        .flatten()
+        .map(|&map_id| db::get_map_by_id(map_id))
+        .collect::<Result<Vec<data::Map>, eyre::Report>>()?;
+    Ok(complevel::get_maps_comp_level(&maps, min_confidence))
+}
+
+// The first Map is the one being played, the others are usually resources for it
+fn get_required_iwad_type(
+    map_ids: &[Option<i32>],
+) -> Result<Option<doom_data::InternalWadType>, eyre::Report> {
+    for &map_id in map_ids.iter().flatten() {
+        let map = db::get_map_by_id(map_id)?;
+        let internal_wad_type = map.iwad().and_then(|required_iwad| {
+            files::get_internal_wad_type_from_file_name(
+                doom_data::get_internal_wad_list(),
+                required_iwad,
+            )
+            .ok()
+        });
+        if internal_wad_type.is_some() {
+            return Ok(internal_wad_type);
+        }
+    }
+    Ok(None)
+}
+
+// The IWAD the Maps were made for, then any IWAD that can play them
+fn get_suggested_iwad_position(
+    iwads: &[data::Iwad],
+    required: &doom_data::InternalWadType,
+) -> Option<usize> {
+    iwads
+        .iter()
+        .position(|iwad| iwad.internal_wad_type == *required)
+        .or_else(|| {
+            iwads
+                .iter()
+                .position(|iwad| required_iwad::can_play(iwad, required))
+        })
+}
+
+fn get_incompatible_iwad_warning(
+    iwad: &data::Iwad,
+    required: &doom_data::InternalWadType,
+) -> String {
+    format!(
+        "The Maps were made for {}, they may not work with IWAD '{}'",
+        required,
+        iwad.get_type_name()
+    )
+}
+
+fn get_launch_environment(
+    defaults: &data::LaunchEnvironment,
+) -> Result<data::LaunchEnvironment, eyre::Report> {
+    let environment = inquire::Text::new("Enter any environment variables (optional):")
+        .with_validator(|input: &str| match runner::parse_environment(input) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+        })
+        .with_help_message("For example 'DOOMWADDIR=~/doom SDL_VIDEODRIVER=wayland'")
+        .with_default(&defaults.environment.clone().unwrap_or_default())
+        .prompt_skippable()?;
+
+    let working_directory = inquire::Text::new("Enter the working directory (optional):")
+        .with_validator(|input: &str| {
+            if input.trim().is_empty() || paths::folder_exists(&paths::resolve_path(input)) {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid("Folder does not exist".into()))
+            }
+        })
+        .with_help_message("The Engine is started in this folder")
+        .with_default(&defaults.working_directory.clone().unwrap_or_default())
+        .prompt_skippable()?;
+
+    let wrapper_command = inquire::Text::new("Enter a wrapper command (optional):")
+        .with_help_message(
+            "Runs the Engine through it, for example 'gamescope -f --' or 'mangohud'",
+        )
+        .with_default(&defaults.wrapper_command.clone().unwrap_or_default())
+        .prompt_skippable()?;
+
+    let pre_launch_hook = inquire::Text::new("Enter a pre-launch hook (optional):")
+        .with_help_message("Shell command run before the Engine starts")
+        .with_default(&defaults.pre_launch_hook.clone().unwrap_or_default())
+        .prompt_skippable()?;
+
+    let post_exit_hook = inquire::Text::new("Enter a post-exit hook (optional):")
+        .with_help_message("Shell command run after the Engine exits, dcli waits for the Engine")
+        .with_default(&defaults.post_exit_hook.clone().unwrap_or_default())
+        .prompt_skippable()?;
+
+    Ok(data::LaunchEnvironment {
+        environment: environment.filter(|value| !value.trim().is_empty()),
+        working_directory: working_directory.filter(|value| !value.trim().is_empty()),
+        wrapper_command: wrapper_command.filter(|value| !value.trim().is_empty()),
+        pre_launch_hook: pre_launch_hook.filter(|value| !value.trim().is_empty()),
+        post_exit_hook: post_exit_hook.filter(|value| !value.trim().is_empty()),
+    })
+}
+
 pub fn set_profile_as_default(
     profile_id: i32,
     profile_name: &str,
@@ -381,6 +569,11 @@ pub fn edit_profile() -> Result<String, eyre::Report> {
         .with_formatter(&|i| i.value.simple_display())
         .prompt()?;
 
+    let comp_level = menu_common::get_comp_level_selection(
+        "Pick the Compatibility Level for this Profile:",
+        &profile_display.comp_level,
+    )?;
+
     let map_selection = menu_common::get_map_selection(maps, default_maps)?;
     let map_id = Some(map_selection[0].id).filter(|&id| id > 0);
     let map_id2 = Some(map_selection[1].id).filter(|&id| id > 0);
@@ -398,6 +591,14 @@ pub fn edit_profile() -> Result<String, eyre::Report> {
         .with_default(&profile_display.additional_arguments)
         .prompt_skippable()?;
 
+    let launch_environment = get_launch_environment(&data::LaunchEnvironment {
+        environment: Some(profile_display.environment.clone()),
+        working_directory: Some(profile_display.working_directory.clone()),
+        wrapper_command: Some(profile_display.wrapper_command.clone()),
+        pre_launch_hook: Some(profile_display.pre_launch_hook.clone()),
+        post_exit_hook: Some(profile_display.post_exit_hook.clone()),
+    })?;
+
     let profile = data::Profile {
         id: profile_display.id,
         name: profile_name.clone(),
@@ -414,6 +615,12 @@ pub fn edit_profile() -> Result<String, eyre::Report> {
         date_edited: Utc::now(),
         date_last_run: profile_display.date_last_run,
         run_count: profile_display.run_count,
+        comp_level,
+        environment: launch_environment.environment,
+        working_directory: launch_environment.working_directory,
+        wrapper_command: launch_environment.wrapper_command,
+        pre_launch_hook: launch_environment.pre_launch_hook,
+        post_exit_hook: launch_environment.post_exit_hook,
     };
     db::update_profile(profile)?;
 
@@ -533,6 +740,160 @@ pub fn change_save_game_on_profile() -> Result<String, eyre::Report> {
     }
 }
 
+pub fn edit_netgame_preset() -> Result<String, eyre::Report> {
+    let display_profile_list = db::get_profile_display_list(data::ProfileOrder::Name)?;
+    if display_profile_list.is_empty() {
+        return Ok("There are no Profiles to edit the Netgame Preset on".to_string());
+    }
+
+    let profile_display = inquire::Select::new(
+        "Pick the Profile to edit the Netgame Preset on:",
+        display_profile_list,
+    )
+    .with_page_size(tui::MENU_PAGE_SIZE)
+    .with_formatter(&|i| i.value.simple_display())
+    .prompt()?;
+
+    let mut netgame_preset = db::get_netgame_preset(profile_display.id)?
+        .unwrap_or_else(|| data::NetgamePreset::new(profile_display.id));
+
+    let game_modes = vec![
+        data::NetGameMode::Coop,
+        data::NetGameMode::Deathmatch,
+        data::NetGameMode::AltDeath,
+    ];
+    let starting_cursor = game_modes
+        .iter()
+        .position(|x| *x == netgame_preset.game_mode)
+        .unwrap_or(0);
+    netgame_preset.game_mode = inquire::Select::new("Pick the Game Mode:", game_modes)
+        .with_starting_cursor(starting_cursor)
+        .prompt()?;
+
+    netgame_preset.players = inquire::CustomType::<u8>::new("Enter number of Players to host:")
+        .with_validator(|input: &u8| {
+            if (2..=constants::MAX_NETGAME_PLAYERS).contains(input) {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    "Players is not within the range".into(),
+                ))
+            }
+        })
+        .with_default(netgame_preset.players)
+        .with_help_message(&format!("Range is 2 to {}", constants::MAX_NETGAME_PLAYERS))
+        .prompt()?;
+
+    netgame_preset.address = inquire::Text::new("Enter address to join (optional):")
+        .with_help_message("For example '192.168.0.10'")
+        .with_default(&netgame_preset.address.clone().unwrap_or_default())
+        .prompt_skippable()?
+        .filter(|address| !address.trim().is_empty());
+
+    netgame_preset.port = inquire::CustomType::<u16>::new("Enter Network Port (optional):")
+        .with_help_message("Leave blank to use the Engine's default")
+        .with_default(netgame_preset.port.unwrap_or(5029))
+        .prompt_skippable()?;
+
+    netgame_preset.time_limit =
+        inquire::CustomType::<u32>::new("Enter Time Limit in minutes (optional):")
+            .with_help_message("Press ESC to have no Time Limit")
+            .with_default(netgame_preset.time_limit.unwrap_or(10))
+            .prompt_skippable()?;
+
+    db::save_netgame_preset(&netgame_preset)?;
+    Ok(format!(
+        "Successfully updated Netgame Preset on Profile '{}'",
+        profile_display.name
+    ))
+}
+
+pub fn cli_set_netgame_preset(
+    profile_name: &str,
+    game_mode: Option<data::NetGameMode>,
+    players: Option<u8>,
+    address: Option<String>,
+    port: Option<u16>,
+    time_limit: Option<u32>,
+) -> Result<String, eyre::Report> {
+    let profile = db::get_profile_by_name(profile_name)?;
+    let mut netgame_preset =
+        db::get_netgame_preset(profile.id)?.unwrap_or_else(|| data::NetgamePreset::new(profile.id));
+
+    if let Some(players) = players {
+        if !(2..=constants::MAX_NETGAME_PLAYERS).contains(&players) {
+            return Err(eyre::eyre!(
+                "Players must be between 2 and {}, not {}",
+                constants::MAX_NETGAME_PLAYERS,
+                players
+            ));
+        }
+        netgame_preset.players = players;
+    }
+    if let Some(game_mode) = game_mode {
+        netgame_preset.game_mode = game_mode;
+    }
+    if address.is_some() {
+        netgame_preset.address = address.filter(|address| !address.trim().is_empty());
+    }
+    if port.is_some() {
+        netgame_preset.port = port;
+    }
+    if time_limit.is_some() {
+        netgame_preset.time_limit = time_limit;
+    }
+
+    db::save_netgame_preset(&netgame_preset)?;
+    Ok(format!(
+        "Successfully updated Netgame Preset on Profile '{}' - {}, {} players",
+        profile.name, netgame_preset.game_mode, netgame_preset.players
+    ))
+}
+
+pub fn check_profile() -> Result<String, eyre::Report> {
+    let profile_list = db::get_profile_display_list(data::ProfileOrder::Name)?;
+    if profile_list.is_empty() {
+        return Ok("There are no Profiles to check".to_string());
+    }
+
+    let profile_selection = inquire::Select::new("Pick the Profile to Check:", profile_list)
+        .with_page_size(tui::MENU_PAGE_SIZE)
+        .with_formatter(&|i| i.value.simple_display())
+        .prompt_skippable()?;
+
+    match profile_selection {
+        Some(profile) => check_profile_core(profile.id, &profile.name),
+        None => Ok("No Profile selected to check".to_string()),
+    }
+}
+
+pub fn cli_check_profile(profile_name: &str) -> Result<String, eyre::Report> {
+    let profile = db::get_profile_by_name(profile_name)?;
+    check_profile_core(profile.id, &profile.name)
+}
+
+fn check_profile_core(profile_id: i32, profile_name: &str) -> Result<String, eyre::Report> {
+    let check_results = runner::check_profile(profile_id)?;
+    if check_results.is_empty() {
+        return Ok(format!(
+            "Successfully checked Profile '{}', no problems found",
+            profile_name
+        ));
+    }
+
+    let mut lines = vec![format!(
+        "Profile '{}' has {} problem(s):",
+        profile_name,
+        check_results.len()
+    )];
+    lines.extend(
+        check_results
+            .iter()
+            .map(|check_result| check_result.display()),
+    );
+    Ok(lines.join("\n"))
+}
+
 fn delete_profile_core(
     profile_id: i32,
     profile_name: &str,
@@ -649,6 +1010,7 @@ pub fn list_profiles(list_type: data::ListType) -> Result<String, eyre::Report>
                 "Map Files",
                 "Save Game",
                 "Additional Args",
+                "Time Played",
                 //"Run Count",
                 //"Date Last Run",
             ]);
@@ -661,6 +1023,7 @@ pub fn list_profiles(list_type: data::ListType) -> Result<String, eyre::Report>
                     data::display_combined_tabled_map_strings(&profile.map_files),
                     profile.save_game,
                     profile.additional_arguments,
+                    data::display_time_played(&profile.time_played),
                     //profile.run_count.to_string(),
                     //data::display_option_utc_datetime_to_local(&profile.date_last_run),
                 ]);
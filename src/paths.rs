@@ -26,7 +26,7 @@ pub fn get_current_exe() -> String {
 }
 
 pub fn resolve_path(folder_path: &str) -> String {
-    if env::consts::OS == constants::OS_MACOS && folder_path.starts_with("~/") {
+    if env::consts::OS != constants::OS_WINDOWS && folder_path.starts_with("~/") {
         if let Some(home) = dirs::home_dir() {
             // Replace ~ with the home directory
             return folder_path.replacen('~', &home.to_string_lossy(), 1);
@@ -40,6 +40,14 @@ pub fn resolve_path_opt(folder_path: Option<String>) -> Option<String> {
     folder_path.map(|path| resolve_path(&path))
 }
 
+pub fn find_on_path(program: &str) -> Option<String> {
+    let path_var = env::var_os("PATH")?;
+    env::split_paths(&path_var)
+        .map(|folder| folder.join(program))
+        .find(|candidate| candidate.is_file())
+        .map(|candidate| candidate.display().to_string())
+}
+
 pub fn folder_exists(folder_path: &str) -> bool {
     let path = Path::new(folder_path);
     path.exists() && path.is_dir()
@@ -108,6 +116,25 @@ pub fn get_temp_dir() -> String {
     temp_dir.display().to_string()
 }
 
+pub fn get_app_data_dir() -> String {
+    match dirs::data_local_dir() {
+        Some(data_dir) => data_dir.join(constants::APP_NAME).display().to_string(),
+        None => get_full_path(&get_temp_dir(), constants::APP_NAME),
+    }
+}
+
+pub fn get_safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
 pub fn get_base_name(file_path: &str) -> String {
     Path::new(file_path)
         .file_stem()
@@ -0,0 +1,394 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+};
+
+use eyre::Context;
+
+use crate::{data, paths};
+
+// Reads the VS_VERSIONINFO resource straight out of a Windows executable, so version
+// details can be found without PowerShell, and on any OS. The layout is described at
+// https://learn.microsoft.com/en-us/windows/win32/menurc/vs-versioninfo
+
+const DOS_SIGNATURE: &[u8] = b"MZ";
+const PE_SIGNATURE: &[u8] = b"PE\0\0";
+const PE_OFFSET_POSITION: usize = 0x3c;
+const COFF_HEADER_SIZE: usize = 20;
+const SECTION_HEADER_SIZE: usize = 40;
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+const RESOURCE_DIRECTORY_INDEX: usize = 2;
+const RESOURCE_TYPE_VERSION: u32 = 16;
+const RESOURCE_SUBDIRECTORY_FLAG: u32 = 0x8000_0000;
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xfeef_04bd;
+const VERSION_INFO_KEY: &str = "VS_VERSION_INFO";
+const STRING_FILE_INFO_KEY: &str = "StringFileInfo";
+const FILE_DESCRIPTION_KEY: &str = "FileDescription";
+const PRODUCT_NAME_KEY: &str = "ProductName";
+
+#[derive(Debug, PartialEq)]
+pub struct PeVersionInfo {
+    pub major: u32,
+    pub minor: u32,
+    pub build: u32,
+    pub revision: u32,
+    pub file_description: Option<String>,
+    pub product_name: Option<String>,
+}
+
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_data_size: u32,
+    raw_data_pointer: u32,
+}
+
+// A node in the version resource. Each has a key, an optional value and child nodes
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+pub fn is_pe_file(full_path: &str) -> bool {
+    let mut signature = [0; 2];
+    File::open(full_path)
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .is_ok_and(|_| signature == DOS_SIGNATURE)
+}
+
+pub fn get_pe_file_version(full_path: &str) -> Result<data::FileVersion, eyre::Report> {
+    let bytes = fs::read(full_path).wrap_err(format!("Failed to read '{}'", full_path))?;
+    let version_info = read_pe_version_info(&bytes).wrap_err(format!(
+        "Failed to read version resource from '{}'",
+        full_path
+    ))?;
+
+    let app_name = version_info
+        .file_description
+        .or(version_info.product_name)
+        .unwrap_or_else(|| paths::extract_file_name(full_path));
+
+    Ok(data::FileVersion {
+        app_name,
+        prefix: "".to_string(),
+        major: version_info.major,
+        minor: version_info.minor,
+        build: version_info.build,
+        revision: Some(version_info.revision),
+        suffix: "".to_string(),
+    })
+}
+
+pub fn read_pe_version_info(bytes: &[u8]) -> Result<PeVersionInfo, eyre::Report> {
+    if !bytes.starts_with(DOS_SIGNATURE) {
+        return Err(eyre::eyre!(
+            "Not a Windows executable, missing MZ signature"
+        ));
+    }
+    let pe_offset = read_u32(bytes, PE_OFFSET_POSITION)? as usize;
+    if bytes.get(pe_offset..pe_offset + PE_SIGNATURE.len()) != Some(PE_SIGNATURE) {
+        return Err(eyre::eyre!(
+            "Not a Windows executable, missing PE signature"
+        ));
+    }
+
+    let coff_offset = pe_offset + PE_SIGNATURE.len();
+    let section_count = read_u16(bytes, coff_offset + 2)? as usize;
+    let optional_header_size = read_u16(bytes, coff_offset + 16)? as usize;
+    let optional_offset = coff_offset + COFF_HEADER_SIZE;
+
+    // Data directories move along by 16 bytes in 64-bit executables
+    let data_directories_offset = match read_u16(bytes, optional_offset)? {
+        PE32_MAGIC => optional_offset + 96,
+        PE32_PLUS_MAGIC => optional_offset + 112,
+        magic => return Err(eyre::eyre!("Unknown optional header magic '{:#x}'", magic)),
+    };
+    let data_directory_count = read_u32(bytes, data_directories_offset - 4)? as usize;
+    if data_directory_count <= RESOURCE_DIRECTORY_INDEX {
+        return Err(eyre::eyre!("Executable has no resources"));
+    }
+    let resource_rva = read_u32(
+        bytes,
+        data_directories_offset + RESOURCE_DIRECTORY_INDEX * 8,
+    )?;
+    if resource_rva == 0 {
+        return Err(eyre::eyre!("Executable has no resources"));
+    }
+
+    let sections = read_sections(bytes, optional_offset + optional_header_size, section_count)?;
+    let resource_offset = rva_to_offset(&sections, resource_rva)?;
+
+    // Resources are a tree of type, then name, then language. Any name or language will do
+    let version_directory = find_resource_entry(
+        bytes,
+        resource_offset,
+        resource_offset,
+        Some(RESOURCE_TYPE_VERSION),
+    )?;
+    let name_directory = find_resource_entry(bytes, resource_offset, version_directory, None)?;
+    let data_entry = find_resource_entry(bytes, resource_offset, name_directory, None)?;
+
+    let version_rva = read_u32(bytes, data_entry)?;
+    let version_size = read_u32(bytes, data_entry + 4)? as usize;
+    let version_offset = rva_to_offset(&sections, version_rva)?;
+    let version_bytes = bytes
+        .get(version_offset..version_offset + version_size)
+        .ok_or_else(|| eyre::eyre!("Version resource is outside of the file"))?;
+
+    read_version_resource(version_bytes)
+}
+
+fn read_sections(
+    bytes: &[u8],
+    section_table_offset: usize,
+    section_count: usize,
+) -> Result<Vec<Section>, eyre::Report> {
+    (0..section_count)
+        .map(|index| {
+            let offset = section_table_offset + index * SECTION_HEADER_SIZE;
+            Ok(Section {
+                virtual_size: read_u32(bytes, offset + 8)?,
+                virtual_address: read_u32(bytes, offset + 12)?,
+                raw_data_size: read_u32(bytes, offset + 16)?,
+                raw_data_pointer: read_u32(bytes, offset + 20)?,
+            })
+        })
+        .collect()
+}
+
+fn rva_to_offset(sections: &[Section], rva: u32) -> Result<usize, eyre::Report> {
+    sections
+        .iter()
+        .find(|section| {
+            let size = section.virtual_size.max(section.raw_data_size);
+            rva >= section.virtual_address
+                && section
+                    .virtual_address
+                    .checked_add(size)
+                    .is_some_and(|section_end| rva < section_end)
+        })
+        .and_then(|section| (rva - section.virtual_address).checked_add(section.raw_data_pointer))
+        .map(|offset| offset as usize)
+        .ok_or_else(|| eyre::eyre!("Address '{:#x}' is not in any section", rva))
+}
+
+fn find_resource_entry(
+    bytes: &[u8],
+    resource_offset: usize,
+    directory_offset: usize,
+    id: Option<u32>,
+) -> Result<usize, eyre::Report> {
+    let named_count = read_u16(bytes, directory_offset + 12)? as usize;
+    let id_count = read_u16(bytes, directory_offset + 14)? as usize;
+
+    for index in 0..named_count + id_count {
+        let entry_offset = directory_offset + 16 + index * 8;
+        let entry_id = read_u32(bytes, entry_offset)?;
+        if id.is_some_and(|id| id != entry_id) {
+            continue;
+        }
+        let entry_data = read_u32(bytes, entry_offset + 4)?;
+        return Ok(resource_offset + (entry_data & !RESOURCE_SUBDIRECTORY_FLAG) as usize);
+    }
+
+    Err(eyre::eyre!("Executable has no version resource"))
+}
+
+fn read_version_resource(bytes: &[u8]) -> Result<PeVersionInfo, eyre::Report> {
+    let (root, _) = read_version_block(bytes, 0)?;
+    if root.key != VERSION_INFO_KEY {
+        return Err(eyre::eyre!(
+            "Unexpected version resource key '{}'",
+            root.key
+        ));
+    }
+    if read_u32(root.value, 0)? != FIXED_FILE_INFO_SIGNATURE {
+        return Err(eyre::eyre!("Version resource has no fixed file info"));
+    }
+    let version_ms = read_u32(root.value, 8)?;
+    let version_ls = read_u32(root.value, 12)?;
+
+    let mut version_info = PeVersionInfo {
+        major: version_ms >> 16,
+        minor: version_ms & 0xffff,
+        build: version_ls >> 16,
+        revision: version_ls & 0xffff,
+        file_description: None,
+        product_name: None,
+    };
+
+    // StringFileInfo holds a string table per language, the first one with a value wins
+    for file_info in read_version_children(root.children)? {
+        if file_info.key != STRING_FILE_INFO_KEY {
+            continue;
+        }
+        for string_table in read_version_children(file_info.children)? {
+            for string in read_version_children(string_table.children)? {
+                let value = read_utf16(string.value);
+                if value.trim().is_empty() {
+                    continue;
+                }
+                match string.key.as_str() {
+                    FILE_DESCRIPTION_KEY if version_info.file_description.is_none() => {
+                        version_info.file_description = Some(value.trim().to_string())
+                    }
+                    PRODUCT_NAME_KEY if version_info.product_name.is_none() => {
+                        version_info.product_name = Some(value.trim().to_string())
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(version_info)
+}
+
+fn read_version_children(bytes: &[u8]) -> Result<Vec<VersionBlock<'_>>, eyre::Report> {
+    let mut children = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let (child, next_offset) = read_version_block(bytes, offset)?;
+        children.push(child);
+        offset = next_offset;
+    }
+    Ok(children)
+}
+
+fn read_version_block(
+    bytes: &[u8],
+    offset: usize,
+) -> Result<(VersionBlock<'_>, usize), eyre::Report> {
+    let length = read_u16(bytes, offset)? as usize;
+    let value_length = read_u16(bytes, offset + 2)? as usize;
+    let is_text = read_u16(bytes, offset + 4)? == 1;
+    let end = offset + length;
+    if length < 6 || end > bytes.len() {
+        return Err(eyre::eyre!("Version resource block has a bad length"));
+    }
+
+    let key_start = offset + 6;
+    let key_length = bytes[key_start..end]
+        .chunks_exact(2)
+        .position(|c| c == [0, 0])
+        .ok_or_else(|| eyre::eyre!("Version resource key is not terminated"))?;
+    let key = read_utf16(&bytes[key_start..key_start + key_length * 2]);
+
+    // Text values are measured in UTF-16 characters, binary values in bytes
+    let value_start = align_to_4(key_start + (key_length + 1) * 2).min(end);
+    let value_size = if is_text {
+        value_length * 2
+    } else {
+        value_length
+    };
+    let value_end = (value_start + value_size).min(end);
+    let children_start = align_to_4(value_end).min(end);
+
+    Ok((
+        VersionBlock {
+            key,
+            value: &bytes[value_start..value_end],
+            children: &bytes[children_start..end],
+        },
+        align_to_4(end),
+    ))
+}
+
+fn align_to_4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn read_utf16(bytes: &[u8]) -> String {
+    let chars: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&c| c != 0)
+        .collect();
+    String::from_utf16_lossy(&chars)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, eyre::Report> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| eyre::eyre!("Unexpected end of file at '{:#x}'", offset))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, eyre::Report> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| eyre::eyre!("Unexpected end of file at '{:#x}'", offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::pe_version::{read_pe_version_info, rva_to_offset, Section};
+
+    #[test]
+    fn test_read_pe_version_info_pe32() {
+        // Arrange
+        let bytes = fs::read("./test-data/version_pe32.exe").unwrap();
+
+        // Act
+        let actual = read_pe_version_info(&bytes).unwrap();
+
+        // Assert
+        assert_eq!(
+            (actual.major, actual.minor, actual.build, actual.revision),
+            (4, 11, 3, 0)
+        );
+        assert_eq!(actual.file_description, Some("GZDoom".to_string()));
+        assert_eq!(actual.product_name, Some("GZDoom".to_string()));
+    }
+
+    #[test]
+    fn test_read_pe_version_info_pe32_plus_product_name_only() {
+        // Arrange
+        let bytes = fs::read("./test-data/version_pe64.exe").unwrap();
+
+        // Act
+        let actual = read_pe_version_info(&bytes).unwrap();
+
+        // Assert
+        assert_eq!(
+            (actual.major, actual.minor, actual.build, actual.revision),
+            (14, 5, 0, 1)
+        );
+        assert_eq!(actual.file_description, None);
+        assert_eq!(actual.product_name, Some("Woof!".to_string()));
+    }
+
+    #[test]
+    fn test_read_pe_version_info_not_an_executable() {
+        // Arrange
+        let bytes = fs::read("./test-data/valiant.json").unwrap();
+
+        // Act
+        let actual = read_pe_version_info(&bytes);
+
+        // Assert
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_rva_to_offset_section_end_overflows() {
+        // Arrange
+        let sections = vec![Section {
+            virtual_address: 0xffff_f000,
+            virtual_size: 0x2000,
+            raw_data_size: 0x2000,
+            raw_data_pointer: 0x400,
+        }];
+
+        // Act
+        let actual = rva_to_offset(&sections, 0xffff_f010);
+
+        // Assert
+        assert!(actual.is_err());
+    }
+}
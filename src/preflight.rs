@@ -0,0 +1,507 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+use strum_macros::Display;
+
+use crate::{
+    data,
+    doom_data::{self, GameEngineType, GameFamily},
+    engine_dialect::{self, PlaySetting},
+};
+
+// Checks run before an Engine is started, to catch combinations that will fail or
+// quietly misbehave. Errors stop the launch, warnings are shown and the launch goes on.
+
+#[derive(Clone, Debug, PartialEq, Display)]
+pub enum CheckSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckResult {
+    pub severity: CheckSeverity,
+    pub message: String,
+}
+
+impl CheckResult {
+    pub fn warning(message: String) -> Self {
+        CheckResult {
+            severity: CheckSeverity::Warning,
+            message,
+        }
+    }
+
+    pub fn error(message: String) -> Self {
+        CheckResult {
+            severity: CheckSeverity::Error,
+            message,
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self.severity {
+            CheckSeverity::Warning => format!("{}: {}", self.severity.yellow(), self.message),
+            CheckSeverity::Error => format!("{}: {}", self.severity.red(), self.message),
+        }
+    }
+}
+
+pub fn has_errors(check_results: &[CheckResult]) -> bool {
+    check_results
+        .iter()
+        .any(|check_result| check_result.severity == CheckSeverity::Error)
+}
+
+pub fn check_launch(
+    game_engine_type: &GameEngineType,
+    iwad: &data::Iwad,
+    map_paths: &[String],
+    play_settings: Option<&data::PlaySettings>,
+    netgame: Option<&data::Netgame>,
+    additional_arguments: &[String],
+) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    check_engine_runs_iwad(&mut results, game_engine_type, iwad);
+    if let Some(netgame) = netgame {
+        check_netgame(&mut results, game_engine_type, netgame);
+    }
+    check_archive_maps(&mut results, game_engine_type, map_paths);
+    check_patches(&mut results, iwad, map_paths);
+    check_comp_level_argument(&mut results, game_engine_type, additional_arguments);
+
+    let mut warp = get_argument_values(additional_arguments, "-warp");
+    let mut skills = get_argument_values(additional_arguments, "-skill");
+    if let Some(play_settings) = play_settings {
+        if let Some(play_settings_warp) = &play_settings.warp {
+            warp = play_settings_warp
+                .split_whitespace()
+                .map(|value| value.to_string())
+                .collect();
+        }
+        if let Some(skill) = play_settings.skill {
+            skills.push(skill.to_string());
+        }
+    }
+    if !warp.is_empty() {
+        check_warp(&mut results, iwad, &warp);
+    }
+    for skill in skills {
+        check_skill(&mut results, &skill);
+    }
+
+    results
+}
+
+fn check_engine_runs_iwad(
+    results: &mut Vec<CheckResult>,
+    game_engine_type: &GameEngineType,
+    iwad: &data::Iwad,
+) {
+    let game = iwad.get_game_family();
+    if game == GameFamily::Doom {
+        return;
+    }
+
+    match (game_engine_type, &game) {
+        (GameEngineType::GzDoom | GameEngineType::Unknown, _) => {}
+        // Of the PrBoom+ family, only dsda-doom runs Heretic and Hexen
+        (GameEngineType::PrBoomPlus, GameFamily::Heretic | GameFamily::Hexen) => {
+            results.push(CheckResult::warning(format!(
+                "{} IWAD '{}' needs dsda-doom, PrBoom+ itself cannot run it",
+                game,
+                iwad.get_type_name()
+            )));
+        }
+        (GameEngineType::EternityEngine, GameFamily::Heretic) => {
+            results.push(CheckResult::warning(format!(
+                "{} support in {} is incomplete",
+                game, game_engine_type
+            )));
+        }
+        _ => {
+            results.push(CheckResult::error(format!(
+                "{} cannot run {} IWAD '{}'",
+                game_engine_type,
+                game,
+                iwad.get_type_name()
+            )));
+        }
+    }
+}
+
+fn check_archive_maps(
+    results: &mut Vec<CheckResult>,
+    game_engine_type: &GameEngineType,
+    map_paths: &[String],
+) {
+    // PK3 and PKE Maps are zip archives, which only the ZDoom family and Eternity load
+    if matches!(
+        game_engine_type,
+        GameEngineType::GzDoom | GameEngineType::EternityEngine | GameEngineType::Unknown
+    ) {
+        return;
+    }
+
+    for map_path in map_paths {
+        let extension = get_extension(map_path);
+        if extension == doom_data::EXT_PK3 || extension == doom_data::EXT_PKE {
+            results.push(CheckResult::error(format!(
+                "{} cannot load {} Map '{}', it needs a ZDoom family Engine",
+                game_engine_type,
+                extension.to_uppercase(),
+                map_path
+            )));
+        }
+    }
+}
+
+fn check_patches(results: &mut Vec<CheckResult>, iwad: &data::Iwad, map_paths: &[String]) {
+    // DeHackEd and BEX patch the Doom executable's tables, they mean nothing to other games
+    let game = iwad.get_game_family();
+    if game == GameFamily::Doom {
+        return;
+    }
+
+    for map_path in map_paths {
+        if doom_data::PATCH_FILES.contains(&get_extension(map_path).as_str()) {
+            results.push(CheckResult::warning(format!(
+                "Patch '{}' is for Doom, it will not work with {} IWAD '{}'",
+                map_path,
+                game,
+                iwad.get_type_name()
+            )));
+        }
+    }
+}
+
+fn get_extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn check_warp(results: &mut Vec<CheckResult>, iwad: &data::Iwad, warp: &[String]) {
+    let warp_display = warp.join(" ");
+    let numbers: Vec<u32> = warp.iter().filter_map(|value| value.parse().ok()).collect();
+
+    // Level names such as 'E1M1' or 'MAP01' aren't understood by most Engines
+    if numbers.len() != warp.len() || numbers.len() > 2 {
+        let suggestion = get_warp_suggestion(&warp_display)
+            .map(|suggestion| format!(", try '{}'", suggestion))
+            .unwrap_or_default();
+        results.push(CheckResult::error(format!(
+            "Warp '{}' is not in a form Engines understand{}",
+            warp_display, suggestion
+        )));
+        return;
+    }
+
+    // Custom IWADs can be laid out either way
+    let Some(episodic) = iwad.internal_wad_type.is_episodic() else {
+        return;
+    };
+    match (episodic, numbers.len()) {
+        (false, 2) => results.push(CheckResult::error(format!(
+            "Warp '{}' is an episode and map, but '{}' has no episodes. Use a single map number",
+            warp_display,
+            iwad.get_type_name()
+        ))),
+        (true, 1) => results.push(CheckResult::warning(format!(
+            "Warp '{}' is a single number, but '{}' has episodes. Use an episode and map such as '1 1'",
+            warp_display,
+            iwad.get_type_name()
+        ))),
+        _ => {}
+    }
+}
+
+fn get_warp_suggestion(warp: &str) -> Option<String> {
+    let warp = warp.to_uppercase();
+    if let Some(map) = warp.strip_prefix("MAP") {
+        return map.parse::<u32>().ok().map(|map| map.to_string());
+    }
+    let (episode, map) = warp.strip_prefix('E')?.split_once('M')?;
+    Some(format!(
+        "{} {}",
+        episode.parse::<u32>().ok()?,
+        map.parse::<u32>().ok()?
+    ))
+}
+
+fn check_skill(results: &mut Vec<CheckResult>, skill: &str) {
+    match skill.parse::<u8>() {
+        Ok(value) if (1..=5).contains(&value) => {}
+        _ => results.push(CheckResult::error(format!(
+            "Skill '{}' is not within the range [1-5]",
+            skill
+        ))),
+    }
+}
+
+fn check_comp_level_argument(
+    results: &mut Vec<CheckResult>,
+    game_engine_type: &GameEngineType,
+    additional_arguments: &[String],
+) {
+    if !additional_arguments
+        .iter()
+        .any(|arg| arg.eq_ignore_ascii_case("-complevel"))
+    {
+        return;
+    }
+
+    if !engine_dialect::is_setting_supported(game_engine_type, &PlaySetting::CompLevel) {
+        results.push(CheckResult::warning(format!(
+            "{} does not understand '-complevel' in the additional arguments, it will be ignored",
+            game_engine_type
+        )));
+        return;
+    }
+
+    let values = get_argument_values(additional_arguments, "-complevel");
+    let value = values.join(" ");
+    // PrBoom+ takes a number, Woof! also takes the family names
+    let is_valid = values.len() == 1
+        && match game_engine_type {
+            GameEngineType::PrBoomPlus => value
+                .parse::<i32>()
+                .is_ok_and(|number| (-1..=21).contains(&number)),
+            GameEngineType::Woof => {
+                ["vanilla", "boom", "mbf", "mbf21"].contains(&value.to_lowercase().as_str())
+                    || value.parse::<i32>().is_ok()
+            }
+            _ => true,
+        };
+    if !is_valid {
+        results.push(CheckResult::error(format!(
+            "Compatibility Level '{}' in the additional arguments is not understood by {}",
+            value, game_engine_type
+        )));
+    }
+}
+
+// Hosting on an Engine that can't would start a single player game instead
+fn check_netgame(
+    results: &mut Vec<CheckResult>,
+    game_engine_type: &GameEngineType,
+    netgame: &data::Netgame,
+) {
+    if matches!(netgame.net_role, data::NetRole::Host { .. })
+        && !engine_dialect::is_setting_supported(game_engine_type, &PlaySetting::HostNetgame)
+    {
+        results.push(CheckResult::error(format!(
+            "{} can't host a Netgame, host it with an Engine that can and join with this one",
+            game_engine_type
+        )));
+    }
+}
+
+fn get_argument_values(arguments: &[String], argument: &str) -> Vec<String> {
+    arguments
+        .iter()
+        .position(|arg| arg.eq_ignore_ascii_case(argument))
+        .map(|index| {
+            arguments[index + 1..]
+                .iter()
+                .take_while(|arg| !arg.starts_with('-') && !arg.starts_with('+'))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data,
+        doom_data::{GameEngineType, GameFamily, InternalWadType},
+        preflight::{check_launch, has_errors, CheckSeverity},
+    };
+
+    fn iwad(internal_wad_type: InternalWadType) -> data::Iwad {
+        data::Iwad {
+            internal_wad_type,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_launch_hexen_with_woof() {
+        // Act
+        let actual = check_launch(
+            &GameEngineType::Woof,
+            &iwad(InternalWadType::Hexen),
+            &[],
+            None,
+            None,
+            &[],
+        );
+
+        // Assert
+        assert_eq!(actual.len(), 1);
+        assert!(has_errors(&actual));
+    }
+
+    #[test]
+    fn test_check_launch_pk3_with_crispy() {
+        // Act
+        let actual = check_launch(
+            &GameEngineType::CrispyDoom,
+            &iwad(InternalWadType::Doom2),
+            &["/maps/sunlust.wad".to_string(), "/maps/mod.PK3".to_string()],
+            None,
+            None,
+            &[],
+        );
+
+        // Assert
+        assert_eq!(actual.len(), 1);
+        assert!(actual[0].message.contains("/maps/mod.PK3"));
+    }
+
+    #[test]
+    fn test_check_launch_episode_warp_on_doom2() {
+        // Arrange
+        let play_settings = data::PlaySettings {
+            warp: Some("1 1".to_string()),
+            skill: Some(6),
+            ..Default::default()
+        };
+
+        // Act
+        let actual = check_launch(
+            &GameEngineType::GzDoom,
+            &iwad(InternalWadType::Doom2),
+            &[],
+            Some(&play_settings),
+            None,
+            &[],
+        );
+
+        // Assert
+        assert_eq!(actual.len(), 2);
+        assert!(actual[0].message.contains("Warp '1 1'"));
+        assert!(actual[1].message.contains("Skill '6'"));
+    }
+
+    #[test]
+    fn test_check_launch_level_name_warp_in_arguments() {
+        // Act
+        let actual = check_launch(
+            &GameEngineType::PrBoomPlus,
+            &iwad(InternalWadType::Doom),
+            &[],
+            None,
+            None,
+            &["-warp".to_string(), "E2M3".to_string(), "-fast".to_string()],
+        );
+
+        // Assert
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].severity, CheckSeverity::Error);
+        assert!(actual[0].message.contains("try '2 3'"));
+    }
+
+    #[test]
+    fn test_check_launch_custom_heretic_iwad_with_woof() {
+        // Arrange
+        let custom_iwad = data::Iwad {
+            internal_wad_type: InternalWadType::Custom,
+            name: Some("Blasphemer".to_string()),
+            game_family: Some(GameFamily::Heretic),
+            ..Default::default()
+        };
+
+        // Act
+        let actual = check_launch(
+            &GameEngineType::Woof,
+            &custom_iwad,
+            &[],
+            None,
+            None,
+            &["-warp".to_string(), "2".to_string()],
+        );
+
+        // Assert
+        assert_eq!(actual.len(), 1);
+        assert!(actual[0].message.contains("Heretic IWAD 'Blasphemer'"));
+    }
+
+    #[test]
+    fn test_check_launch_comp_level_in_arguments() {
+        // Act
+        let actual_gzdoom = check_launch(
+            &GameEngineType::GzDoom,
+            &iwad(InternalWadType::Doom2),
+            &[],
+            None,
+            None,
+            &["-complevel".to_string(), "9".to_string()],
+        );
+        let actual_dsda = check_launch(
+            &GameEngineType::PrBoomPlus,
+            &iwad(InternalWadType::Doom2),
+            &[],
+            None,
+            None,
+            &["-complevel".to_string(), "mbf".to_string()],
+        );
+        let actual_woof = check_launch(
+            &GameEngineType::Woof,
+            &iwad(InternalWadType::Doom2),
+            &[],
+            None,
+            None,
+            &["-complevel".to_string(), "mbf21".to_string()],
+        );
+
+        // Assert
+        assert_eq!(actual_gzdoom.len(), 1);
+        assert_eq!(actual_gzdoom[0].severity, CheckSeverity::Warning);
+        assert!(actual_gzdoom[0].message.contains("'-complevel'"));
+        assert_eq!(actual_dsda.len(), 1);
+        assert!(has_errors(&actual_dsda));
+        assert!(actual_woof.is_empty());
+    }
+
+    #[test]
+    fn test_check_launch_host_netgame_with_prboom_plus() {
+        // Arrange
+        let netgame = |net_role: data::NetRole| data::Netgame {
+            net_role,
+            game_mode: data::NetGameMode::Coop,
+            port: None,
+            time_limit: None,
+        };
+        let host = netgame(data::NetRole::Host { players: 2 });
+        let join = netgame(data::NetRole::Join {
+            address: "192.168.0.10".to_string(),
+        });
+
+        // Act
+        let actual_host = check_launch(
+            &GameEngineType::PrBoomPlus,
+            &iwad(InternalWadType::Doom2),
+            &[],
+            None,
+            Some(&host),
+            &[],
+        );
+        let actual_join = check_launch(
+            &GameEngineType::PrBoomPlus,
+            &iwad(InternalWadType::Doom2),
+            &[],
+            None,
+            Some(&join),
+            &[],
+        );
+
+        // Assert
+        assert!(has_errors(&actual_host));
+        assert!(actual_host[0].message.contains("can't host a Netgame"));
+        assert!(actual_join.is_empty());
+    }
+}
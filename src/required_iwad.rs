@@ -0,0 +1,308 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+use crate::{
+    data::{self, MapLevel},
+    doom_data::{self, InternalWadType},
+};
+
+// Works out which IWAD a Map was made for when it doesn't say so in GAMEINFO. A readme's
+// "Game:" line is the author saying so, then textures and flats that only one IWAD has, then
+// whether levels are named ExMy or MAPxx
+
+// Games that share no textures with Doom II come first, as their maps may use its names too
+const TEXTURE_PATTERNS: [(InternalWadType, &str); 5] = [
+    (
+        InternalWadType::Heretic,
+        r"^(CHAINSD|CSTLRCK|GRSKULL\d|GRSTNPB|LOOSERCK|MOSSRCK\d|SANDSQ\d|SQPEB\d|TRISTON\d|WOODWL|FLTWAWA\d|FLTLAVA\d|FLTSLUD\d|FLOOR\d\d)$",
+    ),
+    (
+        InternalWadType::Hexen,
+        r"^(FOREST\d\d|MONK\d\d|CAVE\d\d|WASTE\d\d|SWAMP\d\d|F_\d{3}|X_\d{3})$",
+    ),
+    (
+        InternalWadType::Tnt,
+        r"^(BTNTMETL|BTNTSLVR|DRSIDE[12]|PNK4EXIT|TNT1)$",
+    ),
+    (
+        InternalWadType::Doom2,
+        r"^(ASHWALL[2-7]|BIGBRIK\d|BRICK\d+|BRONZE\d|BSTONE\d|MODWALL\d|PANEL\d|PIPEWAL\d|ROCK[1-5]|SILVER\d|SPACEW\d|STONE[4-7]|TANROCK\d|ZIMMER\d|GRNROCK|RROCK\d\d|SLIME(09|1[0-6]))$",
+    ),
+    (
+        InternalWadType::Doom,
+        r"^(AASTINKY|BROVINE|BROWNWEL|COMP2|COMPOHSO|COMPTILE|COMPUTE[1-3]|DOORHI|GRAYDANG|ICKDOOR1|ICKWALL6|LITE2|LITE4|LITE96|LITEBLU[23]|LITEMET|LITERED|LITESTON|NUKESLAD|PLANET1|REDWALL1|SKINBORD|SKINTEK[12]|SKULWAL3|SKULWALL|SLADRIP[1-3]|SP_ROCK2|STARTAN1|STONGARG|STONPOIS|TEKWALL[235]|WOODSKUL)$",
+    ),
+];
+const EXCLUSIVE_TEXTURE_GAMES: usize = 3;
+
+// The file name of the IWAD the Map needs, when it can be told
+pub fn get_required_iwad(
+    map_contents: &data::MapContents,
+    textures: &BTreeSet<String>,
+) -> Option<String> {
+    let episodic = is_episodic(&map_contents.levels);
+    let game = map_contents
+        .readme_game
+        .clone()
+        .or_else(|| get_texture_game(textures))
+        .or_else(|| {
+            episodic.map(|episodic| match episodic {
+                true => InternalWadType::Doom,
+                false => InternalWadType::Doom2,
+            })
+        })?;
+
+    let game = fit_to_level_naming(game, episodic);
+    doom_data::get_internal_wad_list()
+        .into_iter()
+        .find(|internal_wad| internal_wad.internal_wad_type == game)
+        .map(|internal_wad| internal_wad.file_name.to_lowercase())
+}
+
+// Shareware IWADs can't load Maps, and Final Doom and Chex Quest Maps need the textures of
+// their own IWAD. Otherwise the game and whether it has episodes have to match
+pub fn can_play(iwad: &data::Iwad, required: &InternalWadType) -> bool {
+    if iwad.internal_wad_type == *required {
+        return true;
+    }
+    if matches!(
+        iwad.internal_wad_type,
+        InternalWadType::DoomShareware
+            | InternalWadType::HereticShareware
+            | InternalWadType::StrifeTeaser
+    ) || matches!(
+        required,
+        InternalWadType::Tnt
+            | InternalWadType::Plutonia
+            | InternalWadType::ChexQuest
+            | InternalWadType::ChexQuest3
+    ) {
+        return false;
+    }
+    if iwad.get_game_family() != required.get_game_family() {
+        return false;
+    }
+    match (iwad.internal_wad_type.is_episodic(), required.is_episodic()) {
+        (Some(episodic), Some(required_episodic)) => episodic == required_episodic,
+        _ => true,
+    }
+}
+
+// From the idgames template: "Game : DOOM2"
+pub fn get_readme_game(text: &str) -> Option<InternalWadType> {
+    let game_re = Regex::new(r"(?im)^\s*game\s*:\s*(.+)$").unwrap();
+    let doom2_re = Regex::new(r"doom\s*(2|ii)\b").unwrap();
+
+    let value = game_re.captures(text)?[1].to_lowercase();
+    let game = if value.contains("plutonia") {
+        InternalWadType::Plutonia
+    } else if value.contains("tnt") || value.contains("evilution") {
+        InternalWadType::Tnt
+    } else if value.contains("hexen") {
+        InternalWadType::Hexen
+    } else if value.contains("heretic") {
+        InternalWadType::Heretic
+    } else if value.contains("strife") {
+        InternalWadType::Strife
+    } else if value.contains("chex") {
+        InternalWadType::ChexQuest
+    } else if doom2_re.is_match(&value) {
+        InternalWadType::Doom2
+    } else if value.contains("doom") {
+        InternalWadType::Doom
+    } else {
+        return None;
+    };
+    Some(game)
+}
+
+fn get_texture_game(textures: &BTreeSet<String>) -> Option<InternalWadType> {
+    let counts: Vec<(InternalWadType, usize)> = TEXTURE_PATTERNS
+        .iter()
+        .map(|(game, pattern)| {
+            let texture_re = Regex::new(pattern).unwrap();
+            let count = textures
+                .iter()
+                .filter(|texture| texture_re.is_match(texture))
+                .count();
+            (game.clone(), count)
+        })
+        .collect();
+
+    if let Some((game, _)) = counts
+        .iter()
+        .take(EXCLUSIVE_TEXTURE_GAMES)
+        .find(|(_, count)| *count > 0)
+    {
+        return Some(game.clone());
+    }
+    // Textures can be copied between Doom and Doom II, so the one used most wins
+    counts
+        .into_iter()
+        .skip(EXCLUSIVE_TEXTURE_GAMES)
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(_, count)| *count)
+        .map(|(game, _)| game)
+}
+
+// None when there are no levels, or both ExMy and MAPxx levels
+fn is_episodic(levels: &[MapLevel]) -> Option<bool> {
+    let episode_re = Regex::new(r"^E\dM\d+$").unwrap();
+    let map_re = Regex::new(r"^MAP\d\d$").unwrap();
+
+    let has_episodes = levels
+        .iter()
+        .any(|level| episode_re.is_match(&level.lump_name));
+    let has_maps = levels.iter().any(|level| map_re.is_match(&level.lump_name));
+    match (has_episodes, has_maps) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        _ => None,
+    }
+}
+
+// Readmes often say "Doom" for a Doom II Map, which its level names give away
+fn fit_to_level_naming(game: InternalWadType, episodic: Option<bool>) -> InternalWadType {
+    match (&game, episodic) {
+        (InternalWadType::Doom, Some(false)) => InternalWadType::Doom2,
+        (InternalWadType::Doom2 | InternalWadType::Tnt | InternalWadType::Plutonia, Some(true)) => {
+            InternalWadType::Doom
+        }
+        _ => game,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::{
+        data::{Iwad, MapContents, MapLevel},
+        doom_data::{InternalWadType, LevelFormat},
+        required_iwad::{can_play, get_readme_game, get_required_iwad},
+    };
+
+    fn map_contents(lump_names: &[&str], readme_game: Option<InternalWadType>) -> MapContents {
+        MapContents {
+            levels: lump_names
+                .iter()
+                .map(|lump_name| MapLevel {
+                    lump_name: lump_name.to_string(),
+                    level_format: LevelFormat::Doom,
+                    level_name: None,
+                })
+                .collect(),
+            readme_game,
+            ..Default::default()
+        }
+    }
+
+    fn textures(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_get_required_iwad() {
+        assert_eq!(
+            get_required_iwad(&map_contents(&["MAP01"], None), &textures(&["STARTAN3"])),
+            Some("doom2.wad".to_string())
+        );
+        assert_eq!(
+            get_required_iwad(&map_contents(&["E1M1"], None), &textures(&["STARTAN3"])),
+            Some("doom.wad".to_string())
+        );
+        assert_eq!(
+            get_required_iwad(
+                &map_contents(&["E1M1", "E1M2"], None),
+                &textures(&["SANDSQ2", "FLOOR03"])
+            ),
+            Some("heretic.wad".to_string())
+        );
+        assert_eq!(
+            get_required_iwad(
+                &map_contents(&["MAP01"], None),
+                &textures(&["BTNTMETL", "BRICK1", "PANEL1"])
+            ),
+            Some("tnt.wad".to_string())
+        );
+        assert_eq!(
+            get_required_iwad(
+                &map_contents(&["MAP01"], Some(InternalWadType::Doom)),
+                &textures(&[])
+            ),
+            Some("doom2.wad".to_string())
+        );
+        assert_eq!(
+            get_required_iwad(
+                &map_contents(&["MAP01"], Some(InternalWadType::Plutonia)),
+                &textures(&["ZIMMER1"])
+            ),
+            Some("plutonia.wad".to_string())
+        );
+        assert_eq!(
+            get_required_iwad(&map_contents(&[], None), &textures(&[])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_readme_game() {
+        assert_eq!(
+            get_readme_game("Title : Sunlust\nGame                    : DOOM2\n"),
+            Some(InternalWadType::Doom2)
+        );
+        assert_eq!(
+            get_readme_game("Game: Final Doom (Plutonia)\n"),
+            Some(InternalWadType::Plutonia)
+        );
+        assert_eq!(
+            get_readme_game("Game : Heretic\n"),
+            Some(InternalWadType::Heretic)
+        );
+        assert_eq!(get_readme_game("Gameplay: Lots of it\n"), None);
+    }
+
+    #[test]
+    fn test_can_play() {
+        // Arrange
+        let iwad = |internal_wad_type: InternalWadType| Iwad {
+            internal_wad_type,
+            ..Default::default()
+        };
+
+        // Assert
+        assert!(can_play(
+            &iwad(InternalWadType::Doom2),
+            &InternalWadType::Doom2
+        ));
+        assert!(can_play(
+            &iwad(InternalWadType::Plutonia),
+            &InternalWadType::Doom2
+        ));
+        assert!(can_play(
+            &iwad(InternalWadType::FreedoomPhase2),
+            &InternalWadType::Doom2
+        ));
+        assert!(!can_play(
+            &iwad(InternalWadType::Doom),
+            &InternalWadType::Doom2
+        ));
+        assert!(!can_play(
+            &iwad(InternalWadType::Doom2),
+            &InternalWadType::Plutonia
+        ));
+        assert!(!can_play(
+            &iwad(InternalWadType::Doom2),
+            &InternalWadType::Heretic
+        ));
+        assert!(!can_play(
+            &iwad(InternalWadType::DoomShareware),
+            &InternalWadType::Doom
+        ));
+        assert!(can_play(
+            &iwad(InternalWadType::Custom),
+            &InternalWadType::Doom
+        ));
+    }
+}
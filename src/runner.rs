@@ -1,22 +1,91 @@
 use std::{
     env,
     ffi::OsStr,
+    fs,
     path::Path,
     process::{Command, Stdio},
 };
 
+use chrono::{Local, Utc};
 use eyre::Context;
 use owo_colors::OwoColorize;
+use serde::Serialize;
 
-use crate::{constants, data, db, files, paths};
+use crate::{
+    complevel, constants, data, db, demo, engine_dialect, files, hooks, launch_log, paths,
+    preflight,
+};
 
 pub fn play_from_profile(
     profile_id: i32,
     update_last_profile: bool,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
+    play_profile(profile_id, update_last_profile, play_mode, None, None)
+}
+
+// Netgame and Warp apply to this launch only, they aren't saved to the Profile
+pub fn play_from_profile_with_overrides(
+    profile_id: i32,
+    net_role: Option<data::NetRole>,
+    warp: Option<String>,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
+    let netgame = net_role
+        .map(|net_role| get_netgame(profile_id, net_role))
+        .transpose()?;
+    play_profile(profile_id, true, play_mode, netgame, warp)
+}
+
+fn get_netgame(profile_id: i32, net_role: data::NetRole) -> Result<data::Netgame, eyre::Report> {
+    // Anything not given on the command line comes from the Profile's netgame preset
+    let netgame_preset =
+        db::get_netgame_preset(profile_id)?.unwrap_or_else(|| data::NetgamePreset::new(profile_id));
+
+    let net_role = match net_role {
+        data::NetRole::Host { players: 0 } => data::NetRole::Host {
+            players: netgame_preset.players,
+        },
+        data::NetRole::Join { address } if address.trim().is_empty() => {
+            match netgame_preset.address {
+                Some(address) if !address.trim().is_empty() => data::NetRole::Join { address },
+                _ => {
+                    return Err(eyre::eyre!(
+                        "Play aborted, no address to join and the Profile's netgame preset doesn't have one"
+                    ))
+                }
+            }
+        }
+        _ => net_role,
+    };
+    if let data::NetRole::Host { players } = net_role {
+        if !(2..=constants::MAX_NETGAME_PLAYERS).contains(&players) {
+            return Err(eyre::eyre!(
+                "Play aborted, a netgame needs between 2 and {} players, not {}",
+                constants::MAX_NETGAME_PLAYERS,
+                players
+            ));
+        }
+    }
+
+    Ok(data::Netgame {
+        net_role,
+        game_mode: netgame_preset.game_mode,
+        port: netgame_preset.port,
+        time_limit: netgame_preset.time_limit,
+    })
+}
+
+fn play_profile(
+    profile_id: i32,
+    update_last_profile: bool,
+    play_mode: data::PlayMode,
+    netgame: Option<data::Netgame>,
+    warp: Option<String>,
 ) -> Result<String, eyre::Report> {
     // Get profile and run it
     let profile = db::get_profile_by_id(profile_id)?;
-    let play_result = play_from_engine_iwad_and_map(
+    play_from_engine_iwad_and_map(
         profile.engine_id.unwrap(),
         profile.iwad_id.unwrap(),
         data::map_ids_from_options(
@@ -26,32 +95,55 @@ pub fn play_from_profile(
             profile.map_id4,
             profile.map_id5,
         ),
-        profile.save_game,
-        profile.additional_arguments,
-    )?;
+        data::PlayOptions {
+            launch_environment: profile.launch_environment(),
+            profile_id: Some(profile_id),
+            record_profile_run: true,
+            update_last_profile,
+            save_game: profile.save_game,
+            additional_arguments: profile.additional_arguments,
+            comp_level: profile.comp_level,
+            netgame,
+            warp,
+            ..Default::default()
+        },
+        play_mode,
+    )
+}
 
+// Called once the Engine has started, so a session that crashes still counts as a run
+fn record_profile_run(
+    profile: &data::Profile,
+    update_last_profile: bool,
+) -> Result<(), eyre::Report> {
     // Update the profile's last run date and run count
-    db::update_profile_date_last_run_and_run_count(profile_id, profile.run_count + 1)?;
+    db::update_profile_date_last_run_and_run_count(profile.id, profile.run_count + 1)?;
 
     // Update last run profile on the app settings
     if update_last_profile {
         let mut app_settings = db::get_app_settings()?;
-        app_settings.last_profile_id = Some(profile_id);
+        app_settings.last_profile_id = Some(profile.id);
         db::save_app_settings(app_settings)?;
     }
-
-    Ok(play_result)
+    Ok(())
 }
 
 pub fn play_from_engine_iwad_and_map(
     engine_id: i32,
     iwad_id: i32,
     map_ids: data::MapIds,
-    save_game: Option<String>,
-    additional_arguments: Option<String>,
+    play_options: data::PlayOptions,
+    play_mode: data::PlayMode,
 ) -> Result<String, eyre::Report> {
     let engine = db::get_engine_by_id(engine_id)?;
     let iwad = db::get_iwad_by_id(iwad_id)?;
+    let profile_id = play_options.profile_id;
+    let profile = match profile_id {
+        Some(profile_id) => Some(db::get_profile_by_id(profile_id)?),
+        None => None,
+    };
+    let profile_name = profile.as_ref().map(|profile| profile.name.clone());
+    let profile_run = profile.as_ref().filter(|_| play_options.record_profile_run);
 
     // For macOS, the path is split up into the Engine path and the internal path
     // need to merge them together in order to run the app
@@ -80,116 +172,614 @@ pub fn play_from_engine_iwad_and_map(
         ));
     }
 
-    // TODO: Refactor to be based off selected Doom Engine config (each engine may have different arguments for the same thing)
-
-    // Build up Command based on Profile settings
-    let mut cmd = Command::new(final_engine_path.clone());
-    cmd.arg("-iwad").arg(iwad.path);
-
     // Multiple Maps may be selected, so we need to add them all
-    if map_ids.0 != 0 {
-        cmd.arg("-file");
-        let map_ids_array = [map_ids.0, map_ids.1, map_ids.2, map_ids.3, map_ids.4];
-        for &id in &map_ids_array {
-            if id != 0 {
-                let map = db::get_map_by_id(id)?;
-
-                if !paths::file_exists(&map.path) {
-                    return Err(eyre::eyre!("Play aborted, Map not found - '{}'", map.path));
-                }
+    let mut maps: Vec<data::Map> = Vec::new();
+    let map_ids_array = [map_ids.0, map_ids.1, map_ids.2, map_ids.3, map_ids.4];
+    for &id in &map_ids_array {
+        if id != 0 {
+            let map = db::get_map_by_id(id)?;
 
-                cmd.arg(&map.path);
+            if !paths::file_exists(&map.path) {
+                return Err(eyre::eyre!("Play aborted, Map not found - '{}'", map.path));
             }
+
+            maps.push(map);
         }
     }
+    let map_paths: Vec<String> = maps.iter().map(|map| map.path.clone()).collect();
+
+    let play_settings = get_launch_play_settings(
+        // Demos play back at their own level, a guess from the Maps would desync them
+        play_options.comp_level.clone().or_else(|| {
+            play_options
+                .demo_playback
+                .is_none()
+                .then(|| complevel::get_maps_comp_level(&maps, data::CompLevelConfidence::Medium))
+                .flatten()
+                .inspect(|comp_level| {
+                    log::info!(
+                        "Using the Compatibility Level recommended for the Maps: {}",
+                        comp_level
+                    )
+                })
+        }),
+        play_options.netgame.as_ref(),
+        play_options.warp.clone(),
+    )?;
+
+    // Catch Engine, IWAD and Map combinations that won't work before starting anything
+    let mut additional_arguments = split_arguments(play_options.additional_arguments.as_deref());
+    if play_options.demo_playback.is_none() {
+        additional_arguments.extend(split_arguments(
+            play_settings.additional_arguments.as_deref(),
+        ));
+    }
+    let check_results = preflight::check_launch(
+        &engine.game_engine_type,
+        &iwad,
+        &map_paths,
+        play_options
+            .demo_playback
+            .is_none()
+            .then_some(&play_settings),
+        play_options.netgame.as_ref(),
+        &additional_arguments,
+    );
+    for check_result in &check_results {
+        log::info!("{}", check_result.display());
+    }
+    if preflight::has_errors(&check_results) {
+        return Err(eyre::eyre!(
+            "Play aborted, pre-flight checks failed. Run 'check-profile' for details"
+        ));
+    }
+
+    // Build up Command based on Profile settings
+    let mut cmd = get_engine_command(&final_engine_path, &play_options.launch_environment)?;
+    cmd.arg("-iwad").arg(&iwad.path);
+    // WADs and archives go after -file, DeHackEd and BEX patches get their own arguments
+    cmd.args(engine_dialect::get_resource_args(
+        &engine.game_engine_type,
+        &map_paths,
+    ));
 
-    // Add in save game
-    if let Some(save_game) = save_game {
+    // Add in save game, a demo always starts from the beginning
+    if let (Some(save_game), None) = (play_options.save_game, &play_options.demo_playback) {
         if !save_game.trim().is_empty() {
             cmd.arg("-loadgame").arg(save_game);
         }
     }
 
     // Add in additional arguments
-    add_arguments_to_command(&mut cmd, additional_arguments);
+    add_arguments_to_command(&mut cmd, play_options.additional_arguments);
 
-    // Add in shared play settings
-    let play_settings = db::get_play_settings()?;
-    if play_settings.comp_level.is_some() {
-        let complevel_value = play_settings.comp_level.unwrap() as i32;
-        cmd.arg("-complevel")
-            .arg(complevel_value.to_string());
+    let mut recorded_demo: Option<data::Demo> = None;
+    match play_options.demo_playback {
+        Some(demo_playback) => {
+            // Play Settings change how the game plays, which would desync the demo, so skip them
+            if !paths::file_exists(&demo_playback.path) {
+                return Err(eyre::eyre!(
+                    "Play aborted, Demo not found - '{}'",
+                    demo_playback.path
+                ));
+            }
+            let demo_args = engine_dialect::get_demo_args(
+                &engine.game_engine_type,
+                &demo_playback.demo_action,
+                &demo_playback.path,
+            );
+            if demo_args.args.is_empty() {
+                return Err(eyre::eyre!(
+                    "Play aborted, {} cannot '{}'",
+                    engine.game_engine_type,
+                    demo_playback.demo_action
+                ));
+            }
+            add_engine_args_to_command(&mut cmd, demo_args);
+            // Except the Compatibility Level it was recorded with, without which it desyncs
+            if let Some(comp_level) = &play_options.comp_level {
+                add_engine_args_to_command(
+                    &mut cmd,
+                    engine_dialect::get_comp_level_args(&engine.game_engine_type, comp_level),
+                );
+            }
+        }
+        None => {
+            // Add in shared play settings, spelt the way the selected Engine expects them
+            add_engine_args_to_command(
+                &mut cmd,
+                engine_dialect::get_play_settings_args(&engine.game_engine_type, &play_settings),
+            );
+            if let Some(netgame) = &play_options.netgame {
+                add_engine_args_to_command(
+                    &mut cmd,
+                    engine_dialect::get_netgame_args(&engine.game_engine_type, netgame),
+                );
+            }
+            add_arguments_to_command(&mut cmd, play_settings.additional_arguments.clone());
+
+            if play_settings.record_demo {
+                let date_recorded = Local::now();
+                let demo_name = demo::get_demo_name(
+                    profile_name.as_deref(),
+                    play_settings.warp.as_deref(),
+                    play_settings.skill,
+                    &date_recorded,
+                );
+                let demo_path = paths::get_full_path(
+                    &demo::get_demo_folder(profile_name.as_deref()),
+                    &format!("{}.{}", demo_name, demo::DEMO_EXTENSION),
+                );
+                let demo_args = engine_dialect::get_demo_args(
+                    &engine.game_engine_type,
+                    &data::DemoAction::Record,
+                    &demo_path,
+                );
+                if !demo_args.args.is_empty() {
+                    recorded_demo = Some(data::Demo {
+                        id: 0,
+                        name: demo_name,
+                        path: demo_path,
+                        profile_id,
+                        engine_id,
+                        iwad_id,
+                        map_id: (map_ids.0 != 0).then_some(map_ids.0),
+                        map_id2: (map_ids.1 != 0).then_some(map_ids.1),
+                        map_id3: (map_ids.2 != 0).then_some(map_ids.2),
+                        map_id4: (map_ids.3 != 0).then_some(map_ids.3),
+                        map_id5: (map_ids.4 != 0).then_some(map_ids.4),
+                        warp: play_settings.warp,
+                        skill: play_settings.skill,
+                        date_recorded: date_recorded.with_timezone(&Utc),
+                        comp_level: play_settings.comp_level.clone(),
+                    });
+                }
+                add_engine_args_to_command(&mut cmd, demo_args);
+            }
+        }
     }
-    if play_settings.config_file.is_some() {
-        cmd.arg("-config").arg(play_settings.config_file.unwrap());
+
+    // Show what would be run, without running it. The command is the result, for the CLI to
+    // print and the Console UI to show once it has cleared the screen
+    if let data::PlayMode::DryRun(dry_run_format) = play_mode {
+        return get_dry_run_output(&cmd, &dry_run_format);
     }
-    if play_settings.fast_monsters {
-        cmd.arg("-fast");
+
+    // A wrapper's own arguments and the Engine path come before the Engine's arguments
+    let wrapper = split_arguments(play_options.launch_environment.wrapper_command.as_deref());
+    let display_args = cmd
+        .get_args()
+        .skip(wrapper.len())
+        .filter_map(|s| s.to_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let run_message = match wrapper.is_empty() {
+        true => format!(
+            "Engine '{}', Args '{}'",
+            final_engine_path.magenta(),
+            display_args.blue()
+        ),
+        false => format!(
+            "Engine '{}' via '{}', Args '{}'",
+            final_engine_path.magenta(),
+            wrapper.join(" ").magenta(),
+            display_args.blue()
+        ),
+    };
+
+    // Hooks see what is about to be played, and a failing pre-launch hook stops the launch
+    let app_settings = db::get_app_settings()?;
+    let mut hook_context = hooks::HookContext {
+        profile_name: profile_name.clone(),
+        engine_path: Some(final_engine_path.clone()),
+        iwad_path: Some(iwad.path.clone()),
+        map_paths: map_paths.clone(),
+        ..Default::default()
+    };
+    hooks::run_hooks(
+        hooks::HookPoint::PreLaunch,
+        &[
+            app_settings.pre_launch_hook.clone(),
+            play_options.launch_environment.pre_launch_hook.clone(),
+        ],
+        &hook_context,
+    )
+    .wrap_err("Play aborted")?;
+    let post_exit_hooks = [
+        app_settings.post_exit_hook.clone(),
+        play_options.launch_environment.post_exit_hook.clone(),
+    ];
+
+    // The Engine writes the demo, but won't create the folder for it
+    if let Some(recorded_demo) = &recorded_demo {
+        let demo_folder = paths::extract_path(&recorded_demo.path);
+        paths::create_folder(&demo_folder)
+            .wrap_err(format!("Failed to create Demo folder '{}'", demo_folder))?;
     }
-    if play_settings.no_monsters {
-        cmd.arg("-nomonsters");
+
+    // Engine output goes to a Launch Log, so there is something to look at if it falls over
+    let (launch_log_path, launch_log_file) =
+        launch_log::create_launch_log(profile_name.as_deref())?;
+    let launch_log_stderr = launch_log_file
+        .try_clone()
+        .wrap_err(format!("Failed to open Launch Log '{}'", launch_log_path))?;
+    log::debug!("Launch Log '{}'", launch_log_path);
+
+    // Let's go!
+    cmd.stdout(Stdio::from(launch_log_file))
+        .stderr(Stdio::from(launch_log_stderr));
+    // A post-exit hook needs to know when the Engine has finished, so wait for it
+    if !app_settings.wait_for_engine && !hooks::has_hooks(&post_exit_hooks) {
+        cmd.spawn()
+            .wrap_err(format!("Failed to run {}", run_message))?;
+        if let Some(profile) = profile_run {
+            record_profile_run(profile, play_options.update_last_profile)?;
+        }
+        add_recorded_demo(recorded_demo.as_ref())?;
+
+        // inquire::Text::new("Press any key to continue...").prompt_skippable()?;
+        return Ok(format!("Successfully opened {}", run_message));
     }
-    if play_settings.respawn_monsters {
-        cmd.arg("-respawn");
+
+    // Supervised launch, hang around until the Engine exits so we know how long was played
+    log::info!("Waiting for {} to exit...", run_message);
+    let date_started = Utc::now();
+    let mut child = cmd
+        .spawn()
+        .wrap_err(format!("Failed to run {}", run_message))?;
+    if let Some(profile) = profile_run {
+        record_profile_run(profile, play_options.update_last_profile)?;
     }
-    if let Some(warp) = &play_settings.warp {
-        cmd.arg("-warp");
-        warp.split_whitespace().for_each(|arg| {
-            cmd.arg(arg);
-        });
+    add_recorded_demo(recorded_demo.as_ref())?;
+    let exit_status = child
+        .wait()
+        .wrap_err(format!("Failed to wait for {}", run_message))?;
+    let date_ended = Utc::now();
+    let duration = (date_ended - date_started).num_seconds();
+
+    db::add_play_session(&data::PlaySession {
+        profile_id,
+        engine_id,
+        iwad_id,
+        map_id: (map_ids.0 != 0).then_some(map_ids.0),
+        map_id2: (map_ids.1 != 0).then_some(map_ids.1),
+        map_id3: (map_ids.2 != 0).then_some(map_ids.2),
+        map_id4: (map_ids.3 != 0).then_some(map_ids.3),
+        map_id5: (map_ids.4 != 0).then_some(map_ids.4),
+        date_started,
+        date_ended,
+        duration,
+        exit_code: exit_status.code(),
+    })?;
+
+    hook_context.session_duration = Some(duration);
+    hook_context.exit_code = exit_status.code();
+    if let Err(e) = hooks::run_hooks(hooks::HookPoint::PostExit, &post_exit_hooks, &hook_context) {
+        log::error!("{}", e);
     }
-    if play_settings.skill.is_some() {
-        cmd.arg("-skill")
-            .arg(play_settings.skill.unwrap().to_string());
+
+    if !exit_status.success() {
+        let launch_log = launch_log::read_launch_log(&launch_log_path)?;
+        return Err(eyre::eyre!(
+            "Engine exited with '{}' after {}. Last lines from Launch Log '{}':\n{}",
+            exit_status,
+            data::display_time_played(&duration),
+            launch_log_path,
+            launch_log::get_tail(&launch_log, launch_log::LAUNCH_LOG_TAIL_LINES)
+        ));
     }
-    if play_settings.turbo.is_some() {
-        cmd.arg("-turbo")
-            .arg(play_settings.turbo.unwrap().to_string());
+
+    Ok(format!(
+        "Finished playing {}, Time Played '{}', Exit Code '{}'",
+        run_message,
+        data::display_time_played(&duration).green(),
+        exit_status
+            .code()
+            .map_or("N/A".to_string(), |code| code.to_string())
+    ))
+}
+
+// Only added once the Engine has started, so there is a file behind it
+fn add_recorded_demo(recorded_demo: Option<&data::Demo>) -> Result<(), eyre::Report> {
+    if let Some(recorded_demo) = recorded_demo {
+        db::add_demo(recorded_demo)?;
+        log::info!("Recording Demo '{}'", recorded_demo.path.magenta());
     }
-    if play_settings.timer.is_some() {
-        cmd.arg("-timer")
-            .arg(play_settings.timer.unwrap().to_string());
+    Ok(())
+}
+
+fn get_engine_command(
+    engine_path: &str,
+    launch_environment: &data::LaunchEnvironment,
+) -> Result<Command, eyre::Report> {
+    // A wrapper command goes first, with the Engine as its first argument
+    let wrapper = split_arguments(launch_environment.wrapper_command.as_deref());
+    let mut cmd = match wrapper.split_first() {
+        Some((program, wrapper_args)) => {
+            let mut cmd = Command::new(program);
+            cmd.args(wrapper_args).arg(engine_path);
+            cmd
+        }
+        None => Command::new(engine_path),
+    };
+
+    if let Some(environment) = &launch_environment.environment {
+        cmd.envs(parse_environment(environment)?);
     }
-    if play_settings.width.is_some() {
-        cmd.arg("-width")
-            .arg(play_settings.width.unwrap().to_string());
+
+    if let Some(working_directory) = &launch_environment.working_directory {
+        let working_directory = paths::resolve_path(working_directory);
+        if !paths::folder_exists(&working_directory) {
+            return Err(eyre::eyre!(
+                "Play aborted, Working Directory not found - '{}'",
+                working_directory
+            ));
+        }
+        cmd.current_dir(working_directory);
     }
-    if play_settings.height.is_some() {
-        cmd.arg("-height")
-            .arg(play_settings.height.unwrap().to_string());
+
+    Ok(cmd)
+}
+
+pub fn parse_environment(environment: &str) -> Result<Vec<(String, String)>, eyre::Report> {
+    split_arguments(Some(environment))
+        .into_iter()
+        .map(|variable| match variable.split_once('=') {
+            // Values aren't passed through a shell, so a leading ~ is expanded here
+            Some((key, value)) if !key.is_empty() && !key.contains(char::is_whitespace) => {
+                Ok((key.to_string(), paths::resolve_path(value)))
+            }
+            _ => Err(eyre::eyre!(
+                "Environment variable '{}' is not in the form KEY=VALUE",
+                variable
+            )),
+        })
+        .collect()
+}
+
+fn get_launch_play_settings(
+    comp_level: Option<data::CompLevel>,
+    netgame: Option<&data::Netgame>,
+    warp: Option<String>,
+) -> Result<data::PlaySettings, eyre::Report> {
+    // Shared play settings, a Profile's own Compatibility Level, or the one recommended for its
+    // Maps, wins over the shared one
+    let mut play_settings = db::get_play_settings()?;
+    if comp_level.is_some() {
+        if let Some(shared_comp_level) = &play_settings.comp_level {
+            if comp_level.as_ref() != Some(shared_comp_level) {
+                log::info!(
+                    "Compatibility Level '{}' replaces '{}' from the Play Settings",
+                    data::display_option_comp_level(&comp_level),
+                    shared_comp_level
+                );
+            }
+        }
+        play_settings.comp_level = comp_level;
     }
-    if play_settings.full_screen {
-        cmd.arg("-fullscreen");
+    // As does a Warp given for this launch
+    if warp.is_some() {
+        play_settings.warp = warp;
     }
-    if play_settings.windowed {
-        cmd.arg("-window");
+    // As does a netgame's time limit
+    if let Some(time_limit) = netgame.and_then(|netgame| netgame.time_limit) {
+        play_settings.timer = Some(time_limit);
     }
-    add_arguments_to_command(&mut cmd, play_settings.additional_arguments);
+    Ok(play_settings)
+}
 
-    let display_args = get_display_args(&cmd);
-    let run_message = format!(
-        "Engine '{}', Args '{}'",
-        final_engine_path.magenta(),
-        display_args.blue()
+pub fn check_profile(profile_id: i32) -> Result<Vec<preflight::CheckResult>, eyre::Report> {
+    let profile = db::get_profile_by_id(profile_id)?;
+    let mut results = Vec::new();
+
+    let (Some(engine_id), Some(iwad_id)) = (profile.engine_id, profile.iwad_id) else {
+        results.push(preflight::CheckResult::error(
+            "Profile has no Engine or IWAD set".to_string(),
+        ));
+        return Ok(results);
+    };
+    let engine = db::get_engine_by_id(engine_id)?;
+    let iwad = db::get_iwad_by_id(iwad_id)?;
+
+    let engine_path = match &engine.internal_path {
+        Some(internal_path) => Path::new(&engine.path)
+            .join(internal_path)
+            .display()
+            .to_string(),
+        None => engine.path.clone(),
+    };
+    if !paths::file_exists(&engine_path) {
+        results.push(preflight::CheckResult::error(format!(
+            "Engine not found - '{}'",
+            engine_path
+        )));
+    }
+    if !paths::file_exists(&iwad.path) {
+        results.push(preflight::CheckResult::error(format!(
+            "IWAD not found - '{}'",
+            iwad.path
+        )));
+    }
+
+    let mut maps: Vec<data::Map> = Vec::new();
+    for map_id in [
+        profile.map_id,
+        profile.map_id2,
+        profile.map_id3,
+        profile.map_id4,
+        profile.map_id5,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let map = db::get_map_by_id(map_id)?;
+        if !paths::file_exists(&map.path) {
+            results.push(preflight::CheckResult::error(format!(
+                "Map not found - '{}'",
+                map.path
+            )));
+        }
+        maps.push(map);
+    }
+    let map_paths: Vec<String> = maps.iter().map(|map| map.path.clone()).collect();
+
+    if let Some(environment) = &profile.environment {
+        if let Err(e) = parse_environment(environment) {
+            results.push(preflight::CheckResult::error(e.to_string()));
+        }
+    }
+    if let Some(working_directory) = &profile.working_directory {
+        let working_directory = paths::resolve_path(working_directory);
+        if !paths::folder_exists(&working_directory) {
+            results.push(preflight::CheckResult::error(format!(
+                "Working Directory not found - '{}'",
+                working_directory
+            )));
+        }
+    }
+    if let Some(program) = split_arguments(profile.wrapper_command.as_deref()).first() {
+        let program = paths::resolve_path(program);
+        if !paths::file_exists(&program) && paths::find_on_path(&program).is_none() {
+            results.push(preflight::CheckResult::error(format!(
+                "Wrapper command not found - '{}'",
+                program
+            )));
+        }
+    }
+
+    let play_settings = get_launch_play_settings(
+        profile
+            .comp_level
+            .or_else(|| complevel::get_maps_comp_level(&maps, data::CompLevelConfidence::Medium)),
+        None,
+        None,
+    )?;
+    let mut additional_arguments = split_arguments(profile.additional_arguments.as_deref());
+    additional_arguments.extend(split_arguments(
+        play_settings.additional_arguments.as_deref(),
+    ));
+    results.extend(preflight::check_launch(
+        &engine.game_engine_type,
+        &iwad,
+        &map_paths,
+        Some(&play_settings),
+        None,
+        &additional_arguments,
+    ));
+
+    // Play Settings the Engine doesn't understand are dropped at launch, worth knowing about
+    results.extend(
+        engine_dialect::get_play_settings_args(&engine.game_engine_type, &play_settings)
+            .warnings
+            .into_iter()
+            .map(preflight::CheckResult::warning),
     );
 
-    // Let's go!
-    cmd.stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .wrap_err(format!("Failed to run {}", run_message))?;
+    Ok(results)
+}
 
-    // inquire::Text::new("Press any key to continue...").prompt_skippable()?;
-    Ok(format!("Successfully opened {}", run_message))
+fn add_engine_args_to_command(cmd: &mut Command, engine_args: engine_dialect::PlaySettingsArgs) {
+    for warning in &engine_args.warnings {
+        log::info!("{}", warning.yellow());
+    }
+    cmd.args(engine_args.args);
 }
 
 fn add_arguments_to_command(cmd: &mut Command, additional_arguments: Option<String>) {
-    if let Some(additional_arguments_unwrapped) = additional_arguments {
-        let escaped_arguments = additional_arguments_unwrapped.replace('\\', r"\\");
-        let args: Vec<String> = shlex::split(&escaped_arguments).unwrap_or_default();
-        for arg in args {
-            cmd.arg(arg);
+    cmd.args(split_arguments(additional_arguments.as_deref()));
+}
+
+fn split_arguments(additional_arguments: Option<&str>) -> Vec<String> {
+    match additional_arguments {
+        Some(additional_arguments) => {
+            shlex::split(&escape_backslashes(additional_arguments)).unwrap_or_default()
         }
+        None => Vec::new(),
+    }
+}
+
+fn escape_backslashes(arguments: &str) -> String {
+    // Windows paths are typed with single backslashes, which shlex would read as escapes.
+    // Inside single quotes a backslash is already literal, and inside double quotes
+    // escapes written by shlex quoting are kept as they are
+    let mut escaped_arguments = String::with_capacity(arguments.len());
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut chars = arguments.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            '\\' if in_double_quotes
+                && chars
+                    .peek()
+                    .is_some_and(|next| ['\\', '"', '$', '`'].contains(next)) =>
+            {
+                escaped_arguments.push(c);
+                escaped_arguments.extend(chars.next());
+                continue;
+            }
+            '\\' if !in_single_quotes => escaped_arguments.push('\\'),
+            _ => {}
+        }
+        escaped_arguments.push(c);
+    }
+    escaped_arguments
+}
+
+#[derive(Serialize)]
+struct DryRunCommand {
+    engine: String,
+    args: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    environment: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    working_directory: Option<String>,
+}
+
+fn get_dry_run_output(
+    cmd: &Command,
+    dry_run_format: &data::DryRunFormat,
+) -> Result<String, eyre::Report> {
+    let dry_run_command = DryRunCommand {
+        engine: cmd.get_program().to_string_lossy().to_string(),
+        args: cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect(),
+        environment: cmd
+            .get_envs()
+            .filter_map(|(key, value)| {
+                value.map(|value| format!("{}={}", key.to_string_lossy(), value.to_string_lossy()))
+            })
+            .collect(),
+        working_directory: cmd
+            .get_current_dir()
+            .map(|working_directory| working_directory.display().to_string()),
+    };
+
+    match dry_run_format {
+        data::DryRunFormat::Shell => {
+            // Environment variables prefix the command, the way a shell would set them
+            let argv = dry_run_command
+                .environment
+                .iter()
+                .chain(std::iter::once(&dry_run_command.engine))
+                .chain(&dry_run_command.args);
+            let command = shlex::try_join(argv.map(|arg| arg.as_str()))
+                .wrap_err("Failed to shell quote the Engine command")?;
+            match &dry_run_command.working_directory {
+                Some(working_directory) => Ok(format!(
+                    "cd {} && {}",
+                    shlex::try_quote(working_directory)
+                        .wrap_err("Failed to shell quote the Working Directory")?,
+                    command
+                )),
+                None => Ok(command),
+            }
+        }
+        data::DryRunFormat::Json => serde_json::to_string(&dry_run_command)
+            .wrap_err("Failed to convert the Engine command to JSON"),
     }
 }
 
@@ -209,33 +799,10 @@ pub fn open_map_readme(map_path: &str) -> Result<String, eyre::Report> {
 
     match readme_file_name {
         Some(readme_file_name) => {
-            let program = {
-                if env::consts::OS == constants::OS_MACOS {
-                    "open"
-                } else if env::consts::OS == constants::OS_WINDOWS {
-                    "cmd"
-                } else {
-                    return Err(eyre::eyre!(format!(
-                        "open_map_readme is only supported on Windows and macOS, not on '{}'",
-                        env::consts::OS
-                    )));
-                }
-            };
-
-            let mut cmd = Command::new(program);
-
-            #[cfg(target_os = "macos")]
-            cmd.arg(&readme_file_name);
-            #[cfg(target_os = "windows")]
-            cmd.args(["/C", "start", "", &readme_file_name]);
-
-            cmd.stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-                .wrap_err(format!(
-                    "Failed to open Map Readme for Map - '{}' / '{}'",
-                    readme_file_name, map_path
-                ))?;
+            open_with_default_app(&readme_file_name, LinuxOpener::Editor).wrap_err(format!(
+                "Failed to open Map Readme for Map - '{}' / '{}'",
+                readme_file_name, map_path
+            ))?;
 
             Ok(format!(
                 "Opened Map Readme for Map - '{}' / '{}'",
@@ -248,38 +815,127 @@ pub fn open_map_readme(map_path: &str) -> Result<String, eyre::Report> {
 }
 
 pub fn open_url(url: &str, description: &str) -> Result<String, eyre::Report> {
-    let program = {
-        if env::consts::OS == constants::OS_MACOS {
-            "open"
-        } else if env::consts::OS == constants::OS_WINDOWS {
-            "cmd"
-        } else {
+    open_with_default_app(url, LinuxOpener::Browser)
+        .wrap_err(format!("Failed to open URL '{}' for {}", url, description))?;
+
+    Ok(format!(
+        "Opened URL '{}' for {}",
+        url.magenta(),
+        description.blue()
+    ))
+}
+
+// SVG previews are best shown by a browser, which also opens PNGs
+pub fn open_file(file_path: &str, description: &str) -> Result<String, eyre::Report> {
+    open_with_default_app(file_path, LinuxOpener::Browser).wrap_err(format!(
+        "Failed to open '{}' for {}",
+        file_path, description
+    ))?;
+
+    Ok(format!(
+        "Opened '{}' for {}",
+        file_path.magenta(),
+        description.blue()
+    ))
+}
+
+#[derive(PartialEq)]
+enum LinuxOpener {
+    Browser,
+    Editor,
+}
+
+fn open_with_default_app(target: &str, linux_opener: LinuxOpener) -> Result<(), eyre::Report> {
+    let mut cmd = match env::consts::OS {
+        constants::OS_MACOS => {
+            let mut cmd = Command::new("open");
+            cmd.arg(target);
+            cmd
+        }
+        constants::OS_WINDOWS => {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", "start", "", target]);
+            cmd
+        }
+        constants::OS_LINUX => return open_with_default_linux_app(target, linux_opener),
+        _ => {
             return Err(eyre::eyre!(format!(
-                "open_url is only supported on Windows and macOS, not on '{}'",
+                "Opening files is only supported on Windows, macOS and Linux, not on '{}'",
                 env::consts::OS
-            )));
+            )))
         }
     };
 
-    let mut cmd = Command::new(program);
+    cmd.stdout(Stdio::null()).stderr(Stdio::null()).spawn()?;
+    Ok(())
+}
 
-    #[cfg(target_os = "macos")]
-    cmd.arg(url);
-    #[cfg(target_os = "windows")]
-    cmd.args(["/C", "start", "", url]);
+fn open_with_default_linux_app(
+    target: &str,
+    linux_opener: LinuxOpener,
+) -> Result<(), eyre::Report> {
+    // $BROWSER can hold a list of browsers, split by ':'. Use the first one
+    let browser = env::var(constants::ENV_BROWSER).ok().and_then(|browser| {
+        browser
+            .split(':')
+            .map(|browser| browser.trim().to_string())
+            .find(|browser| !browser.is_empty())
+    });
+    let editor = env::var(constants::ENV_EDITOR)
+        .ok()
+        .filter(|editor| !editor.trim().is_empty());
+    let xdg_open = paths::find_on_path(constants::XDG_OPEN);
 
-    cmd.stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .wrap_err(format!("Failed to open URL '{}' for {}", url, description))?;
+    // A browser named by the user wins, then xdg-open. $EDITOR is the last resort for
+    // readmes, and is usually a terminal editor so dcli waits for it to close.
+    if let (LinuxOpener::Browser, Some(browser)) = (&linux_opener, &browser) {
+        let mut cmd = get_command_from_program(browser)?;
+        cmd.arg(target)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        return Ok(());
+    }
 
-    Ok(format!(
-        "Opened URL '{}' for {}",
-        url.magenta(),
-        description.blue()
-    ))
+    if let Some(xdg_open) = xdg_open {
+        Command::new(xdg_open)
+            .arg(target)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        return Ok(());
+    }
+
+    if let (LinuxOpener::Editor, Some(editor)) = (&linux_opener, &editor) {
+        let mut cmd = get_command_from_program(editor)?;
+        cmd.arg(target).status()?;
+        return Ok(());
+    }
+
+    Err(eyre::eyre!(format!(
+        "Unable to open '{}'. Install xdg-utils or set ${}",
+        target,
+        match linux_opener {
+            LinuxOpener::Browser => constants::ENV_BROWSER,
+            LinuxOpener::Editor => constants::ENV_EDITOR,
+        }
+    )))
 }
 
+fn get_command_from_program(program: &str) -> Result<Command, eyre::Report> {
+    // Programs set in the environment can come with their own arguments, like "code -w"
+    let parts = shlex::split(program).unwrap_or_default();
+    match parts.split_first() {
+        Some((program, args)) => {
+            let mut cmd = Command::new(program);
+            cmd.args(args);
+            Ok(cmd)
+        }
+        None => Err(eyre::eyre!(format!("Unable to run '{}'", program))),
+    }
+}
+
+#[allow(clippy::unnecessary_unwrap)]
 pub fn editor(map_path: &str, editor: data::Editor) -> Result<String, eyre::Report> {
     let mut cmd = Command::new(&editor.path);
     if editor.load_file_argument.is_some() {
@@ -310,3 +966,100 @@ pub fn editor(map_path: &str, editor: data::Editor) -> Result<String, eyre::Repo
 
     Ok(format!("Successfully opened {}", run_message))
 }
+
+pub fn play_demo(
+    demo: &data::Demo,
+    demo_action: data::DemoAction,
+    play_mode: data::PlayMode,
+) -> Result<String, eyre::Report> {
+    // A Demo plays the way its Profile does, with the same environment, wrapper and hooks
+    let launch_environment = match demo.profile_id {
+        Some(profile_id) => db::get_profile_by_id(profile_id)?.launch_environment(),
+        None => data::LaunchEnvironment::default(),
+    };
+    play_from_engine_iwad_and_map(
+        demo.engine_id,
+        demo.iwad_id,
+        data::map_ids_from_options(
+            demo.map_id,
+            demo.map_id2,
+            demo.map_id3,
+            demo.map_id4,
+            demo.map_id5,
+        ),
+        data::PlayOptions {
+            profile_id: demo.profile_id,
+            demo_playback: Some(data::DemoPlayback {
+                demo_action,
+                path: demo.path.clone(),
+            }),
+            comp_level: match &demo.comp_level {
+                Some(comp_level) => Some(comp_level.clone()),
+                None => get_demo_header_comp_level(demo)?,
+            },
+            launch_environment,
+            ..Default::default()
+        },
+        play_mode,
+    )
+}
+
+// Demos recorded without a Compatibility Level still have one in their header
+fn get_demo_header_comp_level(demo: &data::Demo) -> Result<Option<data::CompLevel>, eyre::Report> {
+    let Ok(bytes) = fs::read(&demo.path) else {
+        return Ok(None);
+    };
+    match demo::parse_demo_header(&bytes) {
+        Ok(header) => {
+            let iwad = db::get_iwad_by_id(demo.iwad_id)?;
+            Ok(Some(header.get_comp_level(&iwad.internal_wad_type)))
+        }
+        Err(e) => {
+            log::debug!("Unable to read the header of Demo '{}': {}", demo.path, e);
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::{parse_environment, split_arguments};
+
+    #[test]
+    fn test_split_arguments_keeps_backslashes() {
+        // Arrange
+        let arguments = r#"-config C:\doom\dsda.cfg -file "C:\my maps\a.wad" 'C:\quoted\b.wad'"#;
+
+        // Act
+        let actual = split_arguments(Some(arguments));
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![
+                "-config",
+                r"C:\doom\dsda.cfg",
+                "-file",
+                r"C:\my maps\a.wad",
+                r"C:\quoted\b.wad"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_environment_quoted_windows_path() {
+        // Arrange
+        let environment =
+            shlex::try_join([r"DOOMWADDIR=C:\foo", "SDL_VIDEODRIVER=wayland"]).unwrap();
+
+        // Act
+        let actual = parse_environment(&environment).unwrap();
+
+        // Assert
+        assert_eq!(actual[0], ("DOOMWADDIR".to_string(), r"C:\foo".to_string()));
+        assert_eq!(
+            actual[1],
+            ("SDL_VIDEODRIVER".to_string(), "wayland".to_string())
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::{self, IsTerminal, Write},
+};
+
+use base64::Engine;
+
+use crate::title_screen::Image;
+
+// Draws images inline in terminals that support it. Kitty's protocol takes a PNG, see
+// https://sw.kovidgoyal.net/kitty/graphics-protocol/, sixel takes up to 256 colours six rows
+// at a time, see https://vt100.net/docs/vt3xx-gp/chapter14.html
+
+const KITTY_CHUNK_SIZE: usize = 4096;
+const SIXEL_BAND_HEIGHT: u32 = 6;
+const SIXEL_MAX_COLOURS: usize = 256;
+const KITTY_TERMINALS: [&str; 2] = ["xterm-kitty", "xterm-ghostty"];
+const KITTY_TERM_PROGRAMS: [&str; 2] = ["ghostty", "WezTerm"];
+const SIXEL_TERMINALS: [&str; 4] = ["foot", "foot-extra", "mlterm", "contour"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TerminalGraphics {
+    Kitty,
+    Sixel,
+}
+
+// Terminals can't be asked without reading a reply from stdin, so this goes by what they
+// set in the environment
+pub fn get_terminal_graphics() -> Option<TerminalGraphics> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    let term = env::var("TERM").unwrap_or_default();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+
+    if env::var("KITTY_WINDOW_ID").is_ok()
+        || KITTY_TERMINALS.contains(&term.as_str())
+        || KITTY_TERM_PROGRAMS.contains(&term_program.as_str())
+    {
+        Some(TerminalGraphics::Kitty)
+    } else if term.contains("sixel") || SIXEL_TERMINALS.contains(&term.as_str()) {
+        Some(TerminalGraphics::Sixel)
+    } else {
+        None
+    }
+}
+
+pub fn draw_image(
+    terminal_graphics: &TerminalGraphics,
+    png_bytes: &[u8],
+    image: &Image,
+) -> Result<(), eyre::Report> {
+    let escape_codes = match terminal_graphics {
+        TerminalGraphics::Kitty => encode_kitty(png_bytes),
+        TerminalGraphics::Sixel => encode_sixel(image),
+    };
+    let mut stdout = io::stdout();
+    stdout.write_all(escape_codes.as_bytes())?;
+    stdout.write_all(b"\n")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn encode_kitty(png_bytes: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png_bytes);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+
+    let mut escape_codes = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        // Only the first chunk says what to do, the rest say if more follow
+        let keys = if index == 0 { "a=T,f=100," } else { "" };
+        let more = if index + 1 < chunks.len() { 1 } else { 0 };
+        escape_codes.push_str(&format!(
+            "\x1b_G{}m={};{}\x1b\\",
+            keys,
+            more,
+            String::from_utf8_lossy(chunk)
+        ));
+    }
+    escape_codes
+}
+
+fn encode_sixel(image: &Image) -> String {
+    let pixels: Vec<[u8; 3]> = image
+        .rgb
+        .chunks_exact(3)
+        .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect();
+
+    // Doom pictures never have more than 256 colours, anything else is cut down to 3-3-2 bits
+    let mut palette: Vec<[u8; 3]> = pixels.clone();
+    palette.sort();
+    palette.dedup();
+    let reduce = palette.len() > SIXEL_MAX_COLOURS;
+    let reduce_colour = |[r, g, b]: [u8; 3]| -> [u8; 3] { [r & 0xE0, g & 0xE0, b & 0xC0] };
+    if reduce {
+        palette = palette.into_iter().map(reduce_colour).collect();
+        palette.sort();
+        palette.dedup();
+    }
+    let colour_indexes: HashMap<[u8; 3], usize> = palette
+        .iter()
+        .enumerate()
+        .map(|(index, colour)| (*colour, index))
+        .collect();
+    let indexes: Vec<usize> = pixels
+        .iter()
+        .map(|pixel| match reduce {
+            true => colour_indexes[&reduce_colour(*pixel)],
+            false => colour_indexes[pixel],
+        })
+        .collect();
+
+    let mut sixel = format!("\x1bPq\"1;1;{};{}", image.width, image.height);
+    for (index, [r, g, b]) in palette.iter().enumerate() {
+        let percent = |value: &u8| *value as u32 * 100 / 255;
+        sixel.push_str(&format!(
+            "#{};2;{};{};{}",
+            index,
+            percent(r),
+            percent(g),
+            percent(b)
+        ));
+    }
+
+    let width = image.width as usize;
+    for band_top in (0..image.height).step_by(SIXEL_BAND_HEIGHT as usize) {
+        let band_rows = (band_top..(band_top + SIXEL_BAND_HEIGHT).min(image.height))
+            .map(|y| y as usize)
+            .collect::<Vec<usize>>();
+        let mut band_colours: Vec<usize> = band_rows
+            .iter()
+            .flat_map(|y| indexes[y * width..(y + 1) * width].iter().copied())
+            .collect();
+        band_colours.sort();
+        band_colours.dedup();
+
+        for (colour_number, colour) in band_colours.iter().enumerate() {
+            let sixels: Vec<u8> = (0..width)
+                .map(|x| {
+                    let bits = band_rows
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, y)| indexes[*y * width + x] == *colour)
+                        .fold(0u8, |bits, (row, _)| bits | 1 << row);
+                    b'?' + bits
+                })
+                .collect();
+            if colour_number > 0 {
+                sixel.push('$');
+            }
+            sixel.push_str(&format!("#{}", colour));
+            sixel.push_str(&run_length_encode(&sixels));
+        }
+        sixel.push('-');
+    }
+    sixel.push_str("\x1b\\");
+    sixel
+}
+
+fn run_length_encode(sixels: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut index = 0;
+    while index < sixels.len() {
+        let sixel = sixels[index];
+        let count = sixels[index..]
+            .iter()
+            .take_while(|next| **next == sixel)
+            .count();
+        if count > 3 {
+            encoded.push_str(&format!("!{}{}", count, sixel as char));
+        } else {
+            encoded.push_str(&(sixel as char).to_string().repeat(count));
+        }
+        index += count;
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{terminal_graphics::encode_sixel, title_screen::Image};
+
+    #[test]
+    fn test_encode_sixel() {
+        // Arrange
+        // Five red pixels on top of one blue one, in a single column
+        let mut rgb: Vec<u8> = [255, 0, 0].repeat(5);
+        rgb.extend([0, 0, 255]);
+        let image = Image {
+            width: 1,
+            height: 6,
+            rgb,
+        };
+
+        // Act
+        let actual = encode_sixel(&image);
+
+        // Assert
+        assert_eq!(
+            actual,
+            "\x1bPq\"1;1;1;6#0;2;0;0;100#1;2;100;0;0#0_$#1^-\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+
+use eyre::Context;
+use zip::ZipArchive;
+
+use crate::{
+    data::{self, TitleScreenLump},
+    db, doom_data, map_preview, paths, wad,
+};
+
+// Decodes the title and menu graphics of a Map into PNGs. Graphics lumps are either PNGs or
+// in the Doom picture format, https://doomwiki.org/wiki/Picture_format, which is coloured
+// using the first palette of PLAYPAL, https://doomwiki.org/wiki/PLAYPAL
+
+pub const TITLE_SCREEN_LUMPS: [TitleScreenLump; 3] = [
+    TitleScreenLump::Titlepic,
+    TitleScreenLump::Interpic,
+    TitleScreenLump::MDoom,
+];
+
+const LUMP_PLAYPAL: &str = "PLAYPAL";
+const PALETTE_SIZE: usize = 768;
+const PICTURE_HEADER_SIZE: usize = 8;
+// Larger than any picture a Doom engine will draw, and keeps a bad header from asking for gigabytes
+const PICTURE_MAX_SIZE: usize = 4096;
+const POST_END: u8 = 0xFF;
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+// Graphics in a PK3 can sit in the root folder or the graphics folder
+const GRAPHICS_FOLDERS: [&str; 2] = ["", "graphics"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub rgb: Vec<u8>,
+}
+
+// A Doom picture is drawn in columns of posts, and anything not covered is transparent
+#[derive(Clone, Debug, PartialEq)]
+struct Picture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Option<u8>>,
+}
+
+// The graphics lumps found in a Map, which is empty for Maps that don't replace any
+pub fn get_title_screen_lumps(map_path: &str) -> Vec<TitleScreenLump> {
+    TITLE_SCREEN_LUMPS
+        .iter()
+        .filter(|lump| {
+            read_map_lump(map_path, &lump.to_string()).is_ok_and(|bytes| bytes.is_some())
+        })
+        .cloned()
+        .collect()
+}
+
+// Named after the whole file name, so 'Map.wad' and 'Map.pk3' don't overwrite each other
+pub fn get_title_screen_path(map_path: &str, lump: &TitleScreenLump) -> String {
+    paths::get_full_path(
+        &paths::extract_path(map_path),
+        &format!(
+            "{}.{}.png",
+            paths::extract_file_name(map_path),
+            lump.to_string().to_lowercase()
+        ),
+    )
+}
+
+// Writes the graphic as a PNG next to the Map, returning its path and the decoded image
+pub fn write_title_screen(
+    map: &data::Map,
+    lump: &TitleScreenLump,
+) -> Result<(String, Image), eyre::Report> {
+    let Some(bytes) = read_map_lump(&map.path, &lump.to_string())? else {
+        return Err(eyre::eyre!("Map '{}' has no {} lump", map.title, lump));
+    };
+
+    let image = if bytes.starts_with(&PNG_SIGNATURE) {
+        decode_png(&bytes)?
+    } else {
+        let palette = get_palette(map)?;
+        let picture = decode_picture(&bytes).wrap_err(format!("Unable to decode {}", lump))?;
+        picture.to_image(&palette)
+    };
+
+    let title_screen_path = get_title_screen_path(&map.path, lump);
+    map_preview::write_png(&title_screen_path, image.width, image.height, &image.rgb)
+        .wrap_err(format!("Failed to write '{}'", title_screen_path))?;
+    Ok((title_screen_path, image))
+}
+
+fn read_map_lump(map_path: &str, lump_name: &str) -> Result<Option<Vec<u8>>, eyre::Report> {
+    let extension = Path::new(map_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        doom_data::EXT_WAD => read_wad_lump(map_path, lump_name),
+        doom_data::EXT_PK3 | doom_data::EXT_PKE => {
+            let reader =
+                paths::open_file(map_path).wrap_err(format!("Failed to open '{}'", map_path))?;
+            let mut archive = ZipArchive::new(reader)
+                .wrap_err(format!("Failed to read archive '{}'", map_path))?;
+            read_archive_lump(&mut archive, lump_name)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn read_wad_lump(wad_path: &str, lump_name: &str) -> Result<Option<Vec<u8>>, eyre::Report> {
+    let wad_directory = wad::read_wad_directory(wad_path)?;
+    match wad_directory.find_lump(lump_name) {
+        Some(lump) => Ok(Some(wad::read_lump(wad_path, lump)?)),
+        None => Ok(None),
+    }
+}
+
+fn read_archive_lump<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    lump_name: &str,
+) -> Result<Option<Vec<u8>>, eyre::Report> {
+    let file_path = archive.file_names().find(|file_path| {
+        let (folder, file_name) = file_path.rsplit_once('/').unwrap_or(("", file_path));
+        let stem = Path::new(file_name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        GRAPHICS_FOLDERS
+            .iter()
+            .any(|graphics_folder| folder.eq_ignore_ascii_case(graphics_folder))
+            && stem.eq_ignore_ascii_case(lump_name)
+    });
+    let Some(file_path) = file_path.map(|file_path| file_path.to_string()) else {
+        return Ok(None);
+    };
+
+    let mut bytes = Vec::new();
+    archive.by_name(&file_path)?.read_to_end(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
+// A Map's own PLAYPAL wins, then the IWAD it needs, then the Default IWAD
+fn get_palette(map: &data::Map) -> Result<Vec<u8>, eyre::Report> {
+    if let Some(playpal) = read_map_lump(&map.path, LUMP_PLAYPAL)? {
+        return check_palette(playpal, &map.path);
+    }
+
+    let iwads = db::get_iwads()?;
+    let default_iwad_id = db::get_app_settings()?.default_iwad_id;
+    let iwad = map
+        .iwad()
+        .and_then(|required_iwad| {
+            iwads.iter().find(|iwad| {
+                paths::extract_file_name(&iwad.path).eq_ignore_ascii_case(required_iwad)
+            })
+        })
+        .or_else(|| iwads.iter().find(|iwad| Some(iwad.id) == default_iwad_id))
+        .or(iwads.first())
+        .ok_or_else(|| eyre::eyre!("There are no IWADs to read a palette from"))?;
+
+    match read_wad_lump(&iwad.path, LUMP_PLAYPAL)? {
+        Some(playpal) => check_palette(playpal, &iwad.path),
+        None => Err(eyre::eyre!("IWAD '{}' has no palette", iwad.path)),
+    }
+}
+
+fn check_palette(mut playpal: Vec<u8>, path: &str) -> Result<Vec<u8>, eyre::Report> {
+    if playpal.len() < PALETTE_SIZE {
+        return Err(eyre::eyre!("Palette in '{}' is too small", path));
+    }
+    playpal.truncate(PALETTE_SIZE);
+    Ok(playpal)
+}
+
+fn decode_picture(bytes: &[u8]) -> Result<Picture, eyre::Report> {
+    if bytes.len() < PICTURE_HEADER_SIZE {
+        return Err(eyre::eyre!("Picture is too small"));
+    }
+    let width = u16::from_le_bytes([bytes[0], bytes[1]]) as usize;
+    let height = u16::from_le_bytes([bytes[2], bytes[3]]) as usize;
+    if width == 0 || height == 0 || bytes.len() < PICTURE_HEADER_SIZE + width * 4 {
+        return Err(eyre::eyre!("Not a Doom picture"));
+    }
+    if width > PICTURE_MAX_SIZE || height > PICTURE_MAX_SIZE {
+        return Err(eyre::eyre!(
+            "Picture is {}x{}, larger than {}x{}",
+            width,
+            height,
+            PICTURE_MAX_SIZE,
+            PICTURE_MAX_SIZE
+        ));
+    }
+
+    let column_offsets: Vec<usize> = (0..width)
+        .map(|x| {
+            let offset_start = PICTURE_HEADER_SIZE + x * 4;
+            u32::from_le_bytes([
+                bytes[offset_start],
+                bytes[offset_start + 1],
+                bytes[offset_start + 2],
+                bytes[offset_start + 3],
+            ]) as usize
+        })
+        .collect();
+    if let Some(x) = column_offsets
+        .iter()
+        .position(|offset| *offset >= bytes.len())
+    {
+        return Err(eyre::eyre!(
+            "Column {} starts past the end of the picture",
+            x
+        ));
+    }
+
+    let mut pixels = vec![None; width * height];
+    for (x, column_offset) in column_offsets.into_iter().enumerate() {
+        let mut offset = column_offset;
+
+        // Tall patches count a top delta that isn't below the last post's top from that top
+        let mut top: Option<usize> = None;
+        loop {
+            let Some(&top_delta) = bytes.get(offset) else {
+                return Err(eyre::eyre!("Column {} runs past the end of the picture", x));
+            };
+            if top_delta == POST_END {
+                break;
+            }
+            let top_delta = top_delta as usize;
+            let post_top = match top {
+                Some(last_top) if top_delta <= last_top => last_top + top_delta,
+                _ => top_delta,
+            };
+            top = Some(post_top);
+
+            let Some(&length) = bytes.get(offset + 1) else {
+                return Err(eyre::eyre!("Column {} runs past the end of the picture", x));
+            };
+            // A padding byte sits either side of the post's pixels
+            let start = offset + 3;
+            let Some(post) = bytes.get(start..start + length as usize) else {
+                return Err(eyre::eyre!("Column {} runs past the end of the picture", x));
+            };
+            for (row, index) in post.iter().enumerate() {
+                if post_top + row < height {
+                    pixels[(post_top + row) * width + x] = Some(*index);
+                }
+            }
+            offset = start + length as usize + 1;
+        }
+    }
+
+    Ok(Picture {
+        width,
+        height,
+        pixels,
+    })
+}
+
+impl Picture {
+    // Transparent pixels are left black, like the screen behind the menu logo
+    fn to_image(&self, palette: &[u8]) -> Image {
+        let rgb = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| match pixel {
+                Some(index) => {
+                    let start = *index as usize * 3;
+                    [palette[start], palette[start + 1], palette[start + 2]]
+                }
+                None => [0, 0, 0],
+            })
+            .collect();
+        Image {
+            width: self.width as u32,
+            height: self.height as u32,
+            rgb,
+        }
+    }
+}
+
+// ZDoom based Engines allow PNGs in place of Doom pictures
+fn decode_png(bytes: &[u8]) -> Result<Image, eyre::Report> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0u8; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)?;
+    buffer.truncate(info.buffer_size());
+
+    let rgb = match info.color_type {
+        png::ColorType::Rgb => buffer,
+        png::ColorType::Rgba => buffer
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+            .collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|grey| [*grey; 3]).collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|pixel| [pixel[0]; 3])
+            .collect(),
+        png::ColorType::Indexed => return Err(eyre::eyre!("Unable to expand indexed PNG")),
+    };
+    Ok(Image {
+        width: info.width,
+        height: info.height,
+        rgb,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::title_screen::{decode_picture, Picture};
+
+    #[test]
+    fn test_decode_picture() {
+        // Arrange
+        // A 2x3 picture, column 0 has one post at the top, column 1 has two posts
+        let bytes: Vec<u8> = vec![
+            2, 0, 3, 0, 0, 0, 0, 0, // Header
+            16, 0, 0, 0, 23, 0, 0, 0, // Column offsets
+            0, 2, 0, 10, 11, 0, 0xFF, // Column 0
+            0, 1, 0, 20, 0, 2, 1, 0, 21, 0, 0xFF, // Column 1
+        ];
+
+        // Act
+        let actual = decode_picture(&bytes).unwrap();
+
+        // Assert
+        assert_eq!(
+            actual,
+            Picture {
+                width: 2,
+                height: 3,
+                pixels: vec![Some(10), Some(20), Some(11), None, None, Some(21)],
+            }
+        );
+        assert!(decode_picture(&[1, 0, 1, 0]).is_err());
+    }
+
+    #[test]
+    fn test_decode_picture_malformed() {
+        // Arrange
+        // A header asking for a 65535x65535 picture
+        let too_large: Vec<u8> =
+            [vec![0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0], vec![0; 65535 * 4]].concat();
+        // A 1x1 picture whose column starts past the end of the lump
+        let column_past_end: Vec<u8> = vec![1, 0, 1, 0, 0, 0, 0, 0, 0xFF, 0, 0, 0, 0, 1, 0, 5, 0];
+        // A 1x1 picture whose post is longer than the lump
+        let post_past_end: Vec<u8> = vec![1, 0, 1, 0, 0, 0, 0, 0, 12, 0, 0, 0, 0, 200, 0, 5, 0];
+
+        // Act
+        let actual = [
+            decode_picture(&too_large),
+            decode_picture(&column_past_end),
+            decode_picture(&post_past_end),
+        ];
+
+        // Assert
+        for result in actual {
+            assert!(result.is_err());
+        }
+    }
+}
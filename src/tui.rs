@@ -13,6 +13,7 @@ use crate::constants;
 use crate::data;
 use crate::db;
 use crate::menu_app_settings;
+use crate::menu_demos;
 use crate::menu_editor;
 use crate::menu_main;
 use crate::menu_maps;
@@ -34,6 +35,7 @@ pub enum MenuLevel {
     Maps,
     MapsSearchDoomworld,
     MapsReadme,
+    Demos,
     AppSettings,
     AppSettingsDefaults,
     AppSettingsList,
@@ -68,6 +70,8 @@ pub enum MenuCommand {
     PlaySettings,
     #[strum(serialize = "Maps >>")]
     Maps,
+    #[strum(serialize = "Demos >>")]
+    Demos,
     #[strum(serialize = "App Settings >>")]
     AppSettings,
 
@@ -80,6 +84,20 @@ pub enum MenuCommand {
     PickAndPlayProfileOnName,
     #[strum(serialize = "Pick & Play Profile on Date Last Run")]
     PickAndPlayProfileOnDateLastRun,
+    #[strum(serialize = "Pick & Play Profile at Level")]
+    PickAndPlayProfileAtLevel,
+    #[strum(serialize = "Host Netgame")]
+    HostNetgame,
+    #[strum(serialize = "Join Netgame")]
+    JoinNetgame,
+    #[strum(serialize = "Show Command for Queue")]
+    ShowCommandForQueue,
+    #[strum(serialize = "Show Command for Map")]
+    ShowCommandForMap,
+    #[strum(serialize = "Show Command for Profile")]
+    ShowCommandForProfile,
+    #[strum(serialize = "Show Command for Netgame")]
+    ShowCommandForNetgame,
 
     // Profile Menu
     #[strum(serialize = "New Profile")]
@@ -90,6 +108,10 @@ pub enum MenuCommand {
     ChangeEngineOnProfile,
     #[strum(serialize = "Change Save Game on Profile")]
     ChangeSaveGameOnProfile,
+    #[strum(serialize = "Edit Netgame Preset on Profile")]
+    EditNetgamePreset,
+    #[strum(serialize = "Check Profile")]
+    CheckProfile,
     #[strum(serialize = "Delete Profile")]
     DeleteProfile,
     #[strum(serialize = "Set Default Profile")]
@@ -116,6 +138,10 @@ pub enum MenuCommand {
     MenuMode,
     #[strum(serialize = "Use Doomworld API")]
     UseDoomworldApi,
+    #[strum(serialize = "Wait for Engine to Exit")]
+    WaitForEngine,
+    #[strum(serialize = "Set Hooks")]
+    SetHooks,
     #[strum(serialize = "Set Defaults >>")]
     SetDefaults,
     #[strum(serialize = "Set Default Engine")]
@@ -178,6 +204,8 @@ pub enum MenuCommand {
     #[strum(serialize = "Full Screen")]
     FullScreen,
     Windowed,
+    #[strum(serialize = "Record Demo")]
+    RecordDemo,
     #[strum(serialize = "Additional Arguments")]
     AdditionalArguments,
     #[strum(serialize = "Reset Play Settings")]
@@ -204,6 +232,10 @@ pub enum MenuCommand {
     // Map Menu
     #[strum(serialize = "View on Doomworld")]
     ViewOnDoomworld,
+    #[strum(serialize = "View Title Screen")]
+    ViewTitleScreen,
+    #[strum(serialize = "View Map Preview")]
+    ViewMapPreview,
     #[strum(serialize = "Search & Download on Doomworld >>")]
     SearchAndDownloadOnDoomworld,
     #[strum(serialize = "Search Doomworld by Author")]
@@ -223,6 +255,20 @@ pub enum MenuCommand {
     #[strum(serialize = "Readme from Pick Map")]
     ReadmeFromPickMap,
 
+    // Demo Menu
+    #[strum(serialize = "Play Demo")]
+    PlayDemo,
+    #[strum(serialize = "Time Demo")]
+    TimeDemo,
+    #[strum(serialize = "Show Command for Demo")]
+    ShowCommandForDemo,
+    #[strum(serialize = "Import Demos")]
+    ImportDemos,
+    #[strum(serialize = "Delete Demo")]
+    DeleteDemo,
+    #[strum(serialize = "List Demos")]
+    ListDemos,
+
     // Back and Quit
     #[strum(serialize = "Back <ESC>")]
     Back,
@@ -250,6 +296,7 @@ pub fn menu_prompt(
                 (MenuCommand::Profiles.to_string(), MenuMode::Simple),
                 (MenuCommand::Queues.to_string(), MenuMode::Full),
                 (MenuCommand::Maps.to_string(), MenuMode::Simple),
+                (MenuCommand::Demos.to_string(), MenuMode::Full),
                 (MenuCommand::Editor.to_string(), MenuMode::Full),
                 (MenuCommand::AppSettings.to_string(), MenuMode::Simple),
                 (MenuCommand::Quit.to_string(), MenuMode::Simple),
@@ -274,6 +321,22 @@ pub fn menu_prompt(
                     MenuCommand::PickAndPlayProfileOnDateLastRun.to_string(),
                     MenuMode::Simple,
                 ),
+                (
+                    MenuCommand::PickAndPlayProfileAtLevel.to_string(),
+                    MenuMode::Full,
+                ),
+                (MenuCommand::HostNetgame.to_string(), MenuMode::Full),
+                (MenuCommand::JoinNetgame.to_string(), MenuMode::Full),
+                (MenuCommand::ShowCommandForQueue.to_string(), MenuMode::Full),
+                (MenuCommand::ShowCommandForMap.to_string(), MenuMode::Full),
+                (
+                    MenuCommand::ShowCommandForProfile.to_string(),
+                    MenuMode::Full,
+                ),
+                (
+                    MenuCommand::ShowCommandForNetgame.to_string(),
+                    MenuMode::Full,
+                ),
                 (MenuCommand::Back.to_string(), MenuMode::Simple),
             ];
             (
@@ -296,8 +359,10 @@ pub fn menu_prompt(
                     MenuCommand::ChangeSaveGameOnProfile.to_string(),
                     MenuMode::Simple,
                 ),
+                (MenuCommand::EditNetgamePreset.to_string(), MenuMode::Full),
                 (MenuCommand::SetDefaultProfile.to_string(), MenuMode::Simple),
                 (MenuCommand::ListProfile.to_string(), MenuMode::Simple),
+                (MenuCommand::CheckProfile.to_string(), MenuMode::Simple),
                 (MenuCommand::DeleteProfile.to_string(), MenuMode::Simple),
                 (MenuCommand::Back.to_string(), MenuMode::Simple),
             ];
@@ -429,6 +494,14 @@ pub fn menu_prompt(
                     format!("{} ({})", MenuCommand::Windowed, play_settings.windowed),
                     MenuMode::Simple,
                 ),
+                (
+                    format!(
+                        "{} ({})",
+                        MenuCommand::RecordDemo,
+                        play_settings.record_demo
+                    ),
+                    MenuMode::Full,
+                ),
                 (
                     format!(
                         "{} ({})",
@@ -475,6 +548,8 @@ pub fn menu_prompt(
                     MenuMode::Simple,
                 ),
                 (MenuCommand::ViewReadme.to_string(), MenuMode::Simple),
+                (MenuCommand::ViewTitleScreen.to_string(), MenuMode::Simple),
+                (MenuCommand::ViewMapPreview.to_string(), MenuMode::Simple),
                 (MenuCommand::UpdateMapInfo.to_string(), MenuMode::Simple),
                 (MenuCommand::ListMaps.to_string(), MenuMode::Simple),
                 (MenuCommand::UpdateMaps.to_string(), MenuMode::Simple),
@@ -531,6 +606,22 @@ pub fn menu_prompt(
                 "View local Map Readmes".to_string(),
             )
         }
+        MenuLevel::Demos => {
+            let selections = vec![
+                (MenuCommand::PlayDemo.to_string(), MenuMode::Full),
+                (MenuCommand::TimeDemo.to_string(), MenuMode::Full),
+                (MenuCommand::ShowCommandForDemo.to_string(), MenuMode::Full),
+                (MenuCommand::ImportDemos.to_string(), MenuMode::Full),
+                (MenuCommand::DeleteDemo.to_string(), MenuMode::Full),
+                (MenuCommand::ListDemos.to_string(), MenuMode::Full),
+                (MenuCommand::Back.to_string(), MenuMode::Simple),
+            ];
+            (
+                selections,
+                "Demos".to_string(),
+                "Watch back recorded and imported Demos".to_string(),
+            )
+        }
         MenuLevel::AppSettings => {
             let app_settings = db::get_app_settings()?;
             let selections = vec![
@@ -546,6 +637,15 @@ pub fn menu_prompt(
                     ),
                     MenuMode::Simple,
                 ),
+                (
+                    format!(
+                        "{} ({})",
+                        MenuCommand::WaitForEngine,
+                        app_settings.wait_for_engine,
+                    ),
+                    MenuMode::Simple,
+                ),
+                (MenuCommand::SetHooks.to_string(), MenuMode::Full),
                 (MenuCommand::SetDefaults.to_string(), MenuMode::Simple),
                 (MenuCommand::UpdateStoredData.to_string(), MenuMode::Simple),
                 (MenuCommand::ListStoredData.to_string(), MenuMode::Simple),
@@ -726,8 +826,8 @@ pub fn run_menu_command_with_force(
 ) -> Result<String, eyre::Report> {
     match menu_command {
         // Main Menu
-        MenuCommand::PlayDefaultProfile => menu_main::play_default_profile(),
-        MenuCommand::PlayLastProfile => menu_main::play_last_profile(),
+        MenuCommand::PlayDefaultProfile => menu_main::play_default_profile(data::PlayMode::Launch),
+        MenuCommand::PlayLastProfile => menu_main::play_last_profile(data::PlayMode::Launch),
         MenuCommand::PlayQueueTop => menu_main::play_queue_top(),
         MenuCommand::PickAndPlay => menu(MenuLevel::PickAndPlay),
         MenuCommand::Editor => menu(MenuLevel::MapEditor),
@@ -735,21 +835,41 @@ pub fn run_menu_command_with_force(
         MenuCommand::Queues => menu(MenuLevel::Queues),
         MenuCommand::PlaySettings => menu(MenuLevel::GameSettings),
         MenuCommand::Maps => menu(MenuLevel::Maps),
+        MenuCommand::Demos => menu(MenuLevel::Demos),
         MenuCommand::AppSettings => menu(MenuLevel::AppSettings),
 
         // Pick & Play Menu
-        MenuCommand::PickAndPlayQueue => menu_main::pick_and_play_queue(),
-        MenuCommand::PickAndPlayProfileOnName => menu_main::pick_and_play_profile_on_name(),
+        MenuCommand::PickAndPlayQueue => menu_main::pick_and_play_queue(data::PlayMode::Launch),
+        MenuCommand::PickAndPlayProfileOnName => {
+            menu_main::pick_and_play_profile_on_name(data::PlayMode::Launch)
+        }
         MenuCommand::PickAndPlayProfileOnDateLastRun => {
-            menu_main::pick_and_play_profile_on_date_last_run()
+            menu_main::pick_and_play_profile_on_date_last_run(data::PlayMode::Launch)
+        }
+        MenuCommand::PickAndPlayProfileAtLevel => {
+            menu_main::pick_and_play_profile_at_level(data::PlayMode::Launch)
+        }
+        MenuCommand::PickAndPlayMap => menu_main::pick_and_play_map(data::PlayMode::Launch),
+        MenuCommand::HostNetgame => menu_main::pick_and_play_netgame(true, data::PlayMode::Launch),
+        MenuCommand::JoinNetgame => menu_main::pick_and_play_netgame(false, data::PlayMode::Launch),
+        MenuCommand::ShowCommandForQueue => {
+            menu_main::pick_and_play_queue(data::PlayMode::DryRun(data::DryRunFormat::Shell))
         }
-        MenuCommand::PickAndPlayMap => menu_main::pick_and_play_map(),
+        MenuCommand::ShowCommandForMap => {
+            menu_main::pick_and_play_map(data::PlayMode::DryRun(data::DryRunFormat::Shell))
+        }
+        MenuCommand::ShowCommandForProfile => menu_main::pick_and_play_profile_on_name(
+            data::PlayMode::DryRun(data::DryRunFormat::Shell),
+        ),
+        MenuCommand::ShowCommandForNetgame => menu_main::pick_and_show_netgame_command(),
 
         // Profile Menu
         MenuCommand::NewProfile => menu_profiles::add_profile(None, None),
         MenuCommand::EditProfile => menu_profiles::edit_profile(),
         MenuCommand::ChangeEngineOnProfile => menu_profiles::change_engine_on_profile(),
         MenuCommand::ChangeSaveGameOnProfile => menu_profiles::change_save_game_on_profile(),
+        MenuCommand::EditNetgamePreset => menu_profiles::edit_netgame_preset(),
+        MenuCommand::CheckProfile => menu_profiles::check_profile(),
         MenuCommand::DeleteProfile => menu_profiles::delete_profile(),
         MenuCommand::SetDefaultProfile => menu_profiles::set_default_profile(),
         MenuCommand::ListProfile => menu_profiles::list_profiles(data::ListType::Summary),
@@ -765,13 +885,15 @@ pub fn run_menu_command_with_force(
         // App Settings Menu
         MenuCommand::MenuMode => menu_app_settings::update_menu_mode(),
         MenuCommand::UseDoomworldApi => menu_app_settings::update_use_doomworld_api(),
+        MenuCommand::WaitForEngine => menu_app_settings::update_wait_for_engine(),
+        MenuCommand::SetHooks => menu_app_settings::update_hooks(),
         MenuCommand::SetDefaults => menu(MenuLevel::AppSettingsDefaults),
         MenuCommand::SetDefaultEngine => menu_app_settings::set_default_engine(),
         MenuCommand::SetDefaultIwad => menu_app_settings::set_default_iwad(),
         MenuCommand::ListStoredData => menu(MenuLevel::AppSettingsList),
         MenuCommand::ListEngines => menu_app_settings::list_engines(),
         MenuCommand::ListIwads => menu_app_settings::list_iwads(),
-        MenuCommand::ListMaps => menu_app_settings::list_maps(),
+        MenuCommand::ListMaps => menu_app_settings::list_maps(data::ListType::Summary, None),
         MenuCommand::ListAppSettings => menu_app_settings::list_app_settings(),
         MenuCommand::ListPlaySettings => menu_play_settings::list_play_settings(),
         MenuCommand::Init => menu_app_settings::init(),
@@ -800,6 +922,7 @@ pub fn run_menu_command_with_force(
         MenuCommand::Height => menu_play_settings::update_screen_height(),
         MenuCommand::FullScreen => menu_play_settings::update_full_screen(),
         MenuCommand::Windowed => menu_play_settings::update_windowed(),
+        MenuCommand::RecordDemo => menu_play_settings::update_record_demo(),
         MenuCommand::AdditionalArguments => menu_play_settings::update_additional_arguments(),
         MenuCommand::ResetPlaySettings => menu_play_settings::reset_play_settings(force),
 
@@ -817,6 +940,8 @@ pub fn run_menu_command_with_force(
         MenuCommand::ViewOnDoomworld => menu_maps::view_on_doomworld(),
         MenuCommand::SearchAndDownloadOnDoomworld => menu(MenuLevel::MapsSearchDoomworld),
         MenuCommand::ViewReadme => menu(MenuLevel::MapsReadme),
+        MenuCommand::ViewTitleScreen => menu_maps::view_title_screen(),
+        MenuCommand::ViewMapPreview => menu_maps::view_map_preview(),
 
         // Map Search on Doomworld Menu
         MenuCommand::SearchDoomworldByAuthor => menu_maps::search_doomworld_by_author(),
@@ -829,6 +954,21 @@ pub fn run_menu_command_with_force(
         MenuCommand::ReadmeFromPickProfile => menu_maps::view_from_pick_profile(),
         MenuCommand::ReadmeFromPickMap => menu_maps::view_from_pick_map(),
 
+        // Demo Menu
+        MenuCommand::PlayDemo => {
+            menu_demos::pick_and_play_demo(data::DemoAction::Play, data::PlayMode::Launch)
+        }
+        MenuCommand::TimeDemo => {
+            menu_demos::pick_and_play_demo(data::DemoAction::TimeDemo, data::PlayMode::Launch)
+        }
+        MenuCommand::ShowCommandForDemo => menu_demos::pick_and_play_demo(
+            data::DemoAction::Play,
+            data::PlayMode::DryRun(data::DryRunFormat::Shell),
+        ),
+        MenuCommand::ImportDemos => menu_demos::import_demos(),
+        MenuCommand::DeleteDemo => menu_demos::delete_demo(),
+        MenuCommand::ListDemos => menu_demos::list_demos(),
+
         // Back and Quit
         MenuCommand::Ignore => Ok("".to_string()),
         MenuCommand::Back => Ok("".to_string()),
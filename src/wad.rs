@@ -0,0 +1,305 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    str::FromStr,
+};
+
+use eyre::Context;
+
+use crate::{
+    complevel, data,
+    doom_data::{self, KeyLump, LevelFormat},
+    mapinfo,
+};
+
+// Reads the header and lump directory of a WAD file, to find the levels and the lumps
+// that matter when playing it. The layout is described at https://doomwiki.org/wiki/WAD
+
+const HEADER_SIZE: usize = 12;
+const DIRECTORY_ENTRY_SIZE: usize = 16;
+const LUMP_NAME_SIZE: usize = 8;
+const LUMP_THINGS: &str = "THINGS";
+const LUMP_TEXTMAP: &str = "TEXTMAP";
+const LUMP_BEHAVIOR: &str = "BEHAVIOR";
+const LUMP_ENDMAP: &str = "ENDMAP";
+// The lumps that follow the marker of a binary format level, BEHAVIOR makes it Hexen format
+const BINARY_LEVEL_LUMPS: [&str; 12] = [
+    "THINGS", "LINEDEFS", "SIDEDEFS", "VERTEXES", "SEGS", "SSECTORS", "NODES", "SECTORS", "REJECT",
+    "BLOCKMAP", "BEHAVIOR", "SCRIPTS",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LumpEntry {
+    pub name: String,
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct WadDirectory {
+    pub lumps: Vec<LumpEntry>,
+}
+
+impl WadDirectory {
+    // Levels are found by what follows their marker, so any name works, not just ExMy and MAPxx
+    pub fn get_levels(&self) -> Vec<data::MapLevel> {
+        let mut levels = Vec::new();
+        for (index, marker) in self.lumps.iter().enumerate() {
+            let Some(level_format) = get_level_format(&self.lumps[index + 1..]) else {
+                continue;
+            };
+            levels.push(data::MapLevel {
+                lump_name: marker.name.clone(),
+                level_format,
+                level_name: None,
+            });
+        }
+
+        levels
+    }
+
+    // The lumps that make up a level, from after its marker up to the next level or lump
+    pub fn get_level_lumps(&self, lump_name: &str) -> Option<(LevelFormat, &[LumpEntry])> {
+        let index = self
+            .lumps
+            .iter()
+            .position(|lump| lump.name.eq_ignore_ascii_case(lump_name))?;
+        let level_lumps = &self.lumps[index + 1..];
+        let level_format = get_level_format(level_lumps)?;
+        let count = match level_format {
+            LevelFormat::Udmf => level_lumps
+                .iter()
+                .position(|lump| lump.name == LUMP_ENDMAP)
+                .unwrap_or(level_lumps.len()),
+            _ => level_lumps
+                .iter()
+                .take_while(|lump| BINARY_LEVEL_LUMPS.contains(&lump.name.as_str()))
+                .count(),
+        };
+        Some((level_format, &level_lumps[..count]))
+    }
+
+    pub fn get_key_lumps(&self) -> Vec<KeyLump> {
+        let mut key_lumps: Vec<KeyLump> = Vec::new();
+        for lump in &self.lumps {
+            if let Ok(key_lump) = KeyLump::from_str(&lump.name) {
+                if !key_lumps.contains(&key_lump) {
+                    key_lumps.push(key_lump);
+                }
+            }
+        }
+
+        key_lumps
+    }
+
+    // When a lump appears more than once, Engines use the last one
+    pub fn find_lump(&self, name: &str) -> Option<&LumpEntry> {
+        self.lumps.iter().rev().find(|lump| lump.name == name)
+    }
+}
+
+pub fn read_wad_contents(wad_path: &str) -> Result<data::MapContents, eyre::Report> {
+    let wad_directory = read_wad_directory(wad_path)?;
+    let mut levels = wad_directory.get_levels();
+
+    for key_lump in &mapinfo::LEVEL_NAME_LUMPS {
+        if let Some(lump) = wad_directory.find_lump(&key_lump.to_string()) {
+            let text = String::from_utf8_lossy(&read_lump(wad_path, lump)?).to_string();
+            mapinfo::set_level_names(&mut levels, key_lump, &text);
+        }
+    }
+
+    let required_iwad = match wad_directory.find_lump(&KeyLump::Gameinfo.to_string()) {
+        Some(lump) => {
+            mapinfo::get_gameinfo_iwad(&String::from_utf8_lossy(&read_lump(wad_path, lump)?))
+        }
+        None => None,
+    };
+
+    let mut comp_level_evidence: Vec<data::CompLevelEvidence> = Vec::new();
+    for key_lump in &complevel::COMP_LEVEL_LUMPS {
+        if let Some(lump) = wad_directory.find_lump(&key_lump.to_string()) {
+            let text = String::from_utf8_lossy(&read_lump(wad_path, lump)?).to_string();
+            comp_level_evidence.extend(complevel::get_lump_evidence(key_lump, &text));
+        }
+    }
+
+    Ok(data::MapContents {
+        levels,
+        lumps: wad_directory.get_key_lumps(),
+        required_iwad,
+        comp_level_evidence,
+        ..Default::default()
+    })
+}
+
+// Levels are told apart by the lump that follows the marker
+fn get_level_format(level_lumps: &[LumpEntry]) -> Option<LevelFormat> {
+    match level_lumps.first().map(|lump| lump.name.as_str()) {
+        Some(LUMP_TEXTMAP) => Some(LevelFormat::Udmf),
+        Some(LUMP_THINGS) => {
+            let is_hexen = level_lumps
+                .iter()
+                .take_while(|lump| BINARY_LEVEL_LUMPS.contains(&lump.name.as_str()))
+                .any(|lump| lump.name == LUMP_BEHAVIOR);
+            if is_hexen {
+                Some(LevelFormat::Hexen)
+            } else {
+                Some(LevelFormat::Doom)
+            }
+        }
+        _ => None,
+    }
+}
+
+pub fn read_wad_directory(path: &str) -> Result<WadDirectory, eyre::Report> {
+    let file = File::open(path).wrap_err(format!("Failed to open '{}'", path))?;
+    read_directory(&mut BufReader::new(file))
+        .wrap_err(format!("Failed to read WAD directory from '{}'", path))
+}
+
+pub fn read_lump(path: &str, lump: &LumpEntry) -> Result<Vec<u8>, eyre::Report> {
+    let mut file = File::open(path).wrap_err(format!("Failed to open '{}'", path))?;
+    read_lump_from(&mut file, lump).wrap_err(format!("Failed to read from '{}'", path))
+}
+
+pub fn read_lump_from<R: Read + Seek>(
+    reader: &mut R,
+    lump: &LumpEntry,
+) -> Result<Vec<u8>, eyre::Report> {
+    reader.seek(SeekFrom::Start(lump.offset as u64))?;
+    let mut bytes = vec![0u8; lump.size as usize];
+    reader
+        .read_exact(&mut bytes)
+        .wrap_err(format!("Failed to read lump '{}'", lump.name))?;
+    Ok(bytes)
+}
+
+pub fn read_directory<R: Read + Seek>(reader: &mut R) -> Result<WadDirectory, eyre::Report> {
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut header = [0u8; HEADER_SIZE];
+    reader
+        .read_exact(&mut header)
+        .wrap_err("File is too small to be a WAD")?;
+    let identifier = &header[0..4];
+    if identifier != doom_data::IWAD_IDENTIFIER && identifier != doom_data::PWAD_IDENTIFIER {
+        return Err(eyre::eyre!(
+            "Not a WAD file, missing IWAD or PWAD identifier"
+        ));
+    }
+    let lump_count = read_u32(&header, 4) as u64;
+    let directory_offset = read_u32(&header, 8) as u64;
+    if directory_offset + lump_count * DIRECTORY_ENTRY_SIZE as u64 > file_size {
+        return Err(eyre::eyre!("Lump directory runs past the end of the file"));
+    }
+
+    reader.seek(SeekFrom::Start(directory_offset))?;
+    let mut directory = vec![0u8; lump_count as usize * DIRECTORY_ENTRY_SIZE];
+    reader.read_exact(&mut directory)?;
+    let lumps: Vec<LumpEntry> = directory
+        .chunks_exact(DIRECTORY_ENTRY_SIZE)
+        .map(|entry| LumpEntry {
+            name: read_lump_name(&entry[8..8 + LUMP_NAME_SIZE]),
+            offset: read_u32(entry, 0),
+            size: read_u32(entry, 4),
+        })
+        .collect();
+
+    for lump in &lumps {
+        if lump.offset as u64 + lump.size as u64 > file_size {
+            return Err(eyre::eyre!(
+                "Lump '{}' runs past the end of the file",
+                lump.name
+            ));
+        }
+    }
+
+    Ok(WadDirectory { lumps })
+}
+
+// Names are padded with nulls, and a few tools write them in lower case
+fn read_lump_name(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| (b as char).to_ascii_uppercase())
+        .collect()
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        doom_data::{KeyLump, LevelFormat},
+        wad::{read_wad_contents, read_wad_directory},
+    };
+
+    #[test]
+    fn test_read_wad_directory_levels() {
+        // Act
+        let actual = read_wad_directory("./test-data/levels.wad")
+            .unwrap()
+            .get_levels();
+
+        // Assert
+        let levels: Vec<(&str, LevelFormat)> = actual
+            .iter()
+            .map(|level| (level.lump_name.as_str(), level.level_format.clone()))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                ("E1M1", LevelFormat::Doom),
+                ("MAP01", LevelFormat::Udmf),
+                ("MAP02", LevelFormat::Hexen),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_wad_directory_key_lumps() {
+        // Act
+        let actual = read_wad_directory("./test-data/levels.wad")
+            .unwrap()
+            .get_key_lumps();
+
+        // Assert
+        assert_eq!(
+            actual,
+            vec![KeyLump::Dehacked, KeyLump::Umapinfo, KeyLump::Zscript]
+        );
+    }
+
+    #[test]
+    fn test_read_wad_contents_level_names() {
+        // Act
+        let actual = read_wad_contents("./test-data/levels.wad").unwrap();
+
+        // Assert
+        let level_names: Vec<Option<&str>> = actual
+            .levels
+            .iter()
+            .map(|level| level.level_name.as_deref())
+            .collect();
+        assert_eq!(level_names, vec![None, Some("Entryway"), None]);
+    }
+
+    #[test]
+    fn test_read_wad_directory_not_a_wad() {
+        // Act
+        let actual = read_wad_directory("./test-data/valiant.json");
+
+        // Assert
+        assert!(actual.is_err());
+    }
+}